@@ -0,0 +1,269 @@
+use crate::config::ArchiveConfig;
+use crate::error::Error;
+use crate::rss_generator::escape_html;
+use crate::state_store::{StateStore, StoredItem};
+use chrono::{DateTime, FixedOffset, Utc};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+
+/// Write every stored article as a standalone page under `YYYY/MM/`, with an
+/// index per month and one listing the months. Returns the number of files
+/// that were created or changed.
+pub fn write_archive(config: &ArchiveConfig, state: &StateStore) -> Result<usize, Error> {
+    let mut months: BTreeMap<String, Vec<&StoredItem>> = BTreeMap::new();
+    for stored in state.items() {
+        months
+            .entry(month_dir(stored.item.pub_date))
+            .or_default()
+            .push(stored);
+    }
+
+    let mut written = 0;
+    for (month, articles) in &mut months {
+        articles.sort_by_key(|stored| std::cmp::Reverse(stored.item.pub_date));
+        let dir = config.dir.join(month.as_str());
+
+        for stored in articles.iter() {
+            let slug = article_slug(&stored.item.link);
+            let page = dir.join(format!("{slug}.html"));
+            written += usize::from(write_if_changed(&page, &article_html(stored))?);
+            if config.markdown && !stored.item.markdown.is_empty() {
+                let markdown = dir.join(format!("{slug}.md"));
+                written += usize::from(write_if_changed(&markdown, &article_markdown(stored))?);
+            }
+        }
+        written += usize::from(write_if_changed(
+            &dir.join("index.html"),
+            &month_index_html(month, articles),
+        )?);
+    }
+    written += usize::from(write_if_changed(
+        &config.dir.join("index.html"),
+        &archive_index_html(&months),
+    )?);
+
+    Ok(written)
+}
+
+fn jst(date: DateTime<Utc>) -> DateTime<FixedOffset> {
+    date.with_timezone(&FixedOffset::east_opt(9 * 3600).unwrap())
+}
+
+/// `YYYY/MM` of the publication date in Japan time
+fn month_dir(date: DateTime<Utc>) -> String {
+    jst(date).format("%Y/%m").to_string()
+}
+
+/// Last path segment of the article URL, reduced to file-name safe characters
+fn article_slug(link: &str) -> String {
+    let segment = link
+        .split(['?', '#'])
+        .next()
+        .unwrap_or(link)
+        .trim_end_matches('/')
+        .rsplit('/')
+        .next()
+        .unwrap_or_default();
+    let slug: String = segment
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || c == '-' || c == '_' {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    let slug = slug.trim_matches('-');
+    if slug.is_empty() {
+        "article".to_string()
+    } else {
+        slug.to_string()
+    }
+}
+
+/// Skip unchanged files so repeated runs leave the archive untouched
+fn write_if_changed(path: &Path, content: &str) -> Result<bool, Error> {
+    if fs::read_to_string(path).is_ok_and(|existing| existing == content) {
+        return Ok(false);
+    }
+    if let Some(parent) = path.parent() {
+        fs::create_dir_all(parent).map_err(Error::io(parent))?;
+    }
+    fs::write(path, content).map_err(Error::io(path))?;
+    Ok(true)
+}
+
+fn escape(text: &str) -> String {
+    escape_html(text).replace('"', "&quot;")
+}
+
+fn page(title: &str, nav: &str, body: &str) -> String {
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n\
+         <meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n\
+         <title>{}</title>\n</head>\n<body>\n<nav>{nav}</nav>\n{body}\n</body>\n</html>\n",
+        escape(title)
+    )
+}
+
+fn article_html(stored: &StoredItem) -> String {
+    let item = &stored.item;
+    let mut meta = vec![format!(
+        "公開日: {}",
+        jst(item.pub_date).format("%Y/%m/%d %H:%M")
+    )];
+    if let Some(updated) = item.updated {
+        meta.push(format!("更新日: {}", jst(updated).format("%Y/%m/%d %H:%M")));
+    }
+    if let Some(author) = &item.author {
+        meta.push(format!("著者: {}", escape(author)));
+    }
+    if let Some(category) = &item.category {
+        meta.push(format!("カテゴリ: {}", escape(category)));
+    }
+
+    let body = format!(
+        "<article>\n<h1>{}</h1>\n<p>{}</p>\n<p>元記事: <a href=\"{link}\">{link}</a></p>\n{}\n</article>",
+        escape(&item.title),
+        meta.join(" / "),
+        item.description,
+        link = escape(&item.link),
+    );
+    page(
+        &item.title,
+        "<a href=\"index.html\">この月の記事</a> / <a href=\"../../index.html\">アーカイブ</a>",
+        &body,
+    )
+}
+
+fn article_markdown(stored: &StoredItem) -> String {
+    let item = &stored.item;
+    let mut header = format!(
+        "# {}\n\n- 公開日: {}\n",
+        item.title,
+        jst(item.pub_date).to_rfc3339()
+    );
+    if let Some(updated) = item.updated {
+        header.push_str(&format!("- 更新日: {}\n", jst(updated).to_rfc3339()));
+    }
+    header.push_str(&format!("- 元記事: <{}>\n", item.link));
+    format!("{header}\n{}\n", item.markdown.trim_end())
+}
+
+fn month_index_html(month: &str, articles: &[&StoredItem]) -> String {
+    let entries: String = articles
+        .iter()
+        .map(|stored| {
+            let item = &stored.item;
+            format!(
+                "<li>{} <a href=\"{}.html\">{}</a> (<a href=\"{}\">元記事</a>)</li>\n",
+                jst(item.pub_date).format("%m/%d"),
+                article_slug(&item.link),
+                escape(&item.title),
+                escape(&item.link)
+            )
+        })
+        .collect();
+    let title = format!("{} の記事", month.replace('/', "年") + "月");
+    page(
+        &title,
+        "<a href=\"../../index.html\">アーカイブ</a>",
+        &format!("<h1>{}</h1>\n<ul>\n{entries}</ul>", escape(&title)),
+    )
+}
+
+fn archive_index_html(months: &BTreeMap<String, Vec<&StoredItem>>) -> String {
+    let entries: String = months
+        .iter()
+        .rev()
+        .map(|(month, articles)| {
+            format!(
+                "<li><a href=\"{month}/index.html\">{}月</a> ({} 件)</li>\n",
+                month.replace('/', "年"),
+                articles.len()
+            )
+        })
+        .collect();
+    page(
+        "記事アーカイブ",
+        "",
+        &format!("<h1>記事アーカイブ</h1>\n<ul>\n{entries}</ul>"),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::rss_item::RssItem;
+    use crate::test_support::temp_dir;
+
+    fn item(slug: &str, date: &str, title: &str) -> RssItem {
+        RssItem {
+            title: title.to_string(),
+            link: format!("https://ledge.ai/articles/{slug}"),
+            description: "<p>本文</p>".to_string(),
+            markdown: "本文".to_string(),
+            pub_date: DateTime::parse_from_rfc3339(date)
+                .unwrap()
+                .with_timezone(&Utc),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_article_slug() {
+        assert_eq!(
+            article_slug("https://ledge.ai/articles/openai_gpt-5"),
+            "openai_gpt-5"
+        );
+        assert_eq!(article_slug("https://ledge.ai/articles/a/?utm=1"), "a");
+        assert_eq!(article_slug("https://ledge.ai/articles/日本語"), "article");
+    }
+
+    #[test]
+    fn test_write_archive_by_month() {
+        let dir = temp_dir("archive");
+        let mut state = StateStore::load(&dir.join("state.json")).unwrap();
+        let now = Utc::now();
+        // 2025-06-30T20:00Z is already July in Japan
+        state.record(
+            &mut item("july", "2025-06-30T20:00:00Z", "7月の<記事>"),
+            now,
+            false,
+        );
+        state.record(
+            &mut item("june", "2025-06-10T00:00:00Z", "6月の記事"),
+            now,
+            false,
+        );
+        let config = ArchiveConfig {
+            dir: dir.join("archive"),
+            markdown: true,
+        };
+
+        assert_eq!(write_archive(&config, &state).unwrap(), 7);
+        let archive = &config.dir;
+
+        let page = fs::read_to_string(archive.join("2025/07/july.html")).unwrap();
+        assert!(page.contains("<h1>7月の&lt;記事&gt;</h1>"));
+        assert!(page.contains("<a href=\"https://ledge.ai/articles/july\">"));
+        assert!(page.contains("<p>本文</p>"));
+        let markdown = fs::read_to_string(archive.join("2025/06/june.md")).unwrap();
+        assert!(markdown.starts_with("# 6月の記事\n"));
+        assert!(markdown.ends_with("\n本文\n"));
+
+        let month = fs::read_to_string(archive.join("2025/06/index.html")).unwrap();
+        assert!(month.contains("<a href=\"june.html\">6月の記事</a>"));
+        let index = fs::read_to_string(archive.join("index.html")).unwrap();
+        assert!(
+            index.find("2025/07/index.html").unwrap() < index.find("2025/06/index.html").unwrap()
+        );
+
+        // Nothing changed, nothing rewritten
+        assert_eq!(write_archive(&config, &state).unwrap(), 0);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
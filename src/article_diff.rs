@@ -0,0 +1,254 @@
+use crate::rss_generator::escape_html;
+use crate::rss_item::RssItem;
+use chrono::{DateTime, FixedOffset, Utc};
+use serde::{Deserialize, Serialize};
+
+/// Revisions kept per article; older ones are dropped first
+pub const MAX_REVISIONS: usize = 10;
+
+/// Paragraphs inserted and removed by one revision of an article
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Revision {
+    pub at: DateTime<Utc>,
+    #[serde(default)]
+    pub inserted: Vec<String>,
+    #[serde(default)]
+    pub removed: Vec<String>,
+}
+
+#[derive(Debug, PartialEq)]
+enum DiffOp<'a> {
+    Equal,
+    Insert(&'a str),
+    Delete(&'a str),
+}
+
+/// Blank-line separated blocks of the extracted markdown, whitespace trimmed
+fn paragraphs(markdown: &str) -> Vec<&str> {
+    markdown
+        .split("\n\n")
+        .map(str::trim)
+        .filter(|paragraph| !paragraph.is_empty())
+        .collect()
+}
+
+/// Longest common subsequence of paragraphs, as a list of edit operations
+fn diff_paragraphs<'a>(old: &[&'a str], new: &[&'a str]) -> Vec<DiffOp<'a>> {
+    // lcs[i][j]: length of the LCS of old[i..] and new[j..]
+    let mut lcs = vec![vec![0usize; new.len() + 1]; old.len() + 1];
+    for i in (0..old.len()).rev() {
+        for j in (0..new.len()).rev() {
+            lcs[i][j] = if old[i] == new[j] {
+                lcs[i + 1][j + 1] + 1
+            } else {
+                lcs[i + 1][j].max(lcs[i][j + 1])
+            };
+        }
+    }
+
+    let (mut i, mut j) = (0, 0);
+    let mut ops = Vec::new();
+    while i < old.len() || j < new.len() {
+        if i < old.len() && j < new.len() && old[i] == new[j] {
+            ops.push(DiffOp::Equal);
+            i += 1;
+            j += 1;
+        } else if j < new.len() && (i == old.len() || lcs[i][j + 1] >= lcs[i + 1][j]) {
+            ops.push(DiffOp::Insert(new[j]));
+            j += 1;
+        } else {
+            ops.push(DiffOp::Delete(old[i]));
+            i += 1;
+        }
+    }
+    ops
+}
+
+/// Compare two versions of an article's markdown paragraph by paragraph.
+/// Returns `None` when no paragraph was inserted or removed.
+pub fn diff_markdown(old: &str, new: &str, at: DateTime<Utc>) -> Option<Revision> {
+    let (old, new) = (paragraphs(old), paragraphs(new));
+    let mut revision = Revision {
+        at,
+        inserted: Vec::new(),
+        removed: Vec::new(),
+    };
+    for op in diff_paragraphs(&old, &new) {
+        match op {
+            DiffOp::Equal => {}
+            DiffOp::Insert(text) => revision.inserted.push(text.to_string()),
+            DiffOp::Delete(text) => revision.removed.push(text.to_string()),
+        }
+    }
+
+    if revision.inserted.is_empty() && revision.removed.is_empty() {
+        None
+    } else {
+        Some(revision)
+    }
+}
+
+/// Inserted and removed paragraphs of one revision as HTML
+pub fn revision_html(revision: &Revision) -> String {
+    let block = |label: &str, tag: &str, paragraphs: &[String]| {
+        if paragraphs.is_empty() {
+            return String::new();
+        }
+        let body: String = paragraphs
+            .iter()
+            .map(|text| format!("<p><{tag}>{}</{tag}></p>", escape_html(text)))
+            .collect();
+        format!("<p>{label}:</p><blockquote>{body}</blockquote>")
+    };
+
+    format!(
+        "{}{}",
+        block("追加", "ins", &revision.inserted),
+        block("削除", "del", &revision.removed)
+    )
+}
+
+/// "更新履歴" section listing the revisions, newest first
+pub fn history_html(revisions: &[Revision]) -> String {
+    if revisions.is_empty() {
+        return String::new();
+    }
+    let sections: String = revisions
+        .iter()
+        .rev()
+        .map(|revision| {
+            format!(
+                "<h3>{} 更新</h3>{}",
+                format_jst(revision.at),
+                revision_html(revision)
+            )
+        })
+        .collect();
+    format!("<hr><h2>更新履歴</h2>{sections}")
+}
+
+/// One changes-feed entry per revision of `item`, each with its own id
+pub fn revision_items(item: &RssItem, revisions: &[Revision]) -> Vec<RssItem> {
+    revisions
+        .iter()
+        .map(|revision| RssItem {
+            title: format!("【更新】{}", item.title),
+            link: item.link.clone(),
+            description: revision_html(revision),
+            pub_date: item.pub_date,
+            updated: Some(revision.at),
+            category: item.category.clone(),
+            tags: item.tags.clone(),
+            author: item.author.clone(),
+            resurfaced: true,
+            ..Default::default()
+        })
+        .collect()
+}
+
+fn format_jst(date: DateTime<Utc>) -> String {
+    let jst = FixedOffset::east_opt(9 * 3600).unwrap();
+    date.with_timezone(&jst)
+        .format("%Y/%m/%d %H:%M")
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_diff_paragraphs_uses_longest_common_subsequence() {
+        let old = ["a", "b", "c", "d"];
+        let new = ["a", "c", "x", "d"];
+
+        assert_eq!(
+            diff_paragraphs(&old, &new),
+            vec![
+                DiffOp::Equal,
+                DiffOp::Delete("b"),
+                DiffOp::Equal,
+                DiffOp::Insert("x"),
+                DiffOp::Equal,
+            ]
+        );
+    }
+
+    #[test]
+    fn test_diff_markdown_reports_inserted_and_removed_paragraphs() {
+        let old = "# 見出し\n\n最初の段落。\n\n古い価格は100円。\n\n最後の段落。";
+        let new = "# 見出し\n\n最初の段落。\n\n新しい価格は120円。\n\n最後の段落。\n\n追記：発売日が決定。";
+        let at = utc("2025-07-20T03:00:00Z");
+
+        let revision = diff_markdown(old, new, at).unwrap();
+        assert_eq!(revision.at, at);
+        assert_eq!(
+            revision.inserted,
+            vec!["新しい価格は120円。", "追記：発売日が決定。"]
+        );
+        assert_eq!(revision.removed, vec!["古い価格は100円。"]);
+    }
+
+    #[test]
+    fn test_diff_markdown_ignores_whitespace_only_changes() {
+        let at = utc("2025-07-20T03:00:00Z");
+        assert_eq!(diff_markdown("a\n\nb", "a\n\n\n\n  b  \n", at), None);
+    }
+
+    #[test]
+    fn test_revision_items_get_one_id_per_revision() {
+        let item = RssItem {
+            title: "記事".to_string(),
+            link: "https://ledge.ai/articles/a".to_string(),
+            ..Default::default()
+        };
+        let revisions = vec![
+            Revision {
+                at: utc("2025-07-20T00:00:00Z"),
+                inserted: vec!["追記".to_string()],
+                removed: Vec::new(),
+            },
+            Revision {
+                at: utc("2025-07-21T00:00:00Z"),
+                inserted: Vec::new(),
+                removed: vec!["削除".to_string()],
+            },
+        ];
+
+        let items = revision_items(&item, &revisions);
+        assert_eq!(items.len(), 2);
+        assert_eq!(items[0].title, "【更新】記事");
+        assert_eq!(items[0].sort_date(), utc("2025-07-20T00:00:00Z"));
+        assert_ne!(items[0].guid(), items[1].guid());
+        assert!(items[1].description.contains("<del>削除</del>"));
+    }
+
+    #[test]
+    fn test_history_html_lists_revisions_newest_first() {
+        let revisions = vec![
+            Revision {
+                at: utc("2025-07-20T03:00:00Z"),
+                inserted: vec!["<script>".to_string()],
+                removed: Vec::new(),
+            },
+            Revision {
+                at: utc("2025-07-21T03:00:00Z"),
+                inserted: Vec::new(),
+                removed: vec!["古い段落".to_string()],
+            },
+        ];
+
+        let html = history_html(&revisions);
+        assert!(html.starts_with("<hr><h2>更新履歴</h2><h3>2025/07/21 12:00 更新</h3>"));
+        assert!(html.contains("<p>削除:</p><blockquote><p><del>古い段落</del></p></blockquote>"));
+        assert!(html.contains("<p><ins>&lt;script&gt;</ins></p>"));
+        assert!(html.find("07/21").unwrap() < html.find("07/20").unwrap());
+        assert_eq!(history_html(&[]), "");
+    }
+}
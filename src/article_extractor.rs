@@ -1,3 +1,6 @@
+use crate::embed_rewriter::rewrite_embeds;
+use crate::error::Error;
+use crate::image_processor::process_images;
 use pulldown_cmark::{html, Parser};
 use regex::Regex;
 use scraper::{Html, Selector};
@@ -10,22 +13,26 @@ fn clean_content(content: &str) -> String {
     content.replace(TARGET_BLANK_PATTERN, "")
 }
 
-pub fn extract_article_content(html: &str) -> Result<String, Box<dyn std::error::Error>> {
+/// Article markdown and the name of the script pattern it was found with;
+/// `url` is only used to describe failures
+pub fn extract_article_content(html: &str, url: &str) -> Result<(String, &'static str), Error> {
     // Extract from script tags (Ledge.ai uses Nuxt.js with __NUXT__ object)
-    if let Ok(content) = extract_from_script_tags(html) {
+    if let Some((content, strategy)) = extract_from_script_tags(html) {
         if content.len() > 100 {
             // Final cleanup: remove any remaining {target="_blank"} patterns
             let cleaned_content = clean_content(&content);
-            return Ok(cleaned_content);
+            return Ok((cleaned_content, strategy));
         }
     }
 
-    Err("Article content not found".into())
+    Err(Error::NoContent {
+        url: url.to_string(),
+    })
 }
 
-fn extract_from_script_tags(html: &str) -> Result<String, Box<dyn std::error::Error>> {
+fn extract_from_script_tags(html: &str) -> Option<(String, &'static str)> {
     let document = Html::parse_document(html);
-    let script_selector = Selector::parse("script")?;
+    let script_selector = Selector::parse("script").unwrap();
 
     for script_element in document.select(&script_selector) {
         let script_text = script_element.text().collect::<String>();
@@ -37,28 +44,29 @@ fn extract_from_script_tags(html: &str) -> Result<String, Box<dyn std::error::Er
         {
             // Look for content in various formats
             if let Some(content) = extract_content_from_script(&script_text) {
-                return Ok(content);
+                return Some(content);
             }
         }
     }
 
-    Err("No content found in scripts".into())
+    None
 }
 
-fn extract_content_from_script(script_text: &str) -> Option<String> {
+fn extract_content_from_script(script_text: &str) -> Option<(String, &'static str)> {
     // Try to find various content patterns in Nuxt.js data - more flexible patterns
+    // (strategy name reported on success, pattern)
     let content_patterns = [
         // More flexible patterns for Ledge.ai
-        r#""body":"([^"]{300,}?)""#,     // Body field, at least 300 chars
-        r#""content":"([^"]{300,}?)""#,  // Content field, at least 300 chars
-        r#""markdown":"([^"]{300,}?)""#, // Markdown content, at least 300 chars
-        r#""text":"([^"]{300,}?)""#,     // Text field, at least 300 chars
-        r#"article.*?content.*?:"([^"]{300,}?)""#, // Article with content
-        r#"post.*?body.*?:"([^"]{300,}?)""#, // Post with body
-        r#"contents.*?body.*?:"([^"]{300,}?)""#, // Contents array with body
+        ("body", r#""body":"([^"]{300,}?)""#), // Body field, at least 300 chars
+        ("content", r#""content":"([^"]{300,}?)""#), // Content field, at least 300 chars
+        ("markdown", r#""markdown":"([^"]{300,}?)""#), // Markdown content, at least 300 chars
+        ("text", r#""text":"([^"]{300,}?)""#), // Text field, at least 300 chars
+        ("article_content", r#"article.*?content.*?:"([^"]{300,}?)""#), // Article with content
+        ("post_body", r#"post.*?body.*?:"([^"]{300,}?)""#), // Post with body
+        ("contents_body", r#"contents.*?body.*?:"([^"]{300,}?)""#), // Contents array with body
     ];
 
-    for pattern in &content_patterns {
+    for (strategy, pattern) in &content_patterns {
         if let Ok(regex) = Regex::new(pattern) {
             if let Some(captures) = regex.captures(script_text) {
                 if let Some(content_match) = captures.get(1) {
@@ -78,7 +86,7 @@ fn extract_content_from_script(script_text: &str) -> Option<String> {
 
                     // More lenient filtering - accept any substantial content
                     if cleaned.len() > 300 {
-                        return Some(cleaned);
+                        return Some((cleaned, *strategy));
                     }
                 }
             }
@@ -190,32 +198,21 @@ fn extract_date_from_nuxt_object(html: &str) -> Option<String> {
     None
 }
 
-pub fn extract_article_date(html: &str) -> Option<String> {
+/// Publication date and the name of the strategy that found it
+pub fn extract_article_date(html: &str) -> Option<(String, &'static str)> {
     let document = Html::parse_document(html);
 
     // Try multiple strategies to extract publication date
 
     // 1. Try __NUXT__ object first (most reliable for Ledge.ai)
-    if let Some(date) = extract_date_from_nuxt_object(html) {
-        return Some(date);
-    }
-
     // 2. Try meta tags
-    if let Some(date) = extract_date_from_meta_tags(&document) {
-        return Some(date);
-    }
-
     // 3. Try time elements
-    if let Some(date) = extract_date_from_time_elements(&document) {
-        return Some(date);
-    }
-
     // 4. Try JSON-LD structured data
-    if let Some(date) = extract_date_from_json_ld(html) {
-        return Some(date);
-    }
-
-    None
+    extract_date_from_nuxt_object(html)
+        .map(|date| (date, "nuxt"))
+        .or_else(|| extract_date_from_meta_tags(&document).map(|date| (date, "meta")))
+        .or_else(|| extract_date_from_time_elements(&document).map(|date| (date, "time")))
+        .or_else(|| extract_date_from_json_ld(html).map(|date| (date, "json_ld")))
 }
 
 fn extract_date_from_meta_tags(document: &Html) -> Option<String> {
@@ -300,14 +297,290 @@ fn extract_date_from_json_ld(html: &str) -> Option<String> {
     None
 }
 
+/// Extract when the article was last revised from meta tags, JSON-LD
+/// `dateModified` or `updatedAt` in the __NUXT__ payload
+pub fn extract_article_modified_date(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+
+    let meta_selectors = [
+        "meta[property=\"article:modified_time\"]",
+        "meta[name=\"article:modified_time\"]",
+        "meta[property=\"og:updated_time\"]",
+        "meta[name=\"last-modified\"]",
+    ];
+    for selector in &meta_selectors {
+        if let Some(date) = meta_contents(&document, selector).into_iter().next() {
+            return Some(date);
+        }
+    }
+
+    for json_data in json_ld_objects(html) {
+        if let Some(date) = json_data.get("dateModified").and_then(|v| v.as_str()) {
+            return Some(date.to_string());
+        }
+    }
+
+    let updated_pattern = Regex::new(
+        r#""?(?:updatedAt|updated_at)"?\s*:\s*"(\d{4}-\d{2}-\d{2}T\d{2}:\d{2}:\d{2}[^"]*)""#,
+    )
+    .ok()?;
+    nuxt_scripts(&document).iter().find_map(|script| {
+        updated_pattern
+            .captures(script)
+            .map(|caps| caps[1].to_string())
+    })
+}
+
+/// Extract the article category from meta tags, JSON-LD or the __NUXT__ payload
+pub fn extract_article_category(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+
+    if let Some(section) = meta_contents(&document, "meta[property=\"article:section\"]")
+        .into_iter()
+        .next()
+    {
+        return Some(section);
+    }
+
+    for json_data in json_ld_objects(html) {
+        if let Some(section) = json_data.get("articleSection") {
+            let section = match section {
+                serde_json::Value::Array(values) => values.first().and_then(|v| v.as_str()),
+                value => value.as_str(),
+            };
+            if let Some(section) = section {
+                return Some(section.to_string());
+            }
+        }
+    }
+
+    let category_pattern =
+        Regex::new(r#"(?s)"?category"?\s*:\s*\{.{0,300}?"?name"?\s*:\s*"([^"]+)""#).ok()?;
+    nuxt_scripts(&document).iter().find_map(|script| {
+        category_pattern
+            .captures(script)
+            .map(|caps| caps[1].to_string())
+    })
+}
+
+/// Extract article tags from meta tags, JSON-LD keywords or the __NUXT__ payload
+pub fn extract_article_tags(html: &str) -> Vec<String> {
+    let document = Html::parse_document(html);
+    let mut tags = meta_contents(&document, "meta[property=\"article:tag\"]");
+
+    if tags.is_empty() {
+        for json_data in json_ld_objects(html) {
+            match json_data.get("keywords") {
+                Some(serde_json::Value::String(keywords)) => {
+                    tags.extend(keywords.split(',').map(|k| k.trim().to_string()));
+                }
+                Some(serde_json::Value::Array(keywords)) => {
+                    tags.extend(keywords.iter().filter_map(|k| k.as_str()).map(String::from));
+                }
+                _ => {}
+            }
+        }
+    }
+
+    if tags.is_empty() {
+        for script in nuxt_scripts(&document) {
+            tags.extend(names_in_nuxt_list(&script, "tags"));
+        }
+    }
+
+    let mut unique = Vec::new();
+    for tag in tags {
+        if !tag.is_empty() && !unique.contains(&tag) {
+            unique.push(tag);
+        }
+    }
+    unique
+}
+
+/// Extract the article title from og:title, <title> or the first <h1>,
+/// without the " | Ledge.ai" style site suffix
+pub fn extract_article_title(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+
+    let title = meta_contents(&document, "meta[property=\"og:title\"]")
+        .into_iter()
+        .next()
+        .or_else(|| {
+            let selector = Selector::parse("title, h1").ok()?;
+            document
+                .select(&selector)
+                .map(|element| element.text().collect::<String>().trim().to_string())
+                .next()
+        })?;
+
+    let title = match title.rsplit_once(" | ") {
+        Some((title, site)) if site.contains("Ledge") => title.trim().to_string(),
+        _ => title,
+    };
+    Some(title).filter(|title| !title.is_empty())
+}
+
+/// Extract the article author from meta tags, JSON-LD or the __NUXT__ payload
+pub fn extract_article_author(html: &str) -> Option<String> {
+    let document = Html::parse_document(html);
+
+    if let Some(author) = meta_contents(&document, "meta[name=\"author\"]")
+        .into_iter()
+        .find(|author| !author.is_empty())
+    {
+        return Some(author);
+    }
+
+    for json_data in json_ld_objects(html) {
+        let author = match json_data.get("author") {
+            Some(serde_json::Value::Array(authors)) => authors.first().cloned(),
+            other => other.cloned(),
+        };
+        let name = match &author {
+            Some(serde_json::Value::Object(object)) => object.get("name").and_then(|n| n.as_str()),
+            Some(value) => value.as_str(),
+            None => None,
+        };
+        if let Some(name) = name {
+            return Some(name.to_string());
+        }
+    }
+
+    let author_pattern =
+        Regex::new(r#"(?s)"?(?:author|writer)"?\s*:\s*\{.{0,300}?"?name"?\s*:\s*"([^"]+)""#)
+            .ok()?;
+    nuxt_scripts(&document).iter().find_map(|script| {
+        author_pattern
+            .captures(script)
+            .map(|caps| caps[1].to_string())
+    })
+}
+
+fn meta_contents(document: &Html, selector: &str) -> Vec<String> {
+    Selector::parse(selector)
+        .map(|selector| {
+            document
+                .select(&selector)
+                .filter_map(|element| element.value().attr("content"))
+                .map(|content| content.trim().to_string())
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
+fn json_ld_objects(html: &str) -> Vec<serde_json::Value> {
+    let Ok(json_ld_pattern) = Regex::new(
+        r#"(?s)<script[^>]*type=["']application/ld\+json["'][^>]*>\s*(.*?)\s*</script>"#,
+    ) else {
+        return Vec::new();
+    };
+
+    json_ld_pattern
+        .captures_iter(html)
+        .filter_map(|capture| serde_json::from_str(capture[1].trim()).ok())
+        .collect()
+}
+
+fn nuxt_scripts(document: &Html) -> Vec<String> {
+    let Ok(script_selector) = Selector::parse("script") else {
+        return Vec::new();
+    };
+
+    document
+        .select(&script_selector)
+        .map(|element| element.text().collect::<String>())
+        .filter(|text| text.contains("__NUXT__"))
+        .collect()
+}
+
+/// Collect `name` values inside the list stored under `key` next to the
+/// article's own `title`; lists nested deeper belong to related articles
+/// e.g., `tags:{data:[{attributes:{name:"生成AI"}}]}` -> ["生成AI"]
+fn names_in_nuxt_list(script: &str, key: &str) -> Vec<String> {
+    let Ok(list_pattern) = Regex::new(&format!(r#""?{key}"?\s*:\s*(?:\{{\s*"?data"?\s*:\s*)?\["#))
+    else {
+        return Vec::new();
+    };
+    let Ok(name_pattern) = Regex::new(r#""?name"?\s*:\s*"([^"]+)""#) else {
+        return Vec::new();
+    };
+    let Ok(title_pattern) = Regex::new(r#"[{,]\s*"?title"?\s*:"#) else {
+        return Vec::new();
+    };
+
+    let lists: Vec<(usize, regex::Match)> = list_pattern
+        .find_iter(script)
+        .map(|list| (nesting_depth(&script[..list.start()]), list))
+        .collect();
+    let article_depth = title_pattern
+        .find_iter(script)
+        .map(|title| nesting_depth(&script[..title.start() + 1]))
+        .min()
+        .or_else(|| lists.iter().map(|(depth, _)| *depth).min());
+
+    let mut names = Vec::new();
+    for (_, list_start) in lists
+        .iter()
+        .filter(|(depth, _)| Some(*depth) == article_depth)
+    {
+        // Find the bracket closing this list
+        let mut depth = 1;
+        let mut end = script.len();
+        for (offset, c) in script[list_start.end()..].char_indices() {
+            match c {
+                '[' => depth += 1,
+                ']' => depth -= 1,
+                _ => {}
+            }
+            if depth == 0 {
+                end = list_start.end() + offset;
+                break;
+            }
+        }
+
+        let list = &script[list_start.end()..end];
+        names.extend(
+            name_pattern
+                .captures_iter(list)
+                .map(|caps| caps[1].to_string()),
+        );
+    }
+    names
+}
+
+/// Number of objects and arrays left open at the end of `script`, ignoring
+/// brackets inside string literals
+fn nesting_depth(script: &str) -> usize {
+    let mut depth: usize = 0;
+    let mut quote = None;
+    let mut escaped = false;
+    for c in script.chars() {
+        match quote {
+            Some(_) if escaped => escaped = false,
+            Some(_) if c == '\\' => escaped = true,
+            Some(q) if c == q => quote = None,
+            Some(_) => {}
+            None => match c {
+                '"' | '\'' => quote = Some(c),
+                '{' | '[' => depth += 1,
+                '}' | ']' => depth = depth.saturating_sub(1),
+                _ => {}
+            },
+        }
+    }
+    depth
+}
+
 pub fn markdown_to_html(markdown: &str) -> String {
     // First, preprocess markdown to handle custom extensions and content filtering
     let preprocessed = preprocess_markdown_content(markdown);
+    // Replace tweets, videos and slide embeds that feed readers would strip
+    let preprocessed = rewrite_embeds(&preprocessed);
 
     let parser = Parser::new(&preprocessed);
     let mut html_output = String::new();
     html::push_html(&mut html_output, parser);
-    html_output
+    process_images(&html_output)
 }
 
 /// Integrated markdown preprocessing that handles all content filtering
@@ -340,6 +613,28 @@ pub fn preprocess_markdown_content(markdown: &str) -> String {
     result.into_owned()
 }
 
+/// Reduce markdown to its visible text, keeping line breaks
+/// e.g., "## [見出し](https://example.com)" -> "見出し"
+pub fn strip_markdown(markdown: &str) -> String {
+    use once_cell::sync::Lazy;
+
+    static IMAGE_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"!\[[^\]]*\]\([^)]*\)").unwrap());
+    static LINK_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\[([^\]]*)\]\([^)]*\)").unwrap());
+    static HTML_TAG_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]+>").unwrap());
+    // Block markers at line start (headings, quotes, list bullets) and inline emphasis
+    static BLOCK_MARKER_PATTERN: Lazy<Regex> =
+        Lazy::new(|| Regex::new(r"(?m)^[ \t]*(?:#{1,6}|>|[-*+]|\d+\.)[ \t]+").unwrap());
+    static EMPHASIS_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\*{1,3}|__|~~|`").unwrap());
+
+    let text = IMAGE_PATTERN.replace_all(markdown, "");
+    let text = LINK_PATTERN.replace_all(&text, "$1");
+    let text = HTML_TAG_PATTERN.replace_all(&text, "");
+    let text = BLOCK_MARKER_PATTERN.replace_all(&text, "");
+    let text = EMPHASIS_PATTERN.replace_all(&text, "");
+
+    text.into_owned()
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -362,6 +657,31 @@ mod tests {
         assert!(html.contains(r#"<a href="https://example.com">link</a>"#));
     }
 
+    #[test]
+    fn test_markdown_to_html_rewrites_embeds() {
+        let markdown =
+            "本文\n\n<iframe src=\"https://www.youtube.com/embed/dQw4w9WgXcQ\"></iframe>\n\n続き";
+        let html = markdown_to_html(markdown);
+        assert!(!html.contains("<iframe"));
+        assert!(html.contains(r#"<a href="https://www.youtube.com/watch?v=dQw4w9WgXcQ">"#));
+        assert!(html.contains("<p>続き</p>"));
+    }
+
+    #[test]
+    fn test_markdown_to_html_processes_images() {
+        let markdown = r#"![構成図](https://example.com/a.png "図1 構成")"#;
+        let html = markdown_to_html(markdown);
+        assert!(html.contains(r#"loading="lazy""#));
+        assert!(html.contains("<figcaption>図1 構成</figcaption>"));
+    }
+
+    #[test]
+    fn test_strip_markdown() {
+        let markdown = "## 見出し\n\n[リンク](https://example.com) と ![画像](https://example.com/a.png) **強調**";
+        assert_eq!(strip_markdown(markdown), "見出し\n\nリンク と  強調");
+        assert_eq!(strip_markdown("- GPT-5 の性能"), "GPT-5 の性能");
+    }
+
     #[test]
     fn test_preprocess_markdown_content_removes_target_blank() {
         let markdown = r#"Here is a [link](https://example.com){target="_blank"} and another [link](https://other.com){target="_blank"}."#;
@@ -475,7 +795,7 @@ Final content.
             </html>
         "###;
 
-        let date = extract_article_date(html);
+        let date = extract_article_date(html).map(|(date, _)| date);
         assert!(date.is_some());
         assert_eq!(date.unwrap(), "2025-07-14T07:50:00.000Z");
     }
@@ -495,7 +815,7 @@ Final content.
             </html>
         "###;
 
-        let date = extract_article_date(html);
+        let date = extract_article_date(html).map(|(date, _)| date);
         assert!(date.is_some());
         assert_eq!(date.unwrap(), "2025-07-14T07:50:00.000Z");
     }
@@ -523,7 +843,7 @@ Final content.
             </html>
         "###;
 
-        let date = extract_article_date(html);
+        let date = extract_article_date(html).map(|(date, _)| date);
         assert!(date.is_some());
         assert_eq!(date.unwrap(), "2025-07-14T07:50:00.000Z");
     }
@@ -542,7 +862,7 @@ Final content.
             </html>
         "###;
 
-        let date = extract_article_date(html);
+        let date = extract_article_date(html).map(|(date, _)| date);
         assert!(date.is_none());
     }
 
@@ -561,7 +881,7 @@ Final content.
             </html>
         "###;
 
-        let date = extract_article_date(html);
+        let date = extract_article_date(html).map(|(date, _)| date);
         assert!(date.is_some());
         assert_eq!(date.unwrap(), "2025-07-15T10:30:00.000Z");
     }
@@ -583,7 +903,7 @@ Final content.
             </html>
         "###;
 
-        let date = extract_article_date(html);
+        let date = extract_article_date(html).map(|(date, _)| date);
         assert!(date.is_some());
         assert_eq!(date.unwrap(), "2025-07-13T04:50:00.014Z");
     }
@@ -633,6 +953,146 @@ Final content.
         assert_eq!(date.unwrap(), "2026-01-14T07:50:00.000Z");
     }
 
+    #[test]
+    fn test_extract_article_category_and_tags_from_meta() {
+        let html = r###"
+            <html>
+                <head>
+                    <meta property="article:section" content="エンジニアリング">
+                    <meta property="article:tag" content="生成AI">
+                    <meta property="article:tag" content="LLM">
+                </head>
+                <body></body>
+            </html>
+        "###;
+
+        assert_eq!(
+            extract_article_category(html).as_deref(),
+            Some("エンジニアリング")
+        );
+        assert_eq!(extract_article_tags(html), vec!["生成AI", "LLM"]);
+    }
+
+    #[test]
+    fn test_extract_article_category_and_tags_from_json_ld() {
+        let html = r###"
+            <html>
+                <head>
+                    <script type="application/ld+json">
+                    {"@type": "NewsArticle", "articleSection": "サイエンス", "keywords": "量子, 物理"}
+                    </script>
+                </head>
+                <body></body>
+            </html>
+        "###;
+
+        assert_eq!(
+            extract_article_category(html).as_deref(),
+            Some("サイエンス")
+        );
+        assert_eq!(extract_article_tags(html), vec!["量子", "物理"]);
+    }
+
+    #[test]
+    fn test_extract_article_category_and_tags_from_nuxt() {
+        let html = r###"
+            <html>
+                <body>
+                    <script>
+                        window.__NUXT__={data:{article:{attributes:{title:"Test",category:{data:{id:3,attributes:{name:"ビジネス",slug:"business"}}},tags:{data:[{id:1,attributes:{name:"生成AI"}},{id:2,attributes:{name:"DX"}}]}}}}};
+                    </script>
+                </body>
+            </html>
+        "###;
+
+        assert_eq!(extract_article_category(html).as_deref(), Some("ビジネス"));
+        assert_eq!(extract_article_tags(html), vec!["生成AI", "DX"]);
+    }
+
+    #[test]
+    fn test_extract_article_tags_ignores_related_articles() {
+        let html = r###"
+            <html>
+                <body>
+                    <script>
+                        window.__NUXT__={data:{article:{attributes:{title:"Test",related:{data:[{attributes:{title:"関連記事",tags:{data:[{attributes:{name:"量子"}}]}}}]},tags:{data:[{id:1,attributes:{name:"生成AI"}}]}}}}};
+                    </script>
+                </body>
+            </html>
+        "###;
+
+        assert_eq!(extract_article_tags(html), vec!["生成AI"]);
+    }
+
+    #[test]
+    fn test_extract_article_category_and_tags_missing() {
+        let html = "<html><head><title>Test</title></head><body></body></html>";
+        assert_eq!(extract_article_category(html), None);
+        assert!(extract_article_tags(html).is_empty());
+    }
+
+    #[test]
+    fn test_extract_article_modified_date() {
+        let meta = r#"<html><head><meta property="article:modified_time" content="2025-07-20T10:00:00+09:00"></head></html>"#;
+        assert_eq!(
+            extract_article_modified_date(meta).as_deref(),
+            Some("2025-07-20T10:00:00+09:00")
+        );
+
+        let json_ld = r#"<html><head><script type="application/ld+json">
+            {"@type": "NewsArticle", "datePublished": "2025-07-14T00:00:00Z", "dateModified": "2025-07-21T00:00:00Z"}
+            </script></head></html>"#;
+        assert_eq!(
+            extract_article_modified_date(json_ld).as_deref(),
+            Some("2025-07-21T00:00:00Z")
+        );
+
+        let nuxt = r#"<html><body><script>window.__NUXT__={article:{publishedAt:"2025-07-14T00:00:00.000Z",updatedAt:"2025-07-22T03:00:00.000Z"}}</script></body></html>"#;
+        assert_eq!(
+            extract_article_modified_date(nuxt).as_deref(),
+            Some("2025-07-22T03:00:00.000Z")
+        );
+
+        assert_eq!(extract_article_modified_date("<html></html>"), None);
+    }
+
+    #[test]
+    fn test_extract_article_title() {
+        let og = r#"<html><head><meta property="og:title" content="生成AIの最新動向 | Ledge.ai"><title>ignored</title></head></html>"#;
+        assert_eq!(
+            extract_article_title(og).as_deref(),
+            Some("生成AIの最新動向")
+        );
+
+        let title = "<html><head><title>Plain | Title</title></head></html>";
+        assert_eq!(
+            extract_article_title(title).as_deref(),
+            Some("Plain | Title")
+        );
+
+        let h1 = "<html><body><h1> 見出し </h1></body></html>";
+        assert_eq!(extract_article_title(h1).as_deref(), Some("見出し"));
+
+        assert_eq!(extract_article_title("<html></html>"), None);
+    }
+
+    #[test]
+    fn test_extract_article_author() {
+        let meta =
+            r#"<html><head><meta name="author" content="山田太郎"></head><body></body></html>"#;
+        assert_eq!(extract_article_author(meta).as_deref(), Some("山田太郎"));
+
+        let json_ld = r#"<html><head><script type="application/ld+json">
+            {"@type": "NewsArticle", "author": [{"@type": "Person", "name": "佐藤花子"}]}
+            </script></head><body></body></html>"#;
+        assert_eq!(extract_article_author(json_ld).as_deref(), Some("佐藤花子"));
+
+        let nuxt = r#"<html><body><script>window.__NUXT__={article:{author:{data:{attributes:{name:"Ledge編集部"}}}}};</script></body></html>"#;
+        assert_eq!(extract_article_author(nuxt).as_deref(), Some("Ledge編集部"));
+
+        assert_eq!(extract_article_author("<html></html>"), None);
+    }
+
     #[test]
     fn test_pulldown_cmark_with_special_characters() {
         // Test if pulldown-cmark itself introduces {target="_blank"} patterns
@@ -714,4 +1174,40 @@ Final content.
         assert!(!cleaned.contains("\\u002F"));
         assert!(!cleaned.contains(r#"{target="_blank"}"#));
     }
+
+    #[test]
+    fn test_extract_article_content_reports_pattern() {
+        let body = "本文".repeat(200);
+        let html = format!(
+            r#"<html><body><script>window.__NUXT__={{"markdown":"{body}"}}</script></body></html>"#
+        );
+        let (content, strategy) =
+            extract_article_content(&html, "https://ledge.ai/articles/a").unwrap();
+        assert_eq!(content, body);
+        assert_eq!(strategy, "markdown");
+
+        assert!(matches!(
+            extract_article_content(
+                "<html><body><p>短い</p></body></html>",
+                "https://ledge.ai/articles/b"
+            ),
+            Err(Error::NoContent { url }) if url == "https://ledge.ai/articles/b"
+        ));
+    }
+
+    #[test]
+    fn test_extract_article_date_reports_strategy() {
+        let meta = r#"<html><head><meta property="article:published_time" content="2025-07-14T07:50:00.000Z"></head></html>"#;
+        assert_eq!(
+            extract_article_date(meta),
+            Some(("2025-07-14T07:50:00.000Z".to_string(), "meta"))
+        );
+
+        let time =
+            r#"<html><body><time datetime="2025-07-14T07:50:00.000Z">7/14</time></body></html>"#;
+        assert_eq!(
+            extract_article_date(time).map(|(_, strategy)| strategy),
+            Some("time")
+        );
+    }
 }
@@ -0,0 +1,454 @@
+use crate::article_extractor::markdown_to_html;
+use crate::error::{Error, Stage};
+use crate::html_parser::ArticleInfo;
+use crate::http_client::HttpClient;
+use crate::media_mirror::MediaMirror;
+use crate::reading_stats::compute_reading_stats;
+use crate::rss_item::RssItem;
+use crate::run_report::{ArticleReport, SHORT_CONTENT_CHARS};
+use crate::source::Source;
+use crate::summarizer::summarize;
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::time::Instant;
+use tracing::{debug, info};
+
+/// Modification dates this close to the publication date are the publishing
+/// itself rather than a later edit
+const UPDATE_TOLERANCE_MINUTES: i64 = 60;
+
+/// Fetch an article page (or reuse it from `page_cache`) and turn it into a
+/// feed item, noting how it went in `report`. Returns `None` without converting
+/// the body or mirroring its images when the article is older than `cutoff`.
+pub async fn process_article(
+    client: &HttpClient,
+    source: &dyn Source,
+    article: &ArticleInfo,
+    cutoff: Option<DateTime<Utc>>,
+    media_mirror: &mut Option<MediaMirror>,
+    page_cache: &mut HashMap<String, String>,
+    report: &mut ArticleReport,
+) -> Result<Option<RssItem>, Error> {
+    let started = Instant::now();
+    let article_html = match page_cache.get(&article.url) {
+        Some(html) => {
+            report.cached = true;
+            html.clone()
+        }
+        None => {
+            let (status, html) = client.fetch_page(&article.url, Stage::Article).await?;
+            let fetch_ms = started.elapsed().as_millis() as u64;
+            report.http_status = Some(status);
+            report.fetch_ms = Some(fetch_ms);
+            debug!(
+                stage = "fetch",
+                status,
+                bytes = html.len(),
+                duration_ms = fetch_ms,
+                "Fetched article page"
+            );
+            if !(200..300).contains(&status) {
+                return Err(Error::Http {
+                    stage: Stage::Article,
+                    url: article.url.clone(),
+                    status,
+                });
+            }
+            html
+        }
+    };
+    page_cache.insert(article.url.clone(), article_html.clone());
+
+    let extracted = source.extract_article(&article_html, &article.url)?;
+    let markdown_content = extracted.markdown;
+    let content_chars = markdown_content.chars().count();
+    report.content_strategy = extracted.content_strategy;
+    report.date_strategy = extracted.date_strategy;
+    report.content_chars = Some(content_chars);
+    debug!(
+        stage = "extract",
+        strategy = report.content_strategy.as_deref(),
+        chars = content_chars,
+        "Extracted content"
+    );
+    if content_chars < SHORT_CONTENT_CHARS {
+        report.warn(format!(
+            "Content is only {content_chars} chars, it may be truncated"
+        ));
+    }
+
+    // Use the actual publication date from the article page when available
+    let actual_date = extracted.date;
+    let date_to_use = actual_date.as_deref().unwrap_or(&article.date);
+
+    if actual_date.is_none() {
+        report.warn(format!(
+            "No publication date on the page, using the listing date {date_to_use}"
+        ));
+    }
+
+    let pub_date = match parse_iso_date(date_to_use, &article.url) {
+        Ok(pub_date) => pub_date,
+        // e.g. a sitemap entry without lastmod; it may be far older than the cutoff
+        Err(e) if cutoff.is_some() => {
+            report.warn(format!("{e}, skipping it as the crawl has a cutoff"));
+            return Ok(None);
+        }
+        Err(e) => {
+            report.warn(format!("{e}, using the current time"));
+            Utc::now()
+        }
+    };
+    // Prefer the revision date on the page over the sitemap's lastmod
+    let updated = extracted
+        .modified_date
+        .as_deref()
+        .and_then(|date| DateTime::parse_from_rfc3339(date).ok())
+        .map(|date| date.with_timezone(&Utc))
+        .or(article.lastmod)
+        .filter(|updated| *updated > pub_date + Duration::minutes(UPDATE_TOLERANCE_MINUTES));
+    if let Some(updated) = updated {
+        debug!(stage = "date", updated = %updated.to_rfc3339(), "Updated since publication");
+    }
+
+    // Older articles are dropped by the caller; skip converting and mirroring them
+    if cutoff.is_some_and(|cutoff| pub_date < cutoff) {
+        return Ok(None);
+    }
+
+    let reading_stats = compute_reading_stats(&markdown_content);
+    let summary = summarize(&markdown_content);
+
+    let mut html_content = markdown_to_html(&markdown_content);
+    debug!(
+        stage = "convert",
+        bytes = html_content.len(),
+        "Converted markdown to HTML"
+    );
+
+    if let Some(mirror) = media_mirror.as_mut() {
+        let (mirrored_html, count) = mirror.mirror_html(client, &html_content).await;
+        html_content = mirrored_html;
+        debug!(stage = "mirror", images = count, "Mirrored images");
+    }
+
+    // Sitemap entries carry no title
+    let title = if article.title.is_empty() {
+        extracted.title.unwrap_or_else(|| article.url.clone())
+    } else {
+        article.title.clone()
+    };
+
+    info!(
+        stage = "done",
+        title = %title,
+        chars = content_chars,
+        reading = %reading_stats.label(),
+        summary = summary.is_some(),
+        published = %pub_date.to_rfc3339(),
+        duration_ms = started.elapsed().as_millis() as u64,
+        "Processed article"
+    );
+
+    Ok(Some(RssItem {
+        title,
+        link: article.url.clone(),
+        description: html_content,
+        markdown: markdown_content,
+        pub_date,
+        updated,
+        reading_stats: Some(reading_stats),
+        summary,
+        category: extracted.category.or_else(|| article.category.clone()),
+        tags: extracted.tags,
+        author: extracted.author,
+        resurfaced: false,
+    }))
+}
+
+/// `url` is only used to describe failures
+pub fn parse_iso_date(date_str: &str, url: &str) -> Result<DateTime<Utc>, Error> {
+    // Try ISO 8601 format (from extract_article_date)
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(date_str) {
+        return Ok(parsed.with_timezone(&Utc));
+    }
+
+    // Try fallback format "2025/01/14 [MON]"
+    if let Some(date_part) = date_str.split(' ').next() {
+        if let Ok(parsed) = chrono::NaiveDate::parse_from_str(date_part, "%Y/%m/%d") {
+            let datetime = parsed.and_hms_opt(12, 0, 0).unwrap();
+            return Ok(DateTime::from_naive_utc_and_offset(datetime, Utc));
+        }
+    }
+
+    Err(Error::DateUnparseable {
+        url: url.to_string(),
+        value: date_str.to_string(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::LedgeAi;
+    use crate::test_support::{record_requests, serve, temp_dir};
+
+    #[test]
+    fn test_parse_iso_date() {
+        let date_str = "2025/1/14 [TUE]";
+        let parsed = parse_iso_date(date_str, "https://ledge.ai/articles/a").unwrap();
+        assert_eq!(parsed.format("%Y-%m-%d").to_string(), "2025-01-14");
+    }
+
+    #[test]
+    fn test_parse_iso_date_invalid() {
+        let date_str = "invalid date";
+        let parsed = parse_iso_date(date_str, "https://ledge.ai/articles/a");
+        assert!(matches!(
+            parsed,
+            Err(Error::DateUnparseable { value, .. }) if value == "invalid date"
+        ));
+    }
+
+    #[test]
+    fn test_parse_iso_date_iso8601() {
+        let date_str = "2025-07-14T07:50:00.000Z";
+        let parsed = parse_iso_date(date_str, "https://ledge.ai/articles/a").unwrap();
+        assert_eq!(
+            parsed.format("%Y-%m-%d %H:%M:%S").to_string(),
+            "2025-07-14 07:50:00"
+        );
+    }
+
+    #[tokio::test]
+    async fn test_process_article_uses_page_cache() {
+        let body = "本文".repeat(200);
+        let html = format!(
+            r#"<html><head><meta property="article:published_time" content="2025-07-14T07:50:00.000Z"><meta property="article:section" content="ビジネス"></head>
+            <body><script>window.__NUXT__={{"body":"{body}"}}</script></body></html>"#
+        );
+        let article = ArticleInfo {
+            title: "Cached Article".to_string(),
+            url: "https://ledge.ai/articles/cached".to_string(),
+            date: "2025/01/14 [MON]".to_string(),
+            category: None,
+            lastmod: None,
+        };
+        let mut page_cache = HashMap::from([(article.url.clone(), html)]);
+        let mut report = ArticleReport::new(&article.url, &article.title);
+
+        let item = process_article(
+            &HttpClient::new(),
+            &LedgeAi,
+            &article,
+            None,
+            &mut None,
+            &mut page_cache,
+            &mut report,
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert!(report.cached);
+        assert_eq!(report.http_status, None);
+        assert_eq!(report.content_strategy.as_deref(), Some("body"));
+        assert_eq!(report.date_strategy.as_deref(), Some("meta"));
+        assert_eq!(report.content_chars, Some(400));
+        assert_eq!(
+            report.warnings,
+            vec!["Content is only 400 chars, it may be truncated"]
+        );
+
+        assert_eq!(item.title, "Cached Article");
+        assert!(item.description.contains(&body));
+        assert_eq!(item.pub_date.to_rfc3339(), "2025-07-14T07:50:00+00:00");
+        assert_eq!(item.category.as_deref(), Some("ビジネス"));
+        assert!(item.reading_stats.is_some());
+        assert_eq!(item.updated, None);
+    }
+
+    #[tokio::test]
+    async fn test_process_article_from_sitemap_entry() {
+        let body = "本文".repeat(200);
+        let html = format!(
+            r#"<html><head><meta property="og:title" content="サイトマップの記事 | Ledge.ai"><meta property="article:published_time" content="2025-07-14T07:50:00.000Z"></head>
+            <body><script>window.__NUXT__={{"body":"{body}"}}</script></body></html>"#
+        );
+        let lastmod = DateTime::parse_from_rfc3339("2025-07-20T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let article = ArticleInfo {
+            title: String::new(),
+            url: "https://ledge.ai/articles/sitemap".to_string(),
+            date: lastmod.to_rfc3339(),
+            category: None,
+            lastmod: Some(lastmod),
+        };
+        let mut page_cache = HashMap::from([(article.url.clone(), html)]);
+
+        let item = process_article(
+            &HttpClient::new(),
+            &LedgeAi,
+            &article,
+            None,
+            &mut None,
+            &mut page_cache,
+            &mut ArticleReport::new(&article.url, ""),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+
+        assert_eq!(item.title, "サイトマップの記事");
+        assert_eq!(item.updated, Some(lastmod));
+    }
+
+    #[tokio::test]
+    async fn test_process_article_without_any_date() {
+        let html = format!(
+            r#"<html><body><script>window.__NUXT__={{"body":"{}"}}</script></body></html>"#,
+            "本文".repeat(200)
+        );
+        // A sitemap entry without lastmod
+        let article = ArticleInfo {
+            title: String::new(),
+            url: "https://ledge.ai/articles/undated".to_string(),
+            date: String::new(),
+            category: None,
+            lastmod: None,
+        };
+        let cutoff = DateTime::parse_from_rfc3339("2025-07-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let mut report = ArticleReport::new(&article.url, "");
+        let item = process_article(
+            &HttpClient::new(),
+            &LedgeAi,
+            &article,
+            Some(cutoff),
+            &mut None,
+            &mut HashMap::from([(article.url.clone(), html.clone())]),
+            &mut report,
+        )
+        .await
+        .unwrap();
+        assert!(item.is_none());
+        assert!(report
+            .warnings
+            .last()
+            .unwrap()
+            .ends_with("skipping it as the crawl has a cutoff"));
+
+        let item = process_article(
+            &HttpClient::new(),
+            &LedgeAi,
+            &article,
+            None,
+            &mut None,
+            &mut HashMap::from([(article.url.clone(), html)]),
+            &mut ArticleReport::new(&article.url, ""),
+        )
+        .await
+        .unwrap()
+        .unwrap();
+        assert!(item.pub_date > cutoff);
+    }
+
+    #[tokio::test]
+    async fn test_process_article_reports_fetch() {
+        let body = "本文".repeat(300);
+        let base = serve(vec![(
+            "/articles/a",
+            format!(r#"<html><body><script>window.__NUXT__={{"markdown":"{body}"}}</script></body></html>"#),
+        )])
+        .await;
+        let article = |path: &str| ArticleInfo {
+            title: "記事".to_string(),
+            url: format!("{base}{path}"),
+            date: "2025/01/14 [MON]".to_string(),
+            category: None,
+            lastmod: None,
+        };
+
+        let found = article("/articles/a");
+        let mut report = ArticleReport::new(&found.url, &found.title);
+        process_article(
+            &HttpClient::new(),
+            &LedgeAi,
+            &found,
+            None,
+            &mut None,
+            &mut HashMap::new(),
+            &mut report,
+        )
+        .await
+        .unwrap();
+        assert_eq!(report.http_status, Some(200));
+        assert!(!report.cached);
+        assert!(report.fetch_ms.is_some());
+        assert_eq!(report.content_strategy.as_deref(), Some("markdown"));
+        assert_eq!(report.date_strategy, None);
+        assert_eq!(
+            report.warnings,
+            vec!["No publication date on the page, using the listing date 2025/01/14 [MON]"]
+        );
+
+        let missing = article("/articles/missing");
+        let mut report = ArticleReport::new(&missing.url, &missing.title);
+        let result = process_article(
+            &HttpClient::new(),
+            &LedgeAi,
+            &missing,
+            None,
+            &mut None,
+            &mut HashMap::new(),
+            &mut report,
+        )
+        .await;
+        assert!(matches!(result, Err(Error::Http { status: 404, .. })));
+        assert_eq!(report.http_status, Some(404));
+        assert!(report.warnings.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_process_article_skips_old_articles_before_mirroring() {
+        let (image_base, requests) = record_requests("200 OK").await;
+        let html = format!(
+            r#"<html><head><meta property="article:published_time" content="2025-06-01T00:00:00Z"></head>
+            <body><script>window.__NUXT__={{"body":"{}![図]({image_base}/a.png)"}}</script></body></html>"#,
+            "本文".repeat(200)
+        );
+        let article = ArticleInfo {
+            title: "古い記事".to_string(),
+            url: "https://ledge.ai/articles/old".to_string(),
+            date: "2025-06-01T00:00:00Z".to_string(),
+            category: None,
+            lastmod: None,
+        };
+        let cutoff = DateTime::parse_from_rfc3339("2025-07-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut mirror = Some(MediaMirror::new(
+            temp_dir("skip-old-media"),
+            "https://example.com/feed",
+            1024,
+        ));
+
+        let item = process_article(
+            &HttpClient::new(),
+            &LedgeAi,
+            &article,
+            Some(cutoff),
+            &mut mirror,
+            &mut HashMap::from([(article.url.clone(), html)]),
+            &mut ArticleReport::new(&article.url, &article.title),
+        )
+        .await
+        .unwrap();
+
+        assert!(item.is_none());
+        assert!(requests.lock().unwrap().is_empty());
+    }
+}
@@ -0,0 +1,199 @@
+use crate::error::Error;
+use crate::rss_item::RssItem;
+use crate::source::{ChannelInfo, FeedLinks};
+use atom_syndication::{
+    CategoryBuilder, ContentBuilder, EntryBuilder, FeedBuilder, FixedDateTime, LinkBuilder,
+    PersonBuilder, Text,
+};
+use chrono::{DateTime, Utc};
+
+/// Generate an Atom 1.0 feed. Entries carry both `published` and `updated`,
+/// so readers can tell revised articles from new ones.
+pub fn generate_atom(
+    channel: &ChannelInfo,
+    links: FeedLinks,
+    items: Vec<RssItem>,
+) -> Result<String, Error> {
+    let feed_updated = items
+        .iter()
+        .map(|item| item.updated.unwrap_or(item.pub_date))
+        .max()
+        // An empty feed must render the same every run, or it would look changed
+        .unwrap_or(DateTime::<Utc>::UNIX_EPOCH);
+
+    let feed_url = links.self_url;
+    let mut feed_links = vec![LinkBuilder::default()
+        .href(channel.link.clone())
+        .rel("alternate")
+        .build()];
+    if let Some(feed_url) = feed_url {
+        feed_links.push(
+            LinkBuilder::default()
+                .href(feed_url)
+                .rel("self")
+                .mime_type(Some("application/atom+xml".to_string()))
+                .build(),
+        );
+    }
+    if let Some(hub) = links.hub {
+        feed_links.push(LinkBuilder::default().href(hub).rel("hub").build());
+    }
+
+    let entries = items
+        .into_iter()
+        .map(|item| {
+            let categories = item
+                .category
+                .iter()
+                .chain(item.tags.iter())
+                .map(|name| CategoryBuilder::default().term(name.clone()).build())
+                .collect::<Vec<_>>();
+            let authors = item
+                .author
+                .iter()
+                .map(|author| PersonBuilder::default().name(author.clone()).build())
+                .collect::<Vec<_>>();
+
+            EntryBuilder::default()
+                .id(item.guid())
+                .title(Text::plain(item.title))
+                .link(
+                    LinkBuilder::default()
+                        .href(item.link)
+                        .rel("alternate")
+                        .build(),
+                )
+                .published(Some(FixedDateTime::from(item.pub_date)))
+                .updated(FixedDateTime::from(item.updated.unwrap_or(item.pub_date)))
+                .summary(item.summary.map(Text::plain))
+                .content(Some(
+                    ContentBuilder::default()
+                        .value(Some(item.description))
+                        .content_type(Some("html".to_string()))
+                        .build(),
+                ))
+                .categories(categories)
+                .authors(authors)
+                .build()
+        })
+        .collect::<Vec<_>>();
+
+    let feed = FeedBuilder::default()
+        .id(feed_url.unwrap_or(&channel.link))
+        .title(Text::plain(channel.title.clone()))
+        .subtitle(Some(Text::plain(channel.description.clone())))
+        .updated(FixedDateTime::from(feed_updated))
+        .authors(vec![PersonBuilder::default()
+            .name(channel.title.clone())
+            .uri(Some(channel.link.clone()))
+            .build()])
+        .links(feed_links)
+        .lang(Some(channel.language.clone()))
+        .entries(entries)
+        .build();
+
+    let buffer = feed
+        .write_to(Vec::new())
+        .map_err(Error::feed_write("atom"))?;
+    String::from_utf8(buffer).map_err(Error::feed_write("atom"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::{LedgeAi, Source};
+    use atom_syndication::Feed;
+    use chrono::Duration;
+
+    #[test]
+    fn test_generate_atom_includes_published_and_updated() {
+        let date = DateTime::parse_from_rfc3339("2025-01-14T10:00:00+09:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let items = vec![
+            RssItem {
+                title: "Revised".to_string(),
+                link: "https://ledge.ai/articles/revised".to_string(),
+                description: "<p>Content</p>".to_string(),
+                pub_date: date,
+                updated: Some(date + Duration::days(3)),
+                summary: Some("要約です。".to_string()),
+                category: Some("ビジネス".to_string()),
+                author: Some("Ledge編集部".to_string()),
+                ..Default::default()
+            },
+            RssItem {
+                title: "Plain".to_string(),
+                link: "https://ledge.ai/articles/plain".to_string(),
+                pub_date: date,
+                ..Default::default()
+            },
+        ];
+
+        let links = FeedLinks {
+            self_url: Some("https://example.com/atom.xml"),
+            hub: Some("https://hub.example.com/"),
+        };
+        let xml = generate_atom(&LedgeAi.channel(), links, items).unwrap();
+        let feed: Feed = xml.parse().unwrap();
+
+        assert_eq!(feed.title().as_str(), "Ledge.ai 新着記事");
+        assert_eq!(feed.id(), "https://example.com/atom.xml");
+        let hub = feed
+            .links()
+            .iter()
+            .find(|link| link.rel() == "hub")
+            .unwrap();
+        assert_eq!(hub.href(), "https://hub.example.com/");
+        assert_eq!(feed.updated().to_rfc3339(), "2025-01-17T01:00:00+00:00");
+
+        let revised = &feed.entries()[0];
+        assert_eq!(revised.id(), "https://ledge.ai/articles/revised");
+        assert_eq!(
+            revised.published().unwrap().to_rfc3339(),
+            "2025-01-14T01:00:00+00:00"
+        );
+        assert_eq!(revised.updated().to_rfc3339(), "2025-01-17T01:00:00+00:00");
+        assert_eq!(revised.summary().unwrap().as_str(), "要約です。");
+        assert_eq!(revised.authors()[0].name(), "Ledge編集部");
+        assert_eq!(revised.categories()[0].term(), "ビジネス");
+        assert_eq!(revised.content().unwrap().value(), Some("<p>Content</p>"));
+
+        let plain = &feed.entries()[1];
+        assert_eq!(plain.updated(), plain.published().unwrap());
+    }
+
+    #[test]
+    fn test_generate_atom_uses_new_id_for_resurfaced_items() {
+        let date = DateTime::parse_from_rfc3339("2025-01-14T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let items = vec![RssItem {
+            link: "https://ledge.ai/articles/revised".to_string(),
+            pub_date: date,
+            updated: Some(date + Duration::days(1)),
+            resurfaced: true,
+            ..Default::default()
+        }];
+
+        let feed: Feed = generate_atom(&LedgeAi.channel(), FeedLinks::default(), items)
+            .unwrap()
+            .parse()
+            .unwrap();
+        assert_eq!(feed.id(), "https://ledge.ai/");
+        assert_eq!(
+            feed.entries()[0].id(),
+            "https://ledge.ai/articles/revised#updated-1736899200"
+        );
+    }
+
+    #[test]
+    fn test_generate_atom_empty_feed_is_stable() {
+        let render =
+            || generate_atom(&LedgeAi.channel(), FeedLinks::default(), Vec::new()).unwrap();
+        let first = render();
+        assert_eq!(first, render());
+        let feed: Feed = first.parse().unwrap();
+        assert_eq!(feed.updated().timestamp(), 0);
+    }
+}
@@ -0,0 +1,294 @@
+use crate::article_pipeline::process_article;
+use crate::cli::BackfillOptions;
+use crate::config::Config;
+use crate::error::{Error, Stage};
+use crate::http_client::HttpClient;
+use crate::media_mirror::MediaMirror;
+use crate::run_report::ArticleReport;
+use crate::state_store::StateStore;
+use chrono::{NaiveDate, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::fs;
+use std::path::Path;
+use std::time::Duration;
+use tokio::time::Instant;
+use tracing::{info, info_span, warn, Instrument};
+
+/// Where each listing's backward walk stopped, so an interrupted backfill can resume
+#[derive(Debug, Default, PartialEq, Serialize, Deserialize)]
+struct Checkpoint {
+    until: Option<NaiveDate>,
+    /// Listing URL -> next page to read, `None` once the listing is done
+    listings: BTreeMap<String, Option<String>>,
+}
+
+impl Checkpoint {
+    fn load(path: &Path, until: NaiveDate) -> Result<Self, Error> {
+        let checkpoint: Checkpoint = match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| Error::Corrupt {
+                path: path.to_path_buf(),
+                reason: e.to_string(),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Checkpoint::default(),
+            Err(e) => return Err(Error::io(path)(e)),
+        };
+
+        if checkpoint.until.is_some_and(|previous| previous != until) {
+            warn!(
+                checkpoint = %path.display(),
+                "Ignoring checkpoint written for a different --until date"
+            );
+            return Ok(Checkpoint {
+                until: Some(until),
+                ..Default::default()
+            });
+        }
+        Ok(Checkpoint {
+            until: Some(until),
+            ..checkpoint
+        })
+    }
+
+    fn save(&self, path: &Path) -> Result<(), Error> {
+        let json = serde_json::to_string_pretty(self).map_err(|e| Error::io(path)(e.into()))?;
+        fs::write(path, json).map_err(Error::io(path))
+    }
+}
+
+/// Keeps at least `delay` between two requests
+struct RateLimiter {
+    delay: Duration,
+    last: Option<Instant>,
+}
+
+impl RateLimiter {
+    fn new(delay: Duration) -> Self {
+        Self { delay, last: None }
+    }
+
+    async fn wait(&mut self) {
+        if let Some(last) = self.last {
+            let elapsed = last.elapsed();
+            if elapsed < self.delay {
+                tokio::time::sleep(self.delay - elapsed).await;
+            }
+        }
+        self.last = Some(Instant::now());
+    }
+}
+
+/// Walk every configured listing backward until `options.until`, storing the
+/// articles as backfilled. Returns the number of newly imported articles.
+pub async fn run_backfill(
+    client: &HttpClient,
+    config: &Config,
+    options: &BackfillOptions,
+    state: &mut StateStore,
+    media_mirror: &mut Option<MediaMirror>,
+) -> Result<usize, Error> {
+    let mut checkpoint = Checkpoint::load(&options.checkpoint_path, options.until)?;
+    let until = options.until.and_hms_opt(0, 0, 0).unwrap().and_utc();
+    let mut limiter = RateLimiter::new(options.delay);
+    let mut imported = 0;
+
+    for feed in &config.feeds {
+        let source = feed.build_source()?;
+
+        for listing_url in feed.listing_urls(source.as_ref()) {
+            let mut next_page = checkpoint
+                .listings
+                .get(&listing_url)
+                .cloned()
+                .unwrap_or_else(|| Some(listing_url.clone()));
+            let mut visited = HashSet::new();
+
+            while let Some(page_url) = next_page {
+                if !visited.insert(page_url.clone()) {
+                    break;
+                }
+
+                limiter.wait().await;
+                info!(page = %page_url, "Backfilling listing page");
+                let html = client.fetch_url(&page_url, Stage::Listing).await?;
+                let articles = match source.parse_listing(&html, &page_url) {
+                    Ok(articles) => articles,
+                    // Paged past the oldest article
+                    Err(Error::NoListingData { .. }) => Vec::new(),
+                    Err(e) => return Err(e),
+                };
+
+                // Listings may pin an old article among new ones, so only a page
+                // with nothing newer than `until` ends the walk
+                let (mut older, mut newer) = (0, 0);
+                for article in &articles {
+                    if let Some(stored) = state.get(&article.url) {
+                        if stored.item.pub_date < until {
+                            older += 1;
+                        } else {
+                            newer += 1;
+                        }
+                        continue;
+                    }
+
+                    limiter.wait().await;
+                    let span = info_span!("article", url = %article.url);
+                    let mut page_cache = HashMap::new();
+                    match process_article(
+                        client,
+                        source.as_ref(),
+                        article,
+                        Some(until),
+                        media_mirror,
+                        &mut page_cache,
+                        &mut ArticleReport::new(&article.url, &article.title),
+                    )
+                    .instrument(span.clone())
+                    .await
+                    {
+                        Ok(Some(mut item)) => {
+                            state.record(&mut item, Utc::now(), true);
+                            imported += 1;
+                            newer += 1;
+                        }
+                        Ok(None) => older += 1,
+                        Err(e) => span.in_scope(|| warn!(error = %e, "Skipping article")),
+                    }
+                }
+                state.save()?;
+
+                let reached_until = older > 0 && newer == 0;
+                next_page = if reached_until || articles.is_empty() {
+                    None
+                } else {
+                    source.listing_links(&html, &page_url).next_page
+                };
+                checkpoint
+                    .listings
+                    .insert(listing_url.clone(), next_page.clone());
+                checkpoint.save(&options.checkpoint_path)?;
+            }
+        }
+    }
+
+    // Everything reached the target date; a later backfill starts from scratch
+    if checkpoint.listings.values().all(Option::is_none) {
+        let _ = fs::remove_file(&options.checkpoint_path);
+    }
+
+    Ok(imported)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::{serve, temp_dir};
+
+    fn article_page(date: &str) -> String {
+        let body = "本文".repeat(200);
+        format!(
+            r#"<html><head><meta property="article:published_time" content="{date}"></head>
+            <body><script>window.__NUXT__={{"body":"{body}"}}</script></body></html>"#
+        )
+    }
+
+    #[test]
+    fn test_checkpoint_resets_for_other_until_date() {
+        let dir = temp_dir("checkpoint-reset");
+        let path = dir.join("checkpoint.json");
+        let until = NaiveDate::from_ymd_opt(2025, 1, 1).unwrap();
+
+        let mut checkpoint = Checkpoint::load(&path, until).unwrap();
+        checkpoint.listings.insert(
+            "https://ledge.ai/".to_string(),
+            Some("https://ledge.ai/?page=3".to_string()),
+        );
+        checkpoint.save(&path).unwrap();
+
+        assert_eq!(Checkpoint::load(&path, until).unwrap(), checkpoint);
+        let other = NaiveDate::from_ymd_opt(2024, 1, 1).unwrap();
+        assert!(Checkpoint::load(&path, other).unwrap().listings.is_empty());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_rate_limiter_spaces_requests() {
+        let mut limiter = RateLimiter::new(Duration::from_millis(50));
+        let start = Instant::now();
+        limiter.wait().await;
+        limiter.wait().await;
+        limiter.wait().await;
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+
+    #[tokio::test]
+    async fn test_backfill_walks_back_to_until_date() {
+        let base = serve(vec![
+            (
+                "/",
+                r#"<a href="{base}/articles/new">New Article</a>
+                   <a href="{base}/articles/pinned">Pinned Old Article</a>
+                   <a rel="next" href="/?page=2">Next</a>"#
+                    .to_string(),
+            ),
+            (
+                "/?page=2",
+                r#"<a href="{base}/articles/mid">Middle Article</a>
+                   <a href="{base}/articles/old">Old Article</a>
+                   <a rel="next" href="/?page=3">Next</a>"#
+                    .to_string(),
+            ),
+            (
+                "/?page=3",
+                r#"<a href="{base}/articles/older">Older Article</a>
+                   <a rel="next" href="/?page=4">Next</a>"#
+                    .to_string(),
+            ),
+            // Must not be fetched
+            (
+                "/?page=4",
+                r#"<a href="{base}/articles/unreached">Unreached</a>"#.to_string(),
+            ),
+            ("/articles/new", article_page("2025-07-14T00:00:00Z")),
+            ("/articles/pinned", article_page("2024-01-01T00:00:00Z")),
+            ("/articles/mid", article_page("2025-06-01T00:00:00Z")),
+            ("/articles/old", article_page("2024-12-01T00:00:00Z")),
+            ("/articles/older", article_page("2024-11-01T00:00:00Z")),
+            ("/articles/unreached", article_page("2025-07-01T00:00:00Z")),
+        ])
+        .await;
+
+        let dir = temp_dir("backfill-run");
+        let config = Config::parse(&format!(
+            r#"{{"feeds": [{{"source": "ledge_ai", "listing_urls": ["{base}/"], "outputs": [{{"path": "rss.xml", "format": "rss"}}]}}]}}"#
+        ))
+        .unwrap();
+        let options = BackfillOptions {
+            until: NaiveDate::from_ymd_opt(2025, 1, 1).unwrap(),
+            checkpoint_path: dir.join("checkpoint.json"),
+            delay: Duration::ZERO,
+        };
+        let mut state = StateStore::load(&dir.join("state.json")).unwrap();
+
+        let imported = run_backfill(&HttpClient::new(), &config, &options, &mut state, &mut None)
+            .await
+            .unwrap();
+
+        assert_eq!(imported, 2);
+        assert_eq!(state.len(), 2);
+        assert!(
+            state
+                .get(&format!("{base}/articles/new"))
+                .unwrap()
+                .backfilled
+        );
+        assert!(state.get(&format!("{base}/articles/old")).is_none());
+        assert!(state.get(&format!("{base}/articles/mid")).is_some());
+        assert!(state.get(&format!("{base}/articles/unreached")).is_none());
+        assert!(!options.checkpoint_path.exists());
+        assert_eq!(StateStore::load(&dir.join("state.json")).unwrap().len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
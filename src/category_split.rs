@@ -0,0 +1,230 @@
+use crate::config::{FilterConfig, OutputConfig, SplitBy, SplitConfig};
+use crate::rss_item::RssItem;
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+/// Items of one category (or tag) and the outputs they are written to
+pub struct CategoryFeed {
+    pub name: String,
+    pub slug: String,
+    pub items: Vec<RssItem>,
+    pub outputs: Vec<OutputConfig>,
+}
+
+/// Group items by category or tag, one feed per group, in order of first appearance
+pub fn split_items(items: &[RssItem], split: &SplitConfig) -> Vec<CategoryFeed> {
+    let mut feeds: Vec<CategoryFeed> = Vec::new();
+
+    for item in items {
+        let names: Vec<&String> = match split.by {
+            SplitBy::Category => item.category.iter().collect(),
+            SplitBy::Tag => item.tags.iter().collect(),
+        };
+
+        for name in names {
+            if !split.only.is_empty() && !split.only.contains(name) {
+                continue;
+            }
+
+            match feeds.iter_mut().find(|feed| &feed.name == name) {
+                Some(feed) => feed.items.push(item.clone()),
+                None => {
+                    let slug = split
+                        .slugs
+                        .get(name)
+                        .cloned()
+                        .unwrap_or_else(|| category_slug(name));
+                    let slug = unique_slug(name, slug, &feeds);
+                    let outputs = split_outputs(split, &slug);
+                    feeds.push(CategoryFeed {
+                        name: name.clone(),
+                        slug,
+                        items: vec![item.clone()],
+                        outputs,
+                    });
+                }
+            }
+        }
+    }
+
+    feeds
+}
+
+/// Keep each feed's files apart: a slug that is empty or already taken by
+/// another name gets a suffix derived from the name
+fn unique_slug(name: &str, slug: String, feeds: &[CategoryFeed]) -> String {
+    let taken = |slug: &str| feeds.iter().any(|feed| feed.slug == slug);
+    if !slug.is_empty() && !taken(&slug) {
+        return slug;
+    }
+
+    let hash: String = Sha256::digest(name.as_bytes())[..4]
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect();
+    let unique = if slug.is_empty() {
+        hash
+    } else {
+        format!("{slug}-{hash}")
+    };
+    warn!(name, slug = %slug, unique = %unique, "Slug is empty or already used");
+    unique
+}
+
+fn split_outputs(split: &SplitConfig, slug: &str) -> Vec<OutputConfig> {
+    split
+        .formats
+        .iter()
+        .map(|format| {
+            let file_name = format!("{slug}.{}", format.extension());
+            OutputConfig {
+                path: split.output_dir.join(&file_name),
+                format: *format,
+                url: split
+                    .base_url
+                    .as_ref()
+                    .map(|base| format!("{}/{file_name}", base.trim_end_matches('/'))),
+                filters: FilterConfig::default(),
+            }
+        })
+        .collect()
+}
+
+/// File-name friendly slug for a category, e.g. "ビジネス" -> "business"
+pub fn category_slug(name: &str) -> String {
+    let known = match name {
+        "ビジネス" => Some("business"),
+        "エンジニアリング" => Some("engineering"),
+        "テクノロジー" => Some("technology"),
+        "サイエンス" => Some("science"),
+        "社会" => Some("society"),
+        "政治" => Some("politics"),
+        "経済" => Some("economy"),
+        "カルチャー" => Some("culture"),
+        "スポーツ" => Some("sports"),
+        "エンタメ" => Some("entertainment"),
+        "ライフ" => Some("life"),
+        "その他" => Some("other"),
+        _ => None,
+    };
+    if let Some(slug) = known {
+        return slug.to_string();
+    }
+
+    let slug: String = name
+        .trim()
+        .chars()
+        .map(|c| {
+            if c.is_alphanumeric() {
+                c.to_lowercase().next().unwrap_or(c)
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    slug.trim_matches('-').to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::FeedFormat;
+    use std::collections::BTreeMap;
+    use std::path::PathBuf;
+
+    fn item(title: &str, category: Option<&str>, tags: &[&str]) -> RssItem {
+        RssItem {
+            title: title.to_string(),
+            category: category.map(String::from),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Default::default()
+        }
+    }
+
+    fn split_config(by: SplitBy) -> SplitConfig {
+        SplitConfig {
+            by,
+            output_dir: PathBuf::from("feeds"),
+            formats: vec![FeedFormat::Rss, FeedFormat::Json],
+            only: Vec::new(),
+            slugs: BTreeMap::new(),
+            base_url: Some("https://example.com/feeds/".to_string()),
+        }
+    }
+
+    #[test]
+    fn test_split_items_by_category() {
+        let items = vec![
+            item("A", Some("ビジネス"), &[]),
+            item("B", Some("サイエンス"), &[]),
+            item("C", Some("ビジネス"), &[]),
+            item("D", None, &[]),
+        ];
+
+        let feeds = split_items(&items, &split_config(SplitBy::Category));
+        assert_eq!(feeds.len(), 2);
+        assert_eq!(feeds[0].slug, "business");
+        assert_eq!(
+            feeds[0]
+                .items
+                .iter()
+                .map(|i| i.title.as_str())
+                .collect::<Vec<_>>(),
+            vec!["A", "C"]
+        );
+        assert_eq!(
+            feeds[0].outputs[0].path,
+            PathBuf::from("feeds/business.xml")
+        );
+        assert_eq!(
+            feeds[0].outputs[1].url.as_deref(),
+            Some("https://example.com/feeds/business.json")
+        );
+        assert_eq!(feeds[1].slug, "science");
+    }
+
+    #[test]
+    fn test_split_items_by_tag_with_only_and_custom_slug() {
+        let items = vec![
+            item("A", None, &["生成AI", "DX"]),
+            item("B", None, &["生成AI"]),
+        ];
+        let mut split = split_config(SplitBy::Tag);
+        split.only = vec!["生成AI".to_string()];
+        split
+            .slugs
+            .insert("生成AI".to_string(), "genai".to_string());
+
+        let feeds = split_items(&items, &split);
+        assert_eq!(feeds.len(), 1);
+        assert_eq!(feeds[0].slug, "genai");
+        assert_eq!(feeds[0].items.len(), 2);
+    }
+
+    #[test]
+    fn test_split_items_keeps_slugs_unique() {
+        let items = vec![
+            item("A", None, &["Machine Learning"]),
+            item("B", None, &["machine-learning"]),
+            item("C", None, &["!!!"]),
+        ];
+
+        let feeds = split_items(&items, &split_config(SplitBy::Tag));
+        let slugs: Vec<&str> = feeds.iter().map(|feed| feed.slug.as_str()).collect();
+        assert_eq!(slugs[0], "machine-learning");
+        assert!(slugs[1].starts_with("machine-learning-"));
+        assert_eq!(slugs[1].len(), "machine-learning-".len() + 8);
+        assert_eq!(slugs[2].len(), 8);
+        assert_eq!(
+            feeds[2].outputs[0].path,
+            PathBuf::from(format!("feeds/{}.xml", slugs[2]))
+        );
+    }
+
+    #[test]
+    fn test_category_slug() {
+        assert_eq!(category_slug("エンジニアリング"), "engineering");
+        assert_eq!(category_slug("Machine Learning"), "machine-learning");
+        assert_eq!(category_slug("生成AI"), "生成ai");
+    }
+}
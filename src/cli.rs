@@ -0,0 +1,467 @@
+use crate::error::Error;
+use crate::media_mirror::DEFAULT_MAX_MEDIA_BYTES;
+use chrono::NaiveDate;
+use std::net::SocketAddr;
+use std::path::PathBuf;
+use std::time::Duration;
+
+pub const USAGE: &str = "Usage: ledge-ai-feed [backfill|serve|daemon|digest] [OPTIONS]
+
+Commands:
+  (default)                 Generate the configured feeds
+  backfill                  Import older articles into the state store without announcing them
+  serve                     Regenerate the feeds periodically and serve them over HTTP
+  daemon                    Keep running and generate each feed on its schedule
+  digest                    Email the articles stored since the last digest (requires --state)
+
+Options:
+  --config <FILE>           JSON file describing the feeds to generate (default: the Ledge.ai feed)
+  --state <FILE>            JSON file remembering every article seen (required by backfill)
+  --media-dir <DIR>         Mirror article images into <DIR>/media/
+  --media-base-url <URL>    Public URL under which <DIR> is served (required with --media-dir)
+  --media-max-bytes <N>     Skip images larger than N bytes (default: 5242880)
+  --notify-dry-run          Log webhook payloads instead of sending them
+  --report <FILE>           Write per-article extraction diagnostics of every run as JSON
+  --metrics-file <FILE>     Write Prometheus metrics after every run (node-exporter textfile)
+  --log-format <FORMAT>     text or json, one event per line (default: text; levels via RUST_LOG)
+  -h, --help                Print this help
+
+Backfill options:
+  --until <YYYY-MM-DD>      Walk listing pages back to this publication date (required)
+  --checkpoint <FILE>       Progress file used to resume (default: backfill-checkpoint.json)
+  --delay-ms <N>            Pause between requests in milliseconds (default: 2000)
+
+Serve and daemon options:
+  --listen <ADDR>           Address serving the feeds and /metrics (serve default: 127.0.0.1:8080,
+                            daemon: no server unless given)
+  --interval-minutes <N>    Minutes between two runs of feeds without a schedule (default: 60)";
+
+const DEFAULT_CHECKPOINT_PATH: &str = "backfill-checkpoint.json";
+const DEFAULT_BACKFILL_DELAY: Duration = Duration::from_secs(2);
+const DEFAULT_LISTEN_ADDR: &str = "127.0.0.1:8080";
+const DEFAULT_INTERVAL: Duration = Duration::from_secs(60 * 60);
+
+#[derive(Debug, Default, PartialEq)]
+pub struct Options {
+    pub command: Command,
+    pub config_path: Option<PathBuf>,
+    pub state_path: Option<PathBuf>,
+    pub report_path: Option<PathBuf>,
+    pub metrics_path: Option<PathBuf>,
+    pub media: Option<MediaOptions>,
+    pub notify_dry_run: bool,
+    pub log_format: LogFormat,
+    pub help: bool,
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq)]
+pub enum LogFormat {
+    #[default]
+    Text,
+    Json,
+}
+
+#[derive(Debug, Default, PartialEq)]
+pub enum Command {
+    #[default]
+    Generate,
+    Backfill(BackfillOptions),
+    Serve(ServeOptions),
+    Daemon(DaemonOptions),
+    Digest,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct BackfillOptions {
+    pub until: NaiveDate,
+    pub checkpoint_path: PathBuf,
+    /// Minimum pause between two requests
+    pub delay: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct ServeOptions {
+    pub listen: SocketAddr,
+    /// Time between two generator runs
+    pub interval: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DaemonOptions {
+    /// Serve the feeds and `/metrics` while running
+    pub listen: Option<SocketAddr>,
+    /// Time between two runs of feeds without their own schedule
+    pub interval: Duration,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct MediaOptions {
+    pub output_dir: PathBuf,
+    pub public_base_url: String,
+    pub max_bytes: usize,
+}
+
+/// Parse command line arguments, excluding the program name
+pub fn parse_args<I>(args: I) -> Result<Options, Error>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut options = Options::default();
+    let mut media_dir = None;
+    let mut media_base_url = None;
+    let mut media_max_bytes = DEFAULT_MAX_MEDIA_BYTES;
+    let mut until = None;
+    let mut checkpoint_path = None;
+    let mut delay = None;
+    let mut listen = None;
+    let mut interval = None;
+
+    let mut args = args.into_iter().peekable();
+    let command = match args.peek().map(String::as_str) {
+        Some(name @ ("backfill" | "serve" | "daemon" | "digest")) => {
+            let name = name.to_string();
+            args.next();
+            Some(name)
+        }
+        _ => None,
+    };
+
+    while let Some(arg) = args.next() {
+        match arg.as_str() {
+            "--config" => options.config_path = Some(PathBuf::from(value_for(&arg, args.next())?)),
+            "--state" => options.state_path = Some(PathBuf::from(value_for(&arg, args.next())?)),
+            "--report" => options.report_path = Some(PathBuf::from(value_for(&arg, args.next())?)),
+            "--metrics-file" => {
+                options.metrics_path = Some(PathBuf::from(value_for(&arg, args.next())?))
+            }
+            "--until" => {
+                let value = value_for(&arg, args.next())?;
+                until = Some(NaiveDate::parse_from_str(&value, "%Y-%m-%d").map_err(|_| {
+                    Error::usage(format!(
+                        "--until expects a date like 2024-01-31, got {value}"
+                    ))
+                })?);
+            }
+            "--checkpoint" => checkpoint_path = Some(PathBuf::from(value_for(&arg, args.next())?)),
+            "--delay-ms" => {
+                let millis: u64 = value_for(&arg, args.next())?
+                    .parse()
+                    .map_err(|_| Error::usage("--delay-ms expects a number of milliseconds"))?;
+                delay = Some(Duration::from_millis(millis));
+            }
+            "--listen" => {
+                let value = value_for(&arg, args.next())?;
+                listen = Some(value.parse::<SocketAddr>().map_err(|_| {
+                    Error::usage(format!(
+                        "--listen expects an address like 127.0.0.1:8080, got {value}"
+                    ))
+                })?);
+            }
+            "--interval-minutes" => {
+                let minutes: u64 = value_for(&arg, args.next())?
+                    .parse()
+                    .ok()
+                    .filter(|minutes| *minutes > 0)
+                    .ok_or_else(|| {
+                        Error::usage("--interval-minutes expects a positive number of minutes")
+                    })?;
+                interval = Some(Duration::from_secs(minutes * 60));
+            }
+            "--media-dir" => media_dir = Some(PathBuf::from(value_for(&arg, args.next())?)),
+            "--media-base-url" => media_base_url = Some(value_for(&arg, args.next())?),
+            "--media-max-bytes" => {
+                media_max_bytes = value_for(&arg, args.next())?
+                    .parse()
+                    .map_err(|_| Error::usage("--media-max-bytes expects a number of bytes"))?
+            }
+            "--notify-dry-run" => options.notify_dry_run = true,
+            "--log-format" => {
+                options.log_format = match value_for(&arg, args.next())?.as_str() {
+                    "text" => LogFormat::Text,
+                    "json" => LogFormat::Json,
+                    other => {
+                        return Err(Error::usage(format!(
+                            "--log-format expects text or json, got {other}"
+                        )))
+                    }
+                }
+            }
+            "-h" | "--help" => options.help = true,
+            _ => return Err(Error::usage(format!("Unknown argument: {arg}"))),
+        }
+    }
+
+    match (media_dir, media_base_url) {
+        (Some(output_dir), Some(public_base_url)) => {
+            options.media = Some(MediaOptions {
+                output_dir,
+                public_base_url,
+                max_bytes: media_max_bytes,
+            })
+        }
+        (Some(_), None) => return Err(Error::usage("--media-dir requires --media-base-url")),
+        (None, Some(_)) => return Err(Error::usage("--media-base-url requires --media-dir")),
+        (None, None) => {}
+    }
+
+    if command.as_deref() == Some("backfill") {
+        let until = until.ok_or_else(|| Error::usage("backfill requires --until <YYYY-MM-DD>"))?;
+        if options.state_path.is_none() {
+            return Err(Error::usage("backfill requires --state <FILE>"));
+        }
+        options.command = Command::Backfill(BackfillOptions {
+            until,
+            checkpoint_path: checkpoint_path
+                .unwrap_or_else(|| PathBuf::from(DEFAULT_CHECKPOINT_PATH)),
+            delay: delay.unwrap_or(DEFAULT_BACKFILL_DELAY),
+        });
+    } else if until.is_some() || checkpoint_path.is_some() || delay.is_some() {
+        return Err(Error::usage(
+            "--until, --checkpoint and --delay-ms are only valid with backfill",
+        ));
+    }
+
+    match command.as_deref() {
+        Some("digest") => {
+            if options.state_path.is_none() {
+                return Err(Error::usage("digest requires --state <FILE>"));
+            }
+            options.command = Command::Digest;
+        }
+        Some("serve") => {
+            options.command = Command::Serve(ServeOptions {
+                listen: listen.unwrap_or_else(|| DEFAULT_LISTEN_ADDR.parse().unwrap()),
+                interval: interval.unwrap_or(DEFAULT_INTERVAL),
+            });
+        }
+        Some("daemon") => {
+            options.command = Command::Daemon(DaemonOptions {
+                listen,
+                interval: interval.unwrap_or(DEFAULT_INTERVAL),
+            });
+        }
+        _ => {}
+    }
+    if (listen.is_some() || interval.is_some())
+        && !matches!(options.command, Command::Serve(_) | Command::Daemon(_))
+    {
+        return Err(Error::usage(
+            "--listen and --interval-minutes are only valid with serve or daemon",
+        ));
+    }
+    if options.metrics_path.is_some()
+        && matches!(options.command, Command::Backfill(_) | Command::Digest)
+    {
+        return Err(Error::usage(
+            "--metrics-file is not valid with backfill or digest",
+        ));
+    }
+
+    Ok(options)
+}
+
+fn value_for(flag: &str, value: Option<String>) -> Result<String, Error> {
+    value.ok_or_else(|| Error::usage(format!("{flag} requires a value")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(list: &[&str]) -> Vec<String> {
+        list.iter().map(|s| s.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args_defaults() {
+        let options = parse_args(args(&[])).unwrap();
+        assert_eq!(options, Options::default());
+    }
+
+    #[test]
+    fn test_parse_args_media_mirroring() {
+        let options = parse_args(args(&[
+            "--media-dir",
+            "public",
+            "--media-base-url",
+            "https://example.com/feed",
+            "--media-max-bytes",
+            "1024",
+        ]))
+        .unwrap();
+
+        assert_eq!(
+            options.media,
+            Some(MediaOptions {
+                output_dir: PathBuf::from("public"),
+                public_base_url: "https://example.com/feed".to_string(),
+                max_bytes: 1024,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_args_notify_dry_run() {
+        let options = parse_args(args(&["--state", "state.json", "--notify-dry-run"])).unwrap();
+        assert!(options.notify_dry_run);
+    }
+
+    #[test]
+    fn test_parse_args_report() {
+        let options = parse_args(args(&["--report", "reports/run.json"])).unwrap();
+        assert_eq!(options.report_path, Some(PathBuf::from("reports/run.json")));
+    }
+
+    #[test]
+    fn test_parse_args_log_format() {
+        let options = parse_args(args(&["--log-format", "json"])).unwrap();
+        assert_eq!(options.log_format, LogFormat::Json);
+
+        assert!(parse_args(args(&["--log-format", "xml"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_config() {
+        let options = parse_args(args(&["--config", "feeds.json"])).unwrap();
+        assert_eq!(options.config_path, Some(PathBuf::from("feeds.json")));
+    }
+
+    #[test]
+    fn test_parse_args_backfill() {
+        let options = parse_args(args(&[
+            "backfill",
+            "--state",
+            "state.json",
+            "--until",
+            "2024-01-31",
+            "--delay-ms",
+            "500",
+        ]))
+        .unwrap();
+
+        assert_eq!(options.state_path, Some(PathBuf::from("state.json")));
+        assert_eq!(
+            options.command,
+            Command::Backfill(BackfillOptions {
+                until: NaiveDate::from_ymd_opt(2024, 1, 31).unwrap(),
+                checkpoint_path: PathBuf::from("backfill-checkpoint.json"),
+                delay: Duration::from_millis(500),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_args_backfill_requires_until_and_state() {
+        assert!(parse_args(args(&["backfill", "--state", "state.json"])).is_err());
+        assert!(parse_args(args(&["backfill", "--until", "2024-01-31"])).is_err());
+        assert!(parse_args(args(&[
+            "backfill", "--state", "s.json", "--until", "Jan 31"
+        ]))
+        .is_err());
+        assert!(parse_args(args(&["--until", "2024-01-31"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_serve() {
+        let options = parse_args(args(&["serve"])).unwrap();
+        assert_eq!(
+            options.command,
+            Command::Serve(ServeOptions {
+                listen: "127.0.0.1:8080".parse().unwrap(),
+                interval: Duration::from_secs(3600),
+            })
+        );
+
+        let options = parse_args(args(&[
+            "serve",
+            "--listen",
+            "0.0.0.0:9000",
+            "--interval-minutes",
+            "15",
+        ]))
+        .unwrap();
+        assert_eq!(
+            options.command,
+            Command::Serve(ServeOptions {
+                listen: "0.0.0.0:9000".parse().unwrap(),
+                interval: Duration::from_secs(900),
+            })
+        );
+
+        assert!(parse_args(args(&["serve", "--interval-minutes", "0"])).is_err());
+        assert!(parse_args(args(&["serve", "--listen", "localhost"])).is_err());
+        assert!(parse_args(args(&["--listen", "127.0.0.1:8080"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_daemon() {
+        let options = parse_args(args(&[
+            "daemon",
+            "--state",
+            "state.json",
+            "--interval-minutes",
+            "30",
+        ]))
+        .unwrap();
+        assert_eq!(
+            options.command,
+            Command::Daemon(DaemonOptions {
+                listen: None,
+                interval: Duration::from_secs(1800),
+            })
+        );
+
+        let options = parse_args(args(&["daemon", "--listen", "127.0.0.1:9100"])).unwrap();
+        assert_eq!(
+            options.command,
+            Command::Daemon(DaemonOptions {
+                listen: Some("127.0.0.1:9100".parse().unwrap()),
+                interval: Duration::from_secs(3600),
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_args_metrics_file() {
+        let options = parse_args(args(&["--metrics-file", "textfile/ledge_ai_feed.prom"])).unwrap();
+        assert_eq!(
+            options.metrics_path,
+            Some(PathBuf::from("textfile/ledge_ai_feed.prom"))
+        );
+
+        assert!(parse_args(args(&[
+            "digest",
+            "--state",
+            "s.json",
+            "--metrics-file",
+            "m.prom"
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_args_digest() {
+        let options = parse_args(args(&["digest", "--state", "state.json"])).unwrap();
+        assert_eq!(options.command, Command::Digest);
+
+        assert!(parse_args(args(&["digest"])).is_err());
+        assert!(parse_args(args(&[
+            "digest",
+            "--state",
+            "s.json",
+            "--interval-minutes",
+            "5"
+        ]))
+        .is_err());
+    }
+
+    #[test]
+    fn test_parse_args_media_dir_requires_base_url() {
+        assert!(parse_args(args(&["--media-dir", "public"])).is_err());
+    }
+
+    #[test]
+    fn test_parse_args_unknown_argument() {
+        assert!(parse_args(args(&["--bogus"])).is_err());
+    }
+}
@@ -0,0 +1,858 @@
+use crate::error::Error;
+use crate::filter::ItemFilter;
+use crate::scheduler::Schedule;
+use crate::source::{ChannelInfo, LedgeAi, Source};
+use chrono::{DateTime, Utc};
+use lettre::message::Mailbox;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// All feeds generated in one run, usually loaded from a JSON file
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct Config {
+    pub feeds: Vec<FeedConfig>,
+    /// Static copy of every stored article (requires --state)
+    #[serde(default)]
+    pub archive: Option<ArchiveConfig>,
+    /// Announce changed feeds to a WebSub hub
+    #[serde(default)]
+    pub websub: Option<WebSubConfig>,
+    /// Email sent by the `digest` command (requires --state)
+    #[serde(default)]
+    pub digest: Option<DigestConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WebSubConfig {
+    /// Hub URL advertised in the feeds and pinged when they change
+    pub hub: String,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ArchiveConfig {
+    /// Root directory; articles go to `YYYY/MM/<slug>.html`
+    pub dir: PathBuf,
+    /// Also write the extracted markdown next to each page
+    #[serde(default = "default_archive_markdown")]
+    pub markdown: bool,
+}
+
+fn default_archive_markdown() -> bool {
+    true
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DigestConfig {
+    pub from: String,
+    pub to: Vec<String>,
+    #[serde(default = "default_digest_subject")]
+    pub subject: String,
+    /// Newest articles included in one email
+    #[serde(default = "default_digest_max_items")]
+    pub max_items: usize,
+    pub transport: DigestTransport,
+}
+
+/// How the digest email leaves the machine
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum DigestTransport {
+    Smtp {
+        host: String,
+        /// Defaults to 587 with STARTTLS, 465 with TLS and 25 without
+        port: Option<u16>,
+        #[serde(default)]
+        security: SmtpSecurity,
+        username: Option<String>,
+        /// Environment variable holding the password, kept out of the config
+        password_env: Option<String>,
+    },
+    /// Pipe the message to a sendmail-compatible command
+    Sendmail {
+        #[serde(default = "default_sendmail_command")]
+        command: String,
+    },
+    /// Write `.eml` files instead of sending, for testing
+    File { dir: PathBuf },
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SmtpSecurity {
+    #[default]
+    Starttls,
+    Tls,
+    None,
+}
+
+fn default_digest_subject() -> String {
+    "Ledge.ai ダイジェスト".to_string()
+}
+
+fn default_digest_max_items() -> usize {
+    50
+}
+
+fn default_sendmail_command() -> String {
+    "sendmail".to_string()
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FeedConfig {
+    /// Source identifier, e.g. "ledge_ai"
+    pub source: String,
+    /// Listing pages to crawl instead of the source defaults
+    #[serde(default)]
+    pub listing_urls: Vec<String>,
+    /// How articles are found: listing pages, sitemaps or both
+    #[serde(default)]
+    pub discovery: Discovery,
+    /// Sitemaps to read instead of the source defaults
+    #[serde(default)]
+    pub sitemap_urls: Vec<String>,
+    /// Keep only the newest N items
+    pub max_items: Option<usize>,
+    #[serde(default)]
+    pub crawl: CrawlConfig,
+    #[serde(default)]
+    pub channel: ChannelOverrides,
+    #[serde(default)]
+    pub filters: FilterConfig,
+    pub outputs: Vec<OutputConfig>,
+    /// Additionally write one feed per category or tag from the same crawl
+    pub split: Option<SplitConfig>,
+    /// Announce significantly revised articles again (requires --state)
+    pub resurface: Option<ResurfaceConfig>,
+    /// Show what changed in revised articles (requires --state)
+    pub diff: Option<DiffConfig>,
+    /// When the feed runs in daemon mode (default: every --interval-minutes)
+    pub schedule: Option<ScheduleConfig>,
+    /// Chat webhooks told about new articles (requires --state)
+    #[serde(default)]
+    pub webhooks: Vec<WebhookConfig>,
+    /// Checks the crawl must pass before any output is replaced
+    pub health: Option<HealthConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Payload shape understood by the chat service
+    pub preset: Option<WebhookPreset>,
+    /// Custom JSON payload; `{title}`, `{link}`, `{summary}`, `{image}`,
+    /// `{category}`, `{tags}`, `{author}` and `{published}` are filled in
+    pub template: Option<serde_json::Value>,
+    /// Further attempts after a failed delivery
+    #[serde(default = "default_webhook_retries")]
+    pub retries: u32,
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum WebhookPreset {
+    Slack,
+    Discord,
+}
+
+fn default_webhook_retries() -> u32 {
+    3
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ScheduleConfig {
+    pub interval_minutes: Option<u64>,
+    /// Five-field cron expression in local time, e.g. "*/15 * * * *"
+    pub cron: Option<String>,
+    /// Delay each run by a random number of seconds up to this value
+    #[serde(default)]
+    pub jitter_seconds: u64,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct DiffConfig {
+    /// Append a "更新履歴" section to revised articles
+    #[serde(default)]
+    pub history: bool,
+    /// Separate feeds with one entry per revision
+    #[serde(default)]
+    pub outputs: Vec<OutputConfig>,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ResurfaceConfig {
+    /// Share of the body text that must change, from 0.0 to 1.0
+    #[serde(default = "default_min_change_ratio")]
+    pub min_change_ratio: f64,
+    /// Prepended to the title of re-surfaced items
+    #[serde(default = "default_resurface_title_prefix")]
+    pub title_prefix: String,
+}
+
+/// Extraction guardrails; a failing feed keeps its previous outputs
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct HealthConfig {
+    #[serde(default = "default_health_min_items")]
+    pub min_items: usize,
+    /// Average body length in characters across all items
+    #[serde(default = "default_health_min_average_chars")]
+    pub min_average_chars: usize,
+    /// Share of articles without a publication date on the page, from 0.0 to 1.0
+    #[serde(default = "default_health_max_fallback_date_ratio")]
+    pub max_fallback_date_ratio: f64,
+    /// Body endings that mean the extractor cut an article short
+    #[serde(default = "default_health_suspicious_endings")]
+    pub suspicious_endings: Vec<String>,
+}
+
+impl Default for HealthConfig {
+    fn default() -> Self {
+        Self {
+            min_items: default_health_min_items(),
+            min_average_chars: default_health_min_average_chars(),
+            max_fallback_date_ratio: default_health_max_fallback_date_ratio(),
+            suspicious_endings: default_health_suspicious_endings(),
+        }
+    }
+}
+
+fn default_health_min_items() -> usize {
+    1
+}
+
+fn default_health_min_average_chars() -> usize {
+    500
+}
+
+fn default_health_max_fallback_date_ratio() -> f64 {
+    0.5
+}
+
+fn default_health_suspicious_endings() -> Vec<String> {
+    vec!["{target=".to_string(), "\\".to_string()]
+}
+
+fn default_min_change_ratio() -> f64 {
+    0.2
+}
+
+fn default_resurface_title_prefix() -> String {
+    "【更新】".to_string()
+}
+
+/// How far to follow links from the listing pages
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct CrawlConfig {
+    /// Pagination pages read per listing, the listing itself included
+    #[serde(default = "default_max_pages")]
+    pub max_pages: usize,
+    /// Also crawl category pages linked from the listing pages
+    #[serde(default)]
+    pub follow_categories: bool,
+    /// Also crawl tag pages linked from the listing pages
+    #[serde(default)]
+    pub follow_tags: bool,
+    /// Upper bound on listing pages fetched for one feed
+    #[serde(default = "default_max_listing_pages")]
+    pub max_listing_pages: usize,
+    /// Ignore articles published more than this many days ago
+    pub max_age_days: Option<u32>,
+    /// Most recently modified sitemap entries to fetch
+    #[serde(default = "default_max_sitemap_urls")]
+    pub max_sitemap_urls: usize,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum Discovery {
+    #[default]
+    Listing,
+    /// Sitemaps, falling back to the listing pages when they yield nothing
+    Sitemap,
+    /// Listing pages merged with sitemap entries
+    Both,
+}
+
+impl Default for CrawlConfig {
+    fn default() -> Self {
+        Self {
+            max_pages: default_max_pages(),
+            follow_categories: false,
+            follow_tags: false,
+            max_listing_pages: default_max_listing_pages(),
+            max_age_days: None,
+            max_sitemap_urls: default_max_sitemap_urls(),
+        }
+    }
+}
+
+fn default_max_pages() -> usize {
+    1
+}
+
+fn default_max_listing_pages() -> usize {
+    50
+}
+
+fn default_max_sitemap_urls() -> usize {
+    100
+}
+
+/// Channel metadata replacing the values provided by the source
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct ChannelOverrides {
+    pub title: Option<String>,
+    pub link: Option<String>,
+    pub description: Option<String>,
+    pub language: Option<String>,
+}
+
+#[derive(Debug, Default, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct FilterConfig {
+    /// Keep only items matching at least one of these rules; all items when empty
+    #[serde(default)]
+    pub include: Vec<FilterRule>,
+    /// Drop items matching any of these rules
+    #[serde(default)]
+    pub exclude: Vec<FilterRule>,
+}
+
+/// A single filter condition, written as e.g. `{"keyword": "AI"}` or `{"min_length": 300}`
+#[derive(Debug, Clone, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum FilterRule {
+    /// Case-insensitive substring of the title or the visible body text
+    Keyword(String),
+    /// Regular expression matched against the title and the visible body text
+    Regex(String),
+    Category(String),
+    Tag(String),
+    Author(String),
+    /// Body of at least this many characters
+    MinLength(usize),
+    /// Body of at most this many characters
+    MaxLength(usize),
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct OutputConfig {
+    pub path: PathBuf,
+    pub format: FeedFormat,
+    /// Public URL of this output, advertised inside the feed when supported
+    pub url: Option<String>,
+    /// Applied to this output only, after the feed-level filters
+    #[serde(default)]
+    pub filters: FilterConfig,
+}
+
+#[derive(Debug, Deserialize, PartialEq)]
+#[serde(deny_unknown_fields)]
+pub struct SplitConfig {
+    #[serde(default)]
+    pub by: SplitBy,
+    /// Directory receiving `<slug>.xml` / `<slug>.json`
+    pub output_dir: PathBuf,
+    #[serde(default = "default_split_formats")]
+    pub formats: Vec<FeedFormat>,
+    /// Only write feeds for these categories (or tags); all when empty
+    #[serde(default)]
+    pub only: Vec<String>,
+    /// File name slugs overriding the built-in ones, e.g. {"生成AI": "genai"}
+    #[serde(default)]
+    pub slugs: BTreeMap<String, String>,
+    /// Public URL of `output_dir`
+    pub base_url: Option<String>,
+}
+
+#[derive(Debug, Default, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum SplitBy {
+    #[default]
+    Category,
+    Tag,
+}
+
+fn default_split_formats() -> Vec<FeedFormat> {
+    vec![FeedFormat::Rss]
+}
+
+#[derive(Debug, Clone, Copy, Deserialize, PartialEq)]
+#[serde(rename_all = "lowercase")]
+pub enum FeedFormat {
+    Rss,
+    Atom,
+    Json,
+}
+
+impl Config {
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let text = fs::read_to_string(path)
+            .map_err(|e| Error::config(format!("Failed to read config {}: {e}", path.display())))?;
+        Self::parse(&text)
+            .map_err(|e| Error::config(format!("Invalid config {}: {e}", path.display())))
+    }
+
+    pub fn parse(text: &str) -> Result<Self, Error> {
+        let config: Config =
+            serde_json::from_str(text).map_err(|e| Error::config(e.to_string()))?;
+        if let Some(websub) = &config.websub {
+            if !websub.hub.starts_with("http://") && !websub.hub.starts_with("https://") {
+                return Err(Error::config(format!(
+                    "WebSub hub '{}' is not an HTTP URL",
+                    websub.hub
+                )));
+            }
+        }
+        if let Some(digest) = &config.digest {
+            if digest.to.is_empty() {
+                return Err(Error::config("digest.to needs at least one recipient"));
+            }
+            for address in std::iter::once(&digest.from).chain(&digest.to) {
+                address.parse::<Mailbox>().map_err(|e| {
+                    Error::config(format!("Invalid digest address '{address}': {e}"))
+                })?;
+            }
+        }
+        for feed in &config.feeds {
+            feed.build_source()?;
+            if feed.outputs.is_empty() {
+                return Err(Error::config(format!(
+                    "Feed for source '{}' has no outputs",
+                    feed.source
+                )));
+            }
+            ItemFilter::new(&feed.filters)?;
+            let diff_outputs = feed.diff.iter().flat_map(|diff| &diff.outputs);
+            for output in feed.outputs.iter().chain(diff_outputs) {
+                ItemFilter::new(&output.filters)?;
+            }
+            if let Some(schedule) = &feed.schedule {
+                Schedule::from_config(schedule)?;
+            }
+            if let Some(resurface) = &feed.resurface {
+                if !(0.0..=1.0).contains(&resurface.min_change_ratio) {
+                    return Err(Error::config(
+                        "resurface.min_change_ratio must be between 0.0 and 1.0",
+                    ));
+                }
+            }
+            if let Some(health) = &feed.health {
+                if !(0.0..=1.0).contains(&health.max_fallback_date_ratio) {
+                    return Err(Error::config(
+                        "health.max_fallback_date_ratio must be between 0.0 and 1.0",
+                    ));
+                }
+            }
+        }
+        Ok(config)
+    }
+}
+
+impl Default for Config {
+    /// The single Ledge.ai feed published on GitHub Pages
+    fn default() -> Self {
+        Self {
+            feeds: vec![FeedConfig {
+                source: "ledge_ai".to_string(),
+                listing_urls: Vec::new(),
+                discovery: Discovery::default(),
+                sitemap_urls: Vec::new(),
+                max_items: Some(10),
+                crawl: CrawlConfig::default(),
+                channel: ChannelOverrides::default(),
+                filters: FilterConfig::default(),
+                outputs: vec![OutputConfig {
+                    path: PathBuf::from("rss.xml"),
+                    format: FeedFormat::Rss,
+                    url: None,
+                    filters: FilterConfig::default(),
+                }],
+                split: None,
+                resurface: None,
+                diff: None,
+                schedule: None,
+                webhooks: Vec::new(),
+                health: None,
+            }],
+            archive: None,
+            websub: None,
+            digest: None,
+        }
+    }
+}
+
+impl FeedFormat {
+    /// File extension used for generated files
+    pub fn extension(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "xml",
+            FeedFormat::Atom => "atom",
+            FeedFormat::Json => "json",
+        }
+    }
+
+    pub fn content_type(self) -> &'static str {
+        match self {
+            FeedFormat::Rss => "application/rss+xml; charset=utf-8",
+            FeedFormat::Atom => "application/atom+xml; charset=utf-8",
+            FeedFormat::Json => "application/feed+json; charset=utf-8",
+        }
+    }
+}
+
+impl CrawlConfig {
+    /// Oldest publication date still accepted, relative to `now`
+    pub fn cutoff(&self, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        self.max_age_days
+            .map(|days| now - chrono::Duration::days(i64::from(days)))
+    }
+}
+
+impl FeedConfig {
+    /// Tells feeds of the same source apart in metrics: the first output path
+    pub fn id(&self) -> String {
+        self.outputs.first().map_or_else(
+            || self.source.clone(),
+            |output| output.path.display().to_string(),
+        )
+    }
+
+    pub fn build_source(&self) -> Result<Box<dyn Source>, Error> {
+        match self.source.as_str() {
+            "ledge_ai" => Ok(Box::new(LedgeAi)),
+            other => Err(Error::config(format!("Unknown source: {other}"))),
+        }
+    }
+
+    pub fn listing_urls(&self, source: &dyn Source) -> Vec<String> {
+        if self.listing_urls.is_empty() {
+            source.listing_urls()
+        } else {
+            self.listing_urls.clone()
+        }
+    }
+
+    pub fn sitemap_urls(&self, source: &dyn Source) -> Vec<String> {
+        if self.sitemap_urls.is_empty() {
+            source.sitemap_urls()
+        } else {
+            self.sitemap_urls.clone()
+        }
+    }
+
+    pub fn channel(&self, source: &dyn Source) -> ChannelInfo {
+        let mut channel = source.channel();
+        let overrides = &self.channel;
+        if let Some(title) = &overrides.title {
+            channel.title = title.clone();
+        }
+        if let Some(link) = &overrides.link {
+            channel.link = link.clone();
+        }
+        if let Some(description) = &overrides.description {
+            channel.description = description.clone();
+        }
+        if let Some(language) = &overrides.language {
+            channel.language = language.clone();
+        }
+        channel
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_config() {
+        let config = Config::parse(
+            r#"{
+                "feeds": [{
+                    "source": "ledge_ai",
+                    "listing_urls": ["https://ledge.ai/categories/business"],
+                    "discovery": "both",
+                    "max_items": 20,
+                    "crawl": {"max_pages": 3, "follow_categories": true, "max_age_days": 7},
+                    "channel": {"title": "Ledge.ai ビジネス"},
+                    "filters": {"exclude": [{"keyword": "PR"}, {"max_length": 299}]},
+                    "outputs": [
+                        {"path": "feeds/business.xml", "format": "rss", "filters": {"include": [{"tag": "生成AI"}]}},
+                        {"path": "feeds/business.json", "format": "json", "url": "https://example.com/business.json"}
+                    ]
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let feed = &config.feeds[0];
+        assert_eq!(feed.max_items, Some(20));
+        assert_eq!(feed.crawl.max_pages, 3);
+        assert!(feed.crawl.follow_categories);
+        assert!(!feed.crawl.follow_tags);
+        assert_eq!(feed.crawl.max_listing_pages, 50);
+        let now = Utc::now();
+        assert_eq!(
+            feed.crawl.cutoff(now),
+            Some(now - chrono::Duration::days(7))
+        );
+        assert_eq!(feed.outputs.len(), 2);
+        assert_eq!(feed.outputs[1].format, FeedFormat::Json);
+        assert_eq!(
+            feed.filters.exclude,
+            vec![
+                FilterRule::Keyword("PR".to_string()),
+                FilterRule::MaxLength(299)
+            ]
+        );
+        assert_eq!(
+            feed.outputs[0].filters.include,
+            vec![FilterRule::Tag("生成AI".to_string())]
+        );
+
+        let source = feed.build_source().unwrap();
+        assert_eq!(
+            feed.listing_urls(source.as_ref()),
+            vec!["https://ledge.ai/categories/business"]
+        );
+        assert_eq!(feed.discovery, Discovery::Both);
+        assert_eq!(
+            feed.sitemap_urls(source.as_ref()),
+            vec!["https://ledge.ai/sitemap.xml"]
+        );
+        let channel = feed.channel(source.as_ref());
+        assert_eq!(channel.title, "Ledge.ai ビジネス");
+        assert_eq!(channel.link, "https://ledge.ai/");
+    }
+
+    #[test]
+    fn test_parse_config_split() {
+        let config = Config::parse(
+            r#"{
+                "feeds": [{
+                    "source": "ledge_ai",
+                    "outputs": [{"path": "rss.xml", "format": "rss"}],
+                    "split": {"output_dir": "feeds", "only": ["ビジネス"]},
+                    "resurface": {"min_change_ratio": 0.3},
+                    "diff": {"history": true, "outputs": [{"path": "changes.xml", "format": "rss"}]}
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let split = config.feeds[0].split.as_ref().unwrap();
+        assert_eq!(split.by, SplitBy::Category);
+        assert_eq!(split.formats, vec![FeedFormat::Rss]);
+        assert_eq!(split.only, vec!["ビジネス"]);
+
+        let resurface = config.feeds[0].resurface.as_ref().unwrap();
+        assert_eq!(resurface.min_change_ratio, 0.3);
+        assert_eq!(resurface.title_prefix, "【更新】");
+
+        let diff = config.feeds[0].diff.as_ref().unwrap();
+        assert!(diff.history);
+        assert_eq!(diff.outputs[0].path, PathBuf::from("changes.xml"));
+    }
+
+    #[test]
+    fn test_parse_config_archive() {
+        let config = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "outputs": [{"path": "rss.xml", "format": "rss"}]}],
+                "archive": {"dir": "archive"}}"#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            config.archive,
+            Some(ArchiveConfig {
+                dir: PathBuf::from("archive"),
+                markdown: true,
+            })
+        );
+    }
+
+    #[test]
+    fn test_parse_config_schedule() {
+        let config = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "schedule": {"cron": "*/15 * * * *", "jitter_seconds": 60}, "outputs": [{"path": "rss.xml", "format": "rss"}]}]}"#,
+        )
+        .unwrap();
+        let schedule = config.feeds[0].schedule.as_ref().unwrap();
+        assert_eq!(schedule.cron.as_deref(), Some("*/15 * * * *"));
+        assert_eq!(schedule.jitter_seconds, 60);
+
+        let invalid = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "schedule": {"cron": "every hour"}, "outputs": [{"path": "rss.xml", "format": "rss"}]}]}"#,
+        );
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_parse_config_websub() {
+        let config = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "outputs": [{"path": "rss.xml", "format": "rss"}]}],
+                "websub": {"hub": "https://pubsubhubbub.appspot.com/"}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.websub,
+            Some(WebSubConfig {
+                hub: "https://pubsubhubbub.appspot.com/".to_string()
+            })
+        );
+
+        let invalid =
+            Config::parse(r#"{"feeds": [], "websub": {"hub": "pubsubhubbub.appspot.com"}}"#);
+        assert!(invalid.is_err());
+    }
+
+    #[test]
+    fn test_parse_config_digest() {
+        let config = Config::parse(
+            r#"{"feeds": [], "digest": {"from": "Feed <feed@example.com>", "to": ["team@example.com"],
+                "transport": {"type": "smtp", "host": "smtp.example.com", "username": "feed",
+                              "password_env": "SMTP_PASSWORD"}}}"#,
+        )
+        .unwrap();
+        let digest = config.digest.unwrap();
+        assert_eq!(digest.subject, "Ledge.ai ダイジェスト");
+        assert_eq!(digest.max_items, 50);
+        assert_eq!(
+            digest.transport,
+            DigestTransport::Smtp {
+                host: "smtp.example.com".to_string(),
+                port: None,
+                security: SmtpSecurity::Starttls,
+                username: Some("feed".to_string()),
+                password_env: Some("SMTP_PASSWORD".to_string()),
+            }
+        );
+
+        let config = Config::parse(
+            r#"{"feeds": [], "digest": {"from": "feed@example.com", "to": ["team@example.com"],
+                "transport": {"type": "sendmail"}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            config.digest.unwrap().transport,
+            DigestTransport::Sendmail {
+                command: "sendmail".to_string()
+            }
+        );
+
+        let invalid = Config::parse(
+            r#"{"feeds": [], "digest": {"from": "not an address", "to": ["team@example.com"],
+                "transport": {"type": "file", "dir": "digests"}}}"#,
+        );
+        assert!(invalid.is_err());
+        let unknown = Config::parse(
+            r#"{"feeds": [], "digest": {"from": "feed@example.com", "to": ["team@example.com"],
+                "transport": {"type": "file", "dir": "digests", "extra": 1}}}"#,
+        );
+        assert!(unknown.is_err());
+    }
+
+    #[test]
+    fn test_parse_config_webhooks() {
+        let config = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "outputs": [{"path": "rss.xml", "format": "rss"}],
+                "webhooks": [
+                    {"url": "https://hooks.slack.com/services/x", "preset": "slack"},
+                    {"url": "https://example.com/hook", "template": {"text": "{title}"}, "retries": 0}
+                ]}]}"#,
+        )
+        .unwrap();
+
+        let webhooks = &config.feeds[0].webhooks;
+        assert_eq!(webhooks[0].preset, Some(WebhookPreset::Slack));
+        assert_eq!(webhooks[0].retries, 3);
+        assert_eq!(
+            webhooks[1].template,
+            Some(serde_json::json!({"text": "{title}"}))
+        );
+        assert_eq!(webhooks[1].retries, 0);
+    }
+
+    #[test]
+    fn test_parse_config_health() {
+        let config = Config::parse(
+            r#"{
+                "feeds": [{
+                    "source": "ledge_ai",
+                    "outputs": [{"path": "rss.xml", "format": "rss"}],
+                    "health": {"min_items": 10, "suspicious_endings": ["…"]}
+                }]
+            }"#,
+        )
+        .unwrap();
+
+        let health = config.feeds[0].health.as_ref().unwrap();
+        assert_eq!(health.min_items, 10);
+        assert_eq!(health.min_average_chars, 500);
+        assert_eq!(health.max_fallback_date_ratio, 0.5);
+        assert_eq!(health.suspicious_endings, vec!["…"]);
+
+        let result = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "health": {"max_fallback_date_ratio": 1.5}, "outputs": [{"path": "a.xml", "format": "rss"}]}]}"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_config_rejects_unknown_source() {
+        let result = Config::parse(
+            r#"{"feeds": [{"source": "unknown", "outputs": [{"path": "a.xml", "format": "rss"}]}]}"#,
+        );
+        assert!(matches!(result, Err(Error::Config { .. })));
+    }
+
+    #[test]
+    fn test_parse_config_rejects_unknown_format() {
+        let result = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "outputs": [{"path": "a.xml", "format": "yaml"}]}]}"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_config_rejects_invalid_filter_regex() {
+        let result = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "filters": {"exclude": [{"regex": "("}]}, "outputs": [{"path": "a.xml", "format": "rss"}]}]}"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_parse_config_rejects_invalid_change_ratio() {
+        let result = Config::parse(
+            r#"{"feeds": [{"source": "ledge_ai", "resurface": {"min_change_ratio": 2}, "outputs": [{"path": "a.atom", "format": "atom"}]}]}"#,
+        );
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_default_config_matches_published_feed() {
+        let config = Config::default();
+        assert_eq!(config.feeds.len(), 1);
+        assert_eq!(config.feeds[0].outputs[0].path, PathBuf::from("rss.xml"));
+        assert_eq!(config.feeds[0].outputs.len(), 1);
+        assert_eq!(config.feeds[0].max_items, Some(10));
+        assert_eq!(config.feeds[0].crawl, CrawlConfig::default());
+        assert_eq!(config.feeds[0].crawl.cutoff(Utc::now()), None);
+        assert_eq!(config.feeds[0].health, None);
+    }
+}
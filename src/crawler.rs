@@ -0,0 +1,326 @@
+use crate::config::CrawlConfig;
+use crate::error::{Error, Stage};
+use crate::html_parser::ArticleInfo;
+use crate::http_client::HttpClient;
+use crate::source::Source;
+use chrono::{DateTime, Utc};
+use std::collections::{HashSet, VecDeque};
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// A listing page waiting to be fetched
+struct ListingPage {
+    url: String,
+    /// Position in its pagination chain, starting at 1
+    page: usize,
+    /// Configured start page (as opposed to one discovered while crawling)
+    start: bool,
+}
+
+/// Crawl the start listings, their pagination and optionally the category and
+/// tag pages they link to. Articles are deduplicated by URL, keeping the first
+/// occurrence. Failing to fetch a start page is an error; discovered pages
+/// are skipped with a warning.
+pub async fn crawl_listings(
+    client: &HttpClient,
+    source: &dyn Source,
+    start_urls: Vec<String>,
+    crawl: &CrawlConfig,
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<Vec<ArticleInfo>, Error> {
+    let mut queue: VecDeque<ListingPage> = start_urls
+        .into_iter()
+        .map(|url| ListingPage {
+            url,
+            page: 1,
+            start: true,
+        })
+        .collect();
+    let mut visited = HashSet::new();
+    let mut articles: Vec<ArticleInfo> = Vec::new();
+
+    while let Some(listing) = queue.pop_front() {
+        if !visited.insert(listing.url.clone()) {
+            continue;
+        }
+        if visited.len() > crawl.max_listing_pages {
+            warn!(
+                limit = crawl.max_listing_pages,
+                "Reached the limit of listing pages"
+            );
+            break;
+        }
+
+        let started = Instant::now();
+        let parsed = match client.fetch_url(&listing.url, Stage::Listing).await {
+            Ok(html) => source
+                .parse_listing(&html, &listing.url)
+                .map(|found| (html, found)),
+            Err(e) => Err(e),
+        };
+        let (html, found) = match parsed {
+            Ok(parsed) => parsed,
+            Err(e) if listing.start => return Err(e),
+            Err(e) => {
+                warn!(url = %listing.url, error = %e, "Skipping listing page");
+                continue;
+            }
+        };
+
+        // Stop paginating once no article on the page is known to be recent
+        let reached_cutoff = !found.is_empty()
+            && !found
+                .iter()
+                .any(|article| is_recent(article, cutoff) == Some(true));
+        let recent: Vec<ArticleInfo> = found
+            .into_iter()
+            .filter(|article| is_recent(article, cutoff) != Some(false))
+            .collect();
+        info!(
+            source = source.id(),
+            url = %listing.url,
+            stage = "listing",
+            bytes = html.len(),
+            duration_ms = started.elapsed().as_millis() as u64,
+            articles = recent.len(),
+            "Fetched listing page"
+        );
+        merge_articles(&mut articles, recent);
+
+        let links = source.listing_links(&html, &listing.url);
+        if let Some(next_page) = links.next_page {
+            if listing.page < crawl.max_pages && !reached_cutoff {
+                queue.push_back(ListingPage {
+                    url: next_page,
+                    page: listing.page + 1,
+                    start: false,
+                });
+            }
+        }
+
+        // Only the configured listings fan out, so category pages do not
+        // pull in every other category again
+        if listing.start {
+            let mut discovered = Vec::new();
+            if crawl.follow_categories {
+                discovered.extend(links.categories);
+            }
+            if crawl.follow_tags {
+                discovered.extend(links.tags);
+            }
+            queue.extend(discovered.into_iter().map(|url| ListingPage {
+                url,
+                page: 1,
+                start: false,
+            }));
+        }
+    }
+
+    Ok(articles)
+}
+
+/// Whether the listing date is on or after the cutoff, `None` when the listing
+/// has no reliable date (e.g. the static HTML fallback). Such articles are
+/// kept and checked again once the article page has been read.
+fn is_recent(article: &ArticleInfo, cutoff: Option<DateTime<Utc>>) -> Option<bool> {
+    match cutoff {
+        None => Some(true),
+        Some(cutoff) => DateTime::parse_from_rfc3339(&article.date)
+            .ok()
+            .map(|date| date >= cutoff),
+    }
+}
+
+/// Append articles not seen yet, completing known ones with missing details
+pub fn merge_articles(articles: &mut Vec<ArticleInfo>, found: Vec<ArticleInfo>) {
+    for article in found {
+        match articles.iter_mut().find(|known| known.url == article.url) {
+            Some(known) => {
+                if known.category.is_none() {
+                    known.category = article.category;
+                }
+                if known.lastmod.is_none() {
+                    known.lastmod = article.lastmod;
+                }
+            }
+            None => articles.push(article),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::LedgeAi;
+    use crate::test_support::serve;
+
+    fn article(url: &str, date: &str, category: Option<&str>) -> ArticleInfo {
+        ArticleInfo {
+            title: "Title".to_string(),
+            url: url.to_string(),
+            date: date.to_string(),
+            category: category.map(String::from),
+            lastmod: None,
+        }
+    }
+
+    #[test]
+    fn test_merge_articles_deduplicates_and_fills_category() {
+        let mut articles = vec![article("https://ledge.ai/articles/a", "", None)];
+        merge_articles(
+            &mut articles,
+            vec![
+                article("https://ledge.ai/articles/a", "", Some("ビジネス")),
+                article("https://ledge.ai/articles/b", "", None),
+            ],
+        );
+
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].category.as_deref(), Some("ビジネス"));
+    }
+
+    #[test]
+    fn test_is_recent() {
+        let cutoff = DateTime::parse_from_rfc3339("2025-07-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let new = article("a", "2025-07-14T07:50:00.000Z", None);
+        let old = article("b", "2025-06-01T00:00:00Z", None);
+        let unknown = article("c", "2025/01/14 [MON]", None);
+
+        assert_eq!(is_recent(&new, Some(cutoff)), Some(true));
+        assert_eq!(is_recent(&old, Some(cutoff)), Some(false));
+        assert_eq!(is_recent(&unknown, Some(cutoff)), None);
+        assert_eq!(is_recent(&old, None), Some(true));
+    }
+
+    #[tokio::test]
+    async fn test_crawl_follows_pagination_and_categories() {
+        let base = serve(vec![
+            (
+                "/",
+                r#"<a href="/articles/one">Article One</a>
+                   <a href="/categories/business">Business</a>
+                   <a rel="next" href="/?page=2">Next</a>"#
+                    .to_string(),
+            ),
+            (
+                "/?page=2",
+                r#"<a href="/articles/two">Article Two</a>
+                   <a rel="next" href="/?page=3">Next</a>"#
+                    .to_string(),
+            ),
+            (
+                "/?page=3",
+                r#"<a href="/articles/three">Article Three</a>"#.to_string(),
+            ),
+            (
+                "/categories/business",
+                r#"<a href="/articles/one">Article One</a>
+                   <a href="/articles/biz">Business Article</a>"#
+                    .to_string(),
+            ),
+        ])
+        .await;
+
+        let crawl = CrawlConfig {
+            max_pages: 2,
+            follow_categories: true,
+            ..Default::default()
+        };
+        let articles = crawl_listings(
+            &HttpClient::new(),
+            &LedgeAi,
+            vec![format!("{base}/")],
+            &crawl,
+            None,
+        )
+        .await
+        .unwrap();
+
+        let urls: Vec<&str> = articles.iter().map(|a| a.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://ledge.ai/articles/one",
+                "https://ledge.ai/articles/two",
+                "https://ledge.ai/articles/biz",
+            ]
+        );
+    }
+
+    #[tokio::test]
+    async fn test_crawl_stops_paginating_without_listing_dates() {
+        let base = serve(vec![
+            (
+                "/",
+                r#"<a href="/articles/one">Article One</a>
+                   <a rel="next" href="/?page=2">Next</a>"#
+                    .to_string(),
+            ),
+            (
+                "/?page=2",
+                r#"<a href="/articles/two">Article Two</a>"#.to_string(),
+            ),
+        ])
+        .await;
+
+        let crawl = CrawlConfig {
+            max_pages: 3,
+            ..Default::default()
+        };
+        let cutoff = DateTime::parse_from_rfc3339("2025-07-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let articles = crawl_listings(
+            &HttpClient::new(),
+            &LedgeAi,
+            vec![format!("{base}/")],
+            &crawl,
+            Some(cutoff),
+        )
+        .await
+        .unwrap();
+
+        // The static HTML listing has no dates: its articles are kept for the
+        // article pages to decide, but it does not count as recent
+        let urls: Vec<&str> = articles.iter().map(|a| a.url.as_str()).collect();
+        assert_eq!(urls, vec!["https://ledge.ai/articles/one"]);
+    }
+
+    #[tokio::test]
+    async fn test_crawl_fails_only_for_start_pages() {
+        let base = serve(vec![(
+            "/",
+            r#"<a href="/articles/one">Article One</a>
+               <a href="/tags/missing">Missing</a>"#
+                .to_string(),
+        )])
+        .await;
+
+        let crawl = CrawlConfig {
+            follow_tags: true,
+            ..Default::default()
+        };
+        let articles = crawl_listings(
+            &HttpClient::new(),
+            &LedgeAi,
+            vec![format!("{base}/")],
+            &crawl,
+            None,
+        )
+        .await
+        .unwrap();
+        assert_eq!(articles.len(), 1);
+
+        let result = crawl_listings(
+            &HttpClient::new(),
+            &LedgeAi,
+            vec!["http://127.0.0.1:1/".to_string()],
+            &crawl,
+            None,
+        )
+        .await;
+        assert!(result.is_err());
+    }
+}
@@ -0,0 +1,402 @@
+use crate::config::{DigestConfig, DigestTransport, SmtpSecurity};
+use crate::error::Error;
+use crate::filter::ItemFilter;
+use crate::rss_generator::escape_attr;
+use crate::rss_item::RssItem;
+use crate::state_store::StateStore;
+use chrono::{DateTime, Duration, FixedOffset, Utc};
+use lettre::message::{Mailbox, MultiPart};
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSendmailTransport, AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use std::fs;
+
+/// Without a previous digest, only articles found during the last day are sent
+const FIRST_DIGEST_WINDOW: Duration = Duration::hours(24);
+
+/// Articles for one digest email
+pub struct DigestItems {
+    /// Newest first
+    pub items: Vec<RssItem>,
+    /// When the last included article was first seen; the next digest starts
+    /// after it, so articles over `max_items` go out next time
+    pub seen_until: Option<DateTime<Utc>>,
+}
+
+/// Articles first seen since the last digest that pass the filters of at
+/// least one feed, up to `max_items` of the earliest found. Backfilled
+/// articles (including those recorded by the first run) are never included.
+pub fn digest_items(
+    state: &StateStore,
+    filters: &[ItemFilter],
+    max_items: usize,
+    now: DateTime<Utc>,
+) -> DigestItems {
+    let since = state.last_digest().unwrap_or(now - FIRST_DIGEST_WINDOW);
+    let mut candidates: Vec<(DateTime<Utc>, RssItem)> = state
+        .items()
+        .filter(|stored| !stored.backfilled && stored.first_seen > since)
+        .filter(|stored| filters.iter().any(|filter| filter.accepts(&stored.item)))
+        .map(|stored| (stored.first_seen, stored.item.clone()))
+        .collect();
+    candidates.sort_by_key(|(first_seen, item)| (*first_seen, item.pub_date));
+
+    if let Some(&(next_seen, _)) = candidates.get(max_items) {
+        // Articles of one run share `first_seen`; keep them together unless a
+        // single run found more than fit in one email
+        let whole_runs = candidates
+            .iter()
+            .take_while(|(first_seen, _)| *first_seen < next_seen)
+            .count();
+        candidates.truncate(if whole_runs > 0 {
+            whole_runs
+        } else {
+            max_items
+        });
+    }
+
+    let seen_until = candidates.last().map(|(first_seen, _)| *first_seen);
+    let mut items: Vec<RssItem> = candidates.into_iter().map(|(_, item)| item).collect();
+    items.sort_by_key(|item| std::cmp::Reverse(item.pub_date));
+    DigestItems { items, seen_until }
+}
+
+fn jst(date: DateTime<Utc>) -> DateTime<FixedOffset> {
+    date.with_timezone(&FixedOffset::east_opt(9 * 3600).unwrap())
+}
+
+/// Multipart email with an HTML and a plain-text version of the digest
+pub fn build_message(
+    config: &DigestConfig,
+    items: &[RssItem],
+    now: DateTime<Utc>,
+) -> Result<Message, Error> {
+    let subject = format!("{} ({})", config.subject, jst(now).format("%Y/%m/%d"));
+    let mut builder = Message::builder()
+        .from(mailbox(&config.from)?)
+        .subject(subject.as_str())
+        .date(now.into());
+    for to in &config.to {
+        builder = builder.to(mailbox(to)?);
+    }
+    builder
+        .multipart(MultiPart::alternative_plain_html(
+            render_text(&subject, items),
+            render_html(&subject, items),
+        ))
+        .map_err(Error::mail)
+}
+
+fn mailbox(address: &str) -> Result<Mailbox, Error> {
+    address
+        .parse()
+        .map_err(|e| Error::config(format!("Invalid digest address {address:?}: {e}")))
+}
+
+fn render_text(subject: &str, items: &[RssItem]) -> String {
+    let mut text = format!("{subject}\n{} 件の新着記事\n", items.len());
+    for item in items {
+        text.push_str(&format!("\n■ {}\n", item.title));
+        text.push_str(&format!(
+            "{}\n",
+            jst(item.pub_date).format("%Y/%m/%d %H:%M")
+        ));
+        if let Some(summary) = &item.summary {
+            text.push_str(&format!("{summary}\n"));
+        }
+        text.push_str(&format!("{}\n", item.link));
+    }
+    text
+}
+
+fn render_html(subject: &str, items: &[RssItem]) -> String {
+    let entries: String = items
+        .iter()
+        .map(|item| {
+            let mut entry = format!(
+                "<div style=\"margin: 0 0 32px;\">\n<h2 style=\"font-size: 18px;\"><a href=\"{}\">{}</a></h2>\n",
+                escape_attr(&item.link),
+                escape_attr(&item.title)
+            );
+            if let Some(image) = item.image() {
+                entry.push_str(&format!(
+                    "<a href=\"{}\"><img src=\"{}\" alt=\"\" style=\"max-width: 100%; height: auto;\"></a>\n",
+                    escape_attr(&item.link),
+                    escape_attr(&image)
+                ));
+            }
+            if let Some(summary) = &item.summary {
+                entry.push_str(&format!("<p>{}</p>\n", escape_attr(summary)));
+            }
+            let mut meta = jst(item.pub_date).format("%Y/%m/%d %H:%M").to_string();
+            if let Some(category) = &item.category {
+                meta.push_str(&format!(" / {}", escape_attr(category)));
+            }
+            entry.push_str(&format!(
+                "<p style=\"color: #666; font-size: 12px;\">{meta}</p>\n</div>\n"
+            ));
+            entry
+        })
+        .collect();
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n<title>{title}</title>\n</head>\n\
+         <body style=\"font-family: sans-serif; max-width: 640px; margin: 0 auto;\">\n\
+         <h1 style=\"font-size: 22px;\">{title}</h1>\n<p>{} 件の新着記事</p>\n{entries}</body>\n</html>\n",
+        items.len(),
+        title = escape_attr(subject)
+    )
+}
+
+/// Hand the message to the configured transport and describe where it went
+pub async fn deliver(
+    transport: &DigestTransport,
+    message: Message,
+    now: DateTime<Utc>,
+) -> Result<String, Error> {
+    match transport {
+        DigestTransport::Smtp {
+            host,
+            port,
+            security,
+            username,
+            password_env,
+        } => {
+            let mut builder = match security {
+                SmtpSecurity::Starttls => {
+                    AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                        .map_err(Error::mail)?
+                }
+                SmtpSecurity::Tls => {
+                    AsyncSmtpTransport::<Tokio1Executor>::relay(host).map_err(Error::mail)?
+                }
+                SmtpSecurity::None => {
+                    AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host).port(25)
+                }
+            };
+            if let Some(port) = port {
+                builder = builder.port(*port);
+            }
+            if let Some(username) = username {
+                let password = match password_env {
+                    Some(name) => std::env::var(name).map_err(|_| {
+                        Error::config(format!("SMTP password variable {name} is not set"))
+                    })?,
+                    None => String::new(),
+                };
+                builder = builder.credentials(Credentials::new(username.clone(), password));
+            }
+            builder.build().send(message).await.map_err(Error::mail)?;
+            Ok(format!("via SMTP server {host}"))
+        }
+        DigestTransport::Sendmail { command } => {
+            AsyncSendmailTransport::<Tokio1Executor>::new_with_command(command)
+                .send(message)
+                .await
+                .map_err(Error::mail)?;
+            Ok(format!("via {command}"))
+        }
+        DigestTransport::File { dir } => {
+            fs::create_dir_all(dir).map_err(Error::io(dir))?;
+            let path = dir.join(format!("digest-{}.eml", now.format("%Y%m%d-%H%M%S")));
+            fs::write(&path, message.formatted()).map_err(Error::io(&path))?;
+            Ok(format!("to {}", path.display()))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{FilterConfig, FilterRule};
+    use crate::test_support::temp_dir;
+    use std::path::PathBuf;
+
+    fn item(slug: &str, date: &str) -> RssItem {
+        RssItem {
+            title: format!("記事 {slug} & <続き>"),
+            link: format!("https://ledge.ai/articles/{slug}"),
+            description: format!(r#"<p><img src="https://ledge.ai/img/{slug}.jpg" alt=""></p>"#),
+            pub_date: DateTime::parse_from_rfc3339(date)
+                .unwrap()
+                .with_timezone(&Utc),
+            summary: Some("要約です。".to_string()),
+            category: Some("ビジネス".to_string()),
+            ..Default::default()
+        }
+    }
+
+    fn config(dir: PathBuf) -> DigestConfig {
+        DigestConfig {
+            from: "Ledge Feed <feed@example.com>".to_string(),
+            to: vec!["team@example.com".to_string()],
+            subject: "Ledge.ai ダイジェスト".to_string(),
+            max_items: 50,
+            transport: DigestTransport::File { dir },
+        }
+    }
+
+    #[test]
+    fn test_digest_items_since_last_digest() {
+        let dir = temp_dir("digest-items");
+        let mut state = StateStore::load(&dir.join("state.json")).unwrap();
+        let now = Utc::now();
+        state.record(
+            &mut item("old", "2025-07-01T00:00:00Z"),
+            now - Duration::days(3),
+            false,
+        );
+        state.record(
+            &mut item("backfilled", "2025-06-01T00:00:00Z"),
+            now - Duration::hours(1),
+            true,
+        );
+        state.record(
+            &mut item("a", "2025-07-10T00:00:00Z"),
+            now - Duration::hours(2),
+            false,
+        );
+        state.record(
+            &mut item("b", "2025-07-12T00:00:00Z"),
+            now - Duration::hours(1),
+            false,
+        );
+
+        let all = vec![ItemFilter::new(&FilterConfig::default()).unwrap()];
+        // The first digest covers the last day
+        let links: Vec<String> = digest_items(&state, &all, 50, now)
+            .items
+            .into_iter()
+            .map(|item| item.link)
+            .collect();
+        assert_eq!(
+            links,
+            vec!["https://ledge.ai/articles/b", "https://ledge.ai/articles/a"]
+        );
+
+        state.set_last_digest(now - Duration::minutes(90));
+        assert_eq!(digest_items(&state, &all, 50, now).items.len(), 1);
+    }
+
+    #[test]
+    fn test_digest_items_over_the_limit_go_out_next_time() {
+        let dir = temp_dir("digest-limit");
+        let mut state = StateStore::load(&dir.join("state.json")).unwrap();
+        let now = Utc::now();
+        let first_run = now - Duration::hours(3);
+        let second_run = now - Duration::hours(2);
+        let third_run = now - Duration::hours(1);
+        for (slug, seen) in [
+            ("a", first_run),
+            ("b", first_run),
+            ("c", second_run),
+            ("d", second_run),
+            ("e", third_run),
+        ] {
+            state.record(&mut item(slug, "2025-07-10T00:00:00Z"), seen, false);
+        }
+        let all = vec![ItemFilter::new(&FilterConfig::default()).unwrap()];
+        let slugs = |selected: &DigestItems| -> Vec<String> {
+            let mut slugs: Vec<String> = selected
+                .items
+                .iter()
+                .map(|item| item.link.rsplit('/').next().unwrap().to_string())
+                .collect();
+            slugs.sort();
+            slugs
+        };
+
+        // Three fit, but the second run's articles are not split up
+        let selected = digest_items(&state, &all, 3, now);
+        assert_eq!(slugs(&selected), vec!["a", "b"]);
+        assert_eq!(selected.seen_until, Some(first_run));
+
+        state.set_last_digest(first_run);
+        let selected = digest_items(&state, &all, 3, now);
+        assert_eq!(slugs(&selected), vec!["c", "d", "e"]);
+
+        // A single run larger than the limit is cut
+        state.set_last_digest(now - Duration::days(1));
+        assert_eq!(slugs(&digest_items(&state, &all, 1, now)), vec!["a"]);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_digest_items_apply_feed_filters() {
+        let dir = temp_dir("digest-filters");
+        let mut state = StateStore::load(&dir.join("state.json")).unwrap();
+        let now = Utc::now();
+        let mut excluded = item("pr", "2025-07-12T00:00:00Z");
+        excluded.title = "【PR】広告記事".to_string();
+        state.record(&mut excluded, now - Duration::hours(1), false);
+        state.record(
+            &mut item("a", "2025-07-10T00:00:00Z"),
+            now - Duration::hours(1),
+            false,
+        );
+
+        let filter = |exclude| {
+            ItemFilter::new(&FilterConfig {
+                include: Vec::new(),
+                exclude,
+            })
+            .unwrap()
+        };
+        let no_pr = filter(vec![FilterRule::Keyword("PR".to_string())]);
+        let links: Vec<String> = digest_items(&state, &[no_pr], 50, now)
+            .items
+            .into_iter()
+            .map(|item| item.link)
+            .collect();
+        assert_eq!(links, vec!["https://ledge.ai/articles/a"]);
+
+        // Another feed without that rule still accepts the article
+        let filters = vec![
+            filter(vec![FilterRule::Keyword("PR".to_string())]),
+            filter(Vec::new()),
+        ];
+        assert_eq!(digest_items(&state, &filters, 50, now).items.len(), 2);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_write_digest_as_eml() {
+        let dir = temp_dir("digest-eml");
+        let config = config(dir.clone());
+        let now = DateTime::parse_from_rfc3339("2025-07-14T01:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let items = vec![item("a", "2025-07-14T00:00:00Z")];
+
+        let message = build_message(&config, &items, now).unwrap();
+        let destination = deliver(&config.transport, message, now).await.unwrap();
+        let path = dir.join("digest-20250714-010000.eml");
+        assert_eq!(destination, format!("to {}", path.display()));
+
+        let eml = fs::read_to_string(&path).unwrap();
+        assert!(eml.contains("To: team@example.com"));
+        assert!(eml.contains("multipart/alternative"));
+        assert!(eml.contains("Content-Type: text/plain"));
+        assert!(eml.contains("Content-Type: text/html"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_render_digest_bodies() {
+        let items = vec![item("a", "2025-07-14T00:00:00Z")];
+
+        let html = render_html("ダイジェスト", &items);
+        assert!(
+            html.contains("<a href=\"https://ledge.ai/articles/a\">記事 a &amp; &lt;続き&gt;</a>")
+        );
+        assert!(html.contains("<img src=\"https://ledge.ai/img/a.jpg\""));
+        assert!(html.contains("<p>要約です。</p>"));
+        assert!(html.contains("2025/07/14 09:00 / ビジネス"));
+
+        let text = render_text("ダイジェスト", &items);
+        assert!(text.contains(
+            "■ 記事 a & <続き>\n2025/07/14 09:00\n要約です。\nhttps://ledge.ai/articles/a\n"
+        ));
+    }
+}
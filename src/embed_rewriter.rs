@@ -0,0 +1,288 @@
+use crate::html_attrs::{attr, parse_attributes};
+use crate::rss_generator::{escape_attr, escape_html};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+
+// Recognized embed markup. All patterns work on the extracted markdown, where
+// raw HTML embeds are passed through verbatim and shortcodes are plain text.
+static YOUTUBE_BARE_ID_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"^[A-Za-z0-9_-]{11}$").unwrap());
+static YOUTUBE_ID_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r"(?:youtube(?:-nocookie)?\.com/(?:embed/|watch\?v=|shorts/|v/)|youtu\.be/)([A-Za-z0-9_-]{11})",
+    )
+    .unwrap()
+});
+static TWEET_URL_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"https?://(?:www\.|mobile\.)?(?:twitter|x)\.com/[A-Za-z0-9_]+/status/\d+").unwrap()
+});
+static IFRAME_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?is)<iframe\b([^>]*)>.*?</iframe>|<iframe\b([^>]*)/>"#).unwrap());
+static TWEET_BLOCKQUOTE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)<blockquote\b[^>]*class="[^"]*twitter-tweet[^"]*"[^>]*>(.*?)</blockquote>"#)
+        .unwrap()
+});
+static INSTAGRAM_BLOCKQUOTE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(
+        r#"(?is)(<blockquote\b[^>]*class="[^"]*instagram-media[^"]*"[^>]*>).*?</blockquote>"#,
+    )
+    .unwrap()
+});
+static EMBED_SCRIPT_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)<script\b[^>]*src="[^"]*(?:platform\.twitter\.com|instagram\.com|speakerdeck\.com|slideshare\.net|tiktok\.com)[^"]*"[^>]*>\s*</script>"#)
+        .unwrap()
+});
+// markdown-it style shortcodes, e.g. `@[youtube](dQw4w9WgXcQ)` or `@[twitter](https://x.com/...)`
+static SHORTCODE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"@\[(youtube|twitter|tweet|x|speakerdeck|slideshare)\]\(([^)\s]+)\)").unwrap()
+});
+// A YouTube or X/Twitter URL standing alone on its own line
+static STANDALONE_URL_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?m)^[ \t]*(https?://[^\s<>()\x00]+)[ \t]*$").unwrap());
+// Fenced code blocks (closed or running to the end) and inline code spans
+static CODE_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?ms)^[ \t]*```.*?(?:^[ \t]*```[ \t]*$|\z)|^[ \t]*~~~.*?(?:^[ \t]*~~~[ \t]*$|\z)|`[^`\n]+`")
+        .unwrap()
+});
+static CODE_PLACEHOLDER_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\x00(\d+)\x00").unwrap());
+
+/// Replace embedded tweets, videos and slide decks with feed-friendly fallbacks
+/// e.g., `<iframe src="https://www.youtube.com/embed/ID">` -> linked thumbnail.
+/// Code blocks and inline code are left as they are.
+pub fn rewrite_embeds(markdown: &str) -> String {
+    let mut code = Vec::new();
+    let masked = CODE_PATTERN.replace_all(markdown, |caps: &Captures| {
+        code.push(caps[0].to_string());
+        format!("\x00{}\x00", code.len() - 1)
+    });
+    let result = rewrite_outside_code(&masked);
+    CODE_PLACEHOLDER_PATTERN
+        .replace_all(&result, |caps: &Captures| {
+            // The article itself may contain NUL-wrapped digits; leave those alone
+            caps[1]
+                .parse::<usize>()
+                .ok()
+                .and_then(|index| code.get(index))
+                .map_or_else(|| caps[0].to_string(), String::clone)
+        })
+        .into_owned()
+}
+
+fn rewrite_outside_code(markdown: &str) -> String {
+    let result = EMBED_SCRIPT_PATTERN.replace_all(markdown, "");
+    let result = TWEET_BLOCKQUOTE_PATTERN.replace_all(&result, |caps: &Captures| {
+        let inner = caps.get(1).map_or("", |m| m.as_str()).trim();
+        match TWEET_URL_PATTERN.find(inner) {
+            Some(url) => block(&format!(
+                "<blockquote>{inner}<p><a href=\"{}\">Xでポストを見る</a></p></blockquote>",
+                escape_attr(url.as_str())
+            )),
+            None => block(&format!("<blockquote>{inner}</blockquote>")),
+        }
+    });
+    let result = INSTAGRAM_BLOCKQUOTE_PATTERN.replace_all(&result, |caps: &Captures| {
+        let attrs = parse_attributes(&caps[1]);
+        match attr(&attrs, "data-instgrm-permalink") {
+            Some(url) => link_fallback(url, "Instagramで投稿を見る"),
+            None => String::new(),
+        }
+    });
+    let result = IFRAME_PATTERN.replace_all(&result, |caps: &Captures| {
+        let attrs = parse_attributes(caps.get(1).or(caps.get(2)).map_or("", |m| m.as_str()));
+        let src = attr(&attrs, "src").unwrap_or_default();
+        if let Some(id) = youtube_id(src) {
+            return youtube_fallback(id);
+        }
+        if src.is_empty() {
+            return String::new();
+        }
+        let src = if src.starts_with("//") {
+            format!("https:{src}")
+        } else {
+            src.to_string()
+        };
+        let label = match attr(&attrs, "title") {
+            Some(title) if !title.trim().is_empty() => title.trim().to_string(),
+            _ => embed_label(&src).to_string(),
+        };
+        link_fallback(&src, &label)
+    });
+    let result = SHORTCODE_PATTERN.replace_all(&result, |caps: &Captures| {
+        let target = &caps[2];
+        match &caps[1] {
+            "youtube" => match youtube_id(target) {
+                Some(id) => youtube_fallback(id),
+                None if YOUTUBE_BARE_ID_PATTERN.is_match(target) => youtube_fallback(target),
+                None => link_fallback(target, "YouTubeで動画を見る"),
+            },
+            "twitter" | "tweet" | "x" => tweet_fallback(target),
+            _ => link_fallback(target, "スライドを見る"),
+        }
+    });
+    let result = STANDALONE_URL_PATTERN.replace_all(&result, |caps: &Captures| {
+        let url = &caps[1];
+        if let Some(id) = youtube_id(url) {
+            youtube_fallback(id)
+        } else if TWEET_URL_PATTERN.is_match(url) {
+            tweet_fallback(url)
+        } else {
+            caps[0].to_string()
+        }
+    });
+
+    result.into_owned()
+}
+
+fn youtube_id(url: &str) -> Option<&str> {
+    YOUTUBE_ID_PATTERN
+        .captures(url)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str())
+}
+
+fn youtube_fallback(id: &str) -> String {
+    let url = format!("https://www.youtube.com/watch?v={id}");
+    block(&format!(
+        "<p><a href=\"{url}\"><img src=\"https://i.ytimg.com/vi/{id}/hqdefault.jpg\" alt=\"YouTube動画のサムネイル\"></a><br><a href=\"{url}\">YouTubeで動画を見る</a></p>"
+    ))
+}
+
+fn tweet_fallback(url: &str) -> String {
+    block(&format!(
+        "<blockquote><p><a href=\"{}\">Xでポストを見る</a></p></blockquote>",
+        escape_attr(url)
+    ))
+}
+
+/// A plain link; anything that is not an http(s) URL is dropped
+fn link_fallback(url: &str, label: &str) -> String {
+    if !(url.starts_with("https://") || url.starts_with("http://")) {
+        return String::new();
+    }
+    block(&format!(
+        "<p><a href=\"{}\">{}</a></p>",
+        escape_attr(url),
+        escape_html(label)
+    ))
+}
+
+/// Surround generated HTML with blank lines so markdown treats it as an HTML block
+fn block(html: &str) -> String {
+    format!("\n\n{html}\n\n")
+}
+
+fn embed_label(src: &str) -> &'static str {
+    if src.contains("speakerdeck.com")
+        || src.contains("slideshare.net")
+        || src.contains("docs.google.com/presentation")
+    {
+        "スライドを見る"
+    } else if TWEET_URL_PATTERN.is_match(src) || src.contains("platform.twitter.com") {
+        "Xでポストを見る"
+    } else {
+        "埋め込みコンテンツを見る"
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rewrite_youtube_iframe() {
+        let markdown = r#"前文
+
+<iframe width="560" height="315" src="https://www.youtube.com/embed/dQw4w9WgXcQ" title="YouTube video player" frameborder="0" allowfullscreen></iframe>
+
+後文"#;
+        let result = rewrite_embeds(markdown);
+        assert!(!result.contains("<iframe"));
+        assert!(result.contains(r#"<a href="https://www.youtube.com/watch?v=dQw4w9WgXcQ">"#));
+        assert!(result.contains("https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"));
+        assert!(result.starts_with("前文"));
+        assert!(result.ends_with("後文"));
+    }
+
+    #[test]
+    fn test_rewrite_tweet_blockquote_removes_widget_script() {
+        let markdown = r#"<blockquote class="twitter-tweet"><p lang="ja" dir="ltr">新モデルを公開しました</p>&mdash; Example (@example) <a href="https://twitter.com/example/status/1234567890">July 14, 2025</a></blockquote> <script async src="https://platform.twitter.com/widgets.js" charset="utf-8"></script>"#;
+        let result = rewrite_embeds(markdown);
+        assert!(!result.contains("<script"));
+        assert!(!result.contains("twitter-tweet"));
+        assert!(result.contains("新モデルを公開しました"));
+        assert!(result.contains(
+            r#"<a href="https://twitter.com/example/status/1234567890">Xでポストを見る</a>"#
+        ));
+    }
+
+    #[test]
+    fn test_rewrite_slide_iframe_uses_title() {
+        let markdown = r#"<iframe class="speakerdeck-iframe" src="https://speakerdeck.com/player/abc123" title="生成AI活用事例"></iframe>"#;
+        let result = rewrite_embeds(markdown);
+        assert_eq!(
+            result.trim(),
+            r#"<p><a href="https://speakerdeck.com/player/abc123">生成AI活用事例</a></p>"#
+        );
+    }
+
+    #[test]
+    fn test_rewrite_shortcodes() {
+        let result = rewrite_embeds("動画はこちら @[youtube](dQw4w9WgXcQ) です");
+        assert!(result.contains("https://www.youtube.com/watch?v=dQw4w9WgXcQ"));
+
+        let result = rewrite_embeds("@[twitter](https://x.com/example/status/42)");
+        assert!(result.contains(r#"<a href="https://x.com/example/status/42">Xでポストを見る</a>"#));
+    }
+
+    #[test]
+    fn test_rewrite_standalone_urls() {
+        let markdown =
+            "紹介動画:\n\nhttps://youtu.be/dQw4w9WgXcQ\n\n参考: https://example.com/page";
+        let result = rewrite_embeds(markdown);
+        assert!(result.contains("https://i.ytimg.com/vi/dQw4w9WgXcQ/hqdefault.jpg"));
+        // URLs embedded in running text are left untouched
+        assert!(result.contains("参考: https://example.com/page"));
+    }
+
+    #[test]
+    fn test_rewrite_embeds_leaves_plain_markdown_untouched() {
+        let markdown = "# Title\n\nThis is [a link](https://example.com).";
+        assert_eq!(rewrite_embeds(markdown), markdown);
+    }
+
+    #[test]
+    fn test_rewrite_escapes_attribute_values() {
+        let markdown = r#"<iframe src="https://speakerdeck.com/player/abc?a=1&amp;b=2" title="A&amp;B 資料"></iframe>"#;
+        assert_eq!(
+            rewrite_embeds(markdown).trim(),
+            r#"<p><a href="https://speakerdeck.com/player/abc?a=1&amp;b=2">A&amp;B 資料</a></p>"#
+        );
+
+        let result = rewrite_embeds("https://x.com/example/status/42?s=\"onmouseover=\"");
+        assert!(
+            result.contains(r#"href="https://x.com/example/status/42?s=&quot;onmouseover=&quot;""#)
+        );
+    }
+
+    #[test]
+    fn test_rewrite_youtube_shortcode_without_valid_id() {
+        let result = rewrite_embeds("@[youtube](https://example.com/video)");
+        assert_eq!(
+            result.trim(),
+            r#"<p><a href="https://example.com/video">YouTubeで動画を見る</a></p>"#
+        );
+        assert_eq!(rewrite_embeds("@[youtube](javascript:alert)").trim(), "");
+    }
+
+    #[test]
+    fn test_rewrite_embeds_skips_code() {
+        let markdown = "```\nhttps://youtu.be/dQw4w9WgXcQ\n<iframe src=\"https://speakerdeck.com/player/abc\"></iframe>\n```\n\n`@[youtube](dQw4w9WgXcQ)` と書きます";
+        assert_eq!(rewrite_embeds(markdown), markdown);
+    }
+
+    #[test]
+    fn test_rewrite_embeds_keeps_literal_placeholders() {
+        let markdown = "\x007\x00 \x0099999999999999999999\x00 `code`";
+        assert_eq!(rewrite_embeds(markdown), markdown);
+    }
+}
@@ -0,0 +1,300 @@
+use std::fmt;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+
+/// Exit code for an invalid config, an unusable state file and other setup failures
+pub const EXIT_FAILURE: i32 = 1;
+/// Exit code for invalid command line arguments
+pub const EXIT_USAGE: i32 = 2;
+pub const EXIT_NETWORK: i32 = 3;
+pub const EXIT_EXTRACTION: i32 = 4;
+pub const EXIT_OUTPUT: i32 = 5;
+pub const EXIT_UNHEALTHY: i32 = 6;
+
+/// What a request was made for
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Stage {
+    Listing,
+    Sitemap,
+    Article,
+    Media,
+    Webhook,
+    WebSub,
+}
+
+impl fmt::Display for Stage {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Stage::Listing => "listing",
+            Stage::Sitemap => "sitemap",
+            Stage::Article => "article",
+            Stage::Media => "media",
+            Stage::Webhook => "webhook",
+            Stage::WebSub => "websub",
+        })
+    }
+}
+
+/// Everything that can fail a feed or a command, from config to delivery
+#[derive(Debug)]
+pub enum Error {
+    /// The server answered with an error status
+    Http {
+        stage: Stage,
+        url: String,
+        status: u16,
+    },
+    /// No complete response within the client timeout
+    Timeout { stage: Stage, url: String },
+    /// Connection, TLS or protocol failure
+    Network {
+        stage: Stage,
+        url: String,
+        source: reqwest::Error,
+    },
+    /// The response was larger than we accept
+    TooLarge {
+        stage: Stage,
+        url: String,
+        bytes: u64,
+        limit: usize,
+    },
+    /// A listing page had no articles we could read
+    NoListingData { url: String },
+    /// An article page had no body we could read
+    NoContent { url: String },
+    /// Neither the article page nor the listing had a date we understand
+    DateUnparseable { url: String, value: String },
+    /// A feed document could not be serialized
+    FeedWrite {
+        format: &'static str,
+        reason: String,
+    },
+    Io {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+    /// The crawl failed its health checks; the previous outputs were kept
+    Unhealthy { feed: String, failures: Vec<String> },
+    /// A downloaded body was not in a format we can read, e.g. broken gzip
+    Decode { url: String, reason: String },
+    /// The digest email could not be built or delivered
+    Mail { reason: String },
+    /// A state or checkpoint file we wrote earlier could not be read back
+    Corrupt { path: PathBuf, reason: String },
+    /// Another process holds the lock on the state file
+    Locked { path: PathBuf, pid: String },
+    /// The config or a value in it is invalid
+    Config { reason: String },
+    /// Invalid command line arguments
+    Usage { reason: String },
+    /// The HTTP server could not listen on its address
+    Listen {
+        addr: SocketAddr,
+        source: std::io::Error,
+    },
+    /// The shutdown signal handler could not be installed
+    Signal { source: std::io::Error },
+}
+
+impl Error {
+    /// Classify a failed request; timeouts are told apart from other failures
+    pub fn request(stage: Stage, url: &str, source: reqwest::Error) -> Self {
+        let url = url.to_string();
+        if source.is_timeout() {
+            Error::Timeout { stage, url }
+        } else {
+            Error::Network { stage, url, source }
+        }
+    }
+
+    /// For `map_err` on file system calls
+    pub fn io(path: &Path) -> impl FnOnce(std::io::Error) -> Self + '_ {
+        move |source| Error::Io {
+            path: path.to_path_buf(),
+            source,
+        }
+    }
+
+    /// For `map_err` on feed serialization
+    pub fn feed_write<E: fmt::Display>(format: &'static str) -> impl FnOnce(E) -> Self {
+        move |e| Error::FeedWrite {
+            format,
+            reason: e.to_string(),
+        }
+    }
+
+    /// An invalid config value or option, described by `reason`
+    pub fn config(reason: impl Into<String>) -> Self {
+        Error::Config {
+            reason: reason.into(),
+        }
+    }
+
+    /// Invalid command line arguments, described by `reason`
+    pub fn usage(reason: impl Into<String>) -> Self {
+        Error::Usage {
+            reason: reason.into(),
+        }
+    }
+
+    /// For `map_err` on building and sending the digest email
+    pub fn mail<E: fmt::Display>(e: E) -> Self {
+        Error::Mail {
+            reason: e.to_string(),
+        }
+    }
+
+    /// Short label for the run report and metrics, e.g. `http_status`
+    pub fn reason(&self) -> &'static str {
+        match self {
+            Error::Http { .. } => "http_status",
+            Error::Timeout { .. } => "timeout",
+            Error::Network { .. } => "network",
+            Error::TooLarge { .. } => "too_large",
+            Error::NoListingData { .. } => "no_listing_data",
+            Error::NoContent { .. } => "no_content",
+            Error::DateUnparseable { .. } => "date_unparseable",
+            Error::FeedWrite { .. } => "feed_write",
+            Error::Io { .. } => "io",
+            Error::Unhealthy { .. } => "unhealthy",
+            Error::Decode { .. } => "decode",
+            Error::Mail { .. } => "mail",
+            Error::Corrupt { .. } => "corrupt",
+            Error::Locked { .. } => "locked",
+            Error::Config { .. } => "config",
+            Error::Usage { .. } => "usage",
+            Error::Listen { .. } => "listen",
+            Error::Signal { .. } => "signal",
+        }
+    }
+
+    /// Process exit code for this class of failure
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            Error::Http { .. }
+            | Error::Timeout { .. }
+            | Error::Network { .. }
+            | Error::TooLarge { .. } => EXIT_NETWORK,
+            Error::NoListingData { .. }
+            | Error::NoContent { .. }
+            | Error::DateUnparseable { .. }
+            | Error::Decode { .. } => EXIT_EXTRACTION,
+            Error::FeedWrite { .. } | Error::Io { .. } | Error::Mail { .. } => EXIT_OUTPUT,
+            Error::Unhealthy { .. } => EXIT_UNHEALTHY,
+            Error::Corrupt { .. }
+            | Error::Locked { .. }
+            | Error::Config { .. }
+            | Error::Listen { .. }
+            | Error::Signal { .. } => EXIT_FAILURE,
+            Error::Usage { .. } => EXIT_USAGE,
+        }
+    }
+}
+
+impl fmt::Display for Error {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Error::Http { stage, url, status } => {
+                write!(f, "{stage} request to {url} returned HTTP {status}")
+            }
+            Error::Timeout { stage, url } => write!(f, "{stage} request to {url} timed out"),
+            Error::Network { stage, url, source } => {
+                write!(f, "{stage} request to {url} failed: {source}")
+            }
+            Error::TooLarge {
+                stage,
+                url,
+                bytes,
+                limit,
+            } => write!(f, "{stage} {url} is {bytes} bytes (limit {limit})"),
+            Error::NoListingData { url } => write!(f, "No articles found on listing page {url}"),
+            Error::NoContent { url } => write!(f, "No article content found on {url}"),
+            Error::DateUnparseable { url, value } => {
+                write!(f, "Unparseable publication date {value:?} for {url}")
+            }
+            Error::FeedWrite { format, reason } => {
+                write!(f, "Failed to generate {format} feed: {reason}")
+            }
+            Error::Io { path, source } => write!(f, "{}: {source}", path.display()),
+            Error::Unhealthy { feed, failures } => write!(
+                f,
+                "Feed {feed} failed its health checks: {}",
+                failures.join("; ")
+            ),
+            Error::Decode { url, reason } => write!(f, "Failed to decode {url}: {reason}"),
+            Error::Mail { reason } => write!(f, "Failed to send digest: {reason}"),
+            Error::Corrupt { path, reason } => write!(f, "Invalid {}: {reason}", path.display()),
+            Error::Locked { path, pid } => write!(
+                f,
+                "Another run (pid {pid}) holds {}; remove it if it is stale",
+                path.display()
+            ),
+            Error::Config { reason } | Error::Usage { reason } => f.write_str(reason),
+            Error::Listen { addr, source } => write!(f, "Failed to listen on {addr}: {source}"),
+            Error::Signal { source } => write!(f, "Failed to install signal handler: {source}"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Network { source, .. } => Some(source),
+            Error::Io { source, .. } | Error::Listen { source, .. } | Error::Signal { source } => {
+                Some(source)
+            }
+            _ => None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_exit_codes_by_failure_class() {
+        let http = Error::Http {
+            stage: Stage::Listing,
+            url: "https://ledge.ai/".to_string(),
+            status: 503,
+        };
+        assert_eq!(http.exit_code(), EXIT_NETWORK);
+        assert_eq!(
+            http.to_string(),
+            "listing request to https://ledge.ai/ returned HTTP 503"
+        );
+
+        let no_content = Error::NoContent {
+            url: "https://ledge.ai/articles/a".to_string(),
+        };
+        assert_eq!(no_content.exit_code(), EXIT_EXTRACTION);
+
+        let io = Error::io(Path::new("rss.xml"))(std::io::Error::new(
+            std::io::ErrorKind::PermissionDenied,
+            "denied",
+        ));
+        assert_eq!(io.exit_code(), EXIT_OUTPUT);
+        assert_eq!(io.to_string(), "rss.xml: denied");
+
+        let unhealthy = Error::Unhealthy {
+            feed: "ledge_ai".to_string(),
+            failures: vec!["Only 0 items, expected at least 1".to_string()],
+        };
+        assert_eq!(unhealthy.exit_code(), EXIT_UNHEALTHY);
+
+        let mail = Error::mail("connection refused");
+        assert_eq!(mail.exit_code(), EXIT_OUTPUT);
+        assert_eq!(mail.reason(), "mail");
+
+        let config = Error::config("Unknown source: example");
+        assert_eq!(config.exit_code(), EXIT_FAILURE);
+        assert_eq!(config.to_string(), "Unknown source: example");
+
+        assert_eq!(
+            Error::usage("Unknown argument: --x").exit_code(),
+            EXIT_USAGE
+        );
+    }
+}
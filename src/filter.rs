@@ -0,0 +1,339 @@
+use crate::article_extractor::strip_markdown;
+use crate::config::{FilterConfig, FilterRule};
+use crate::error::Error;
+use crate::rss_item::RssItem;
+use regex::Regex;
+use std::fmt;
+
+/// Compiled include/exclude rules applied to extracted items
+pub struct ItemFilter {
+    include: Vec<CompiledRule>,
+    exclude: Vec<CompiledRule>,
+}
+
+struct CompiledRule {
+    rule: FilterRule,
+    regex: Option<Regex>,
+}
+
+/// Items dropped by each rule, in rule order
+#[derive(Debug, Default, PartialEq)]
+pub struct FilterReport {
+    pub dropped: Vec<DroppedItems>,
+}
+
+#[derive(Debug, PartialEq)]
+pub struct DroppedItems {
+    /// Human readable rule, e.g. `exclude keyword "PR"`
+    pub rule: String,
+    pub titles: Vec<String>,
+}
+
+impl ItemFilter {
+    pub fn new(config: &FilterConfig) -> Result<Self, Error> {
+        let compile = |rules: &[FilterRule]| -> Result<Vec<CompiledRule>, Error> {
+            rules
+                .iter()
+                .map(|rule| {
+                    let regex = match rule {
+                        FilterRule::Regex(pattern) => Some(Regex::new(pattern).map_err(|e| {
+                            Error::config(format!("Invalid filter regex '{pattern}': {e}"))
+                        })?),
+                        _ => None,
+                    };
+                    Ok(CompiledRule {
+                        rule: rule.clone(),
+                        regex,
+                    })
+                })
+                .collect()
+        };
+
+        Ok(Self {
+            include: compile(&config.include)?,
+            exclude: compile(&config.exclude)?,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.include.is_empty() && self.exclude.is_empty()
+    }
+
+    /// Keep items matching any include rule (or all when there are none) and
+    /// no exclude rule. Each dropped item is attributed to the first rule that
+    /// rejected it.
+    pub fn apply(&self, items: Vec<RssItem>) -> (Vec<RssItem>, FilterReport) {
+        let mut report = FilterReport::default();
+        let mut kept = Vec::new();
+
+        for item in items {
+            match self.rejected_by(&item) {
+                Some(rule) => report.record(rule, item.title),
+                None => kept.push(item),
+            }
+        }
+
+        (kept, report)
+    }
+
+    /// Whether [`Self::apply`] would keep the item
+    pub fn accepts(&self, item: &RssItem) -> bool {
+        self.rejected_by(item).is_none()
+    }
+
+    /// The first rule that rejects the item, if any
+    fn rejected_by(&self, item: &RssItem) -> Option<String> {
+        let text = body_text(item);
+        if !self.include.is_empty() && !self.include.iter().any(|rule| rule.matches(item, &text)) {
+            Some("include (no rule matched)".to_string())
+        } else {
+            self.exclude
+                .iter()
+                .find(|rule| rule.matches(item, &text))
+                .map(|rule| format!("exclude {}", rule.rule))
+        }
+    }
+}
+
+impl CompiledRule {
+    /// Whether the rule holds for `item`; `text` is its body without markup
+    fn matches(&self, item: &RssItem, text: &str) -> bool {
+        match &self.rule {
+            FilterRule::Keyword(keyword) => {
+                let keyword = keyword.to_lowercase();
+                item.title.to_lowercase().contains(&keyword)
+                    || text.to_lowercase().contains(&keyword)
+            }
+            FilterRule::Regex(_) => self
+                .regex
+                .as_ref()
+                .is_some_and(|regex| regex.is_match(&item.title) || regex.is_match(text)),
+            FilterRule::Category(category) => item.category.as_ref() == Some(category),
+            FilterRule::Tag(tag) => item.tags.contains(tag),
+            FilterRule::Author(author) => item.author.as_ref() == Some(author),
+            FilterRule::MinLength(min) => body_length(item) >= *min,
+            FilterRule::MaxLength(max) => body_length(item) <= *max,
+        }
+    }
+}
+
+/// Visible article text, so link targets and attributes never match
+fn body_text(item: &RssItem) -> String {
+    if item.markdown.is_empty() {
+        strip_markdown(&item.description)
+    } else {
+        strip_markdown(&item.markdown)
+    }
+}
+
+/// Length of the article body in characters, markup excluded when known
+fn body_length(item: &RssItem) -> usize {
+    item.reading_stats
+        .as_ref()
+        .map(|stats| stats.char_count)
+        .unwrap_or_else(|| item.description.chars().count())
+}
+
+impl FilterReport {
+    fn record(&mut self, rule: String, title: String) {
+        match self.dropped.iter_mut().find(|dropped| dropped.rule == rule) {
+            Some(dropped) => dropped.titles.push(title),
+            None => self.dropped.push(DroppedItems {
+                rule,
+                titles: vec![title],
+            }),
+        }
+    }
+
+    pub fn total(&self) -> usize {
+        self.dropped
+            .iter()
+            .map(|dropped| dropped.titles.len())
+            .sum()
+    }
+}
+
+impl fmt::Display for FilterRule {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FilterRule::Keyword(keyword) => write!(f, "keyword {keyword:?}"),
+            FilterRule::Regex(pattern) => write!(f, "regex /{pattern}/"),
+            FilterRule::Category(category) => write!(f, "category {category:?}"),
+            FilterRule::Tag(tag) => write!(f, "tag {tag:?}"),
+            FilterRule::Author(author) => write!(f, "author {author:?}"),
+            FilterRule::MinLength(min) => write!(f, "at least {min} chars"),
+            FilterRule::MaxLength(max) => write!(f, "at most {max} chars"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reading_stats::ReadingStats;
+
+    fn item(title: &str, description: &str) -> RssItem {
+        RssItem {
+            title: title.to_string(),
+            description: description.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn titles(items: &[RssItem]) -> Vec<&str> {
+        items.iter().map(|item| item.title.as_str()).collect()
+    }
+
+    #[test]
+    fn test_filter_include_and_exclude_keywords() {
+        let filter = ItemFilter::new(&FilterConfig {
+            include: vec![FilterRule::Keyword("ai".to_string())],
+            exclude: vec![FilterRule::Keyword("PR".to_string())],
+        })
+        .unwrap();
+
+        let (kept, report) = filter.apply(vec![
+            item("生成AIの最新動向", ""),
+            item("量子コンピュータの最新動向", ""),
+            item("【PR】AI導入セミナー", ""),
+        ]);
+
+        assert_eq!(titles(&kept), vec!["生成AIの最新動向"]);
+        assert_eq!(report.total(), 2);
+        assert_eq!(report.dropped[0].rule, "include (no rule matched)");
+        assert_eq!(report.dropped[1].rule, "exclude keyword \"PR\"");
+        assert_eq!(report.dropped[1].titles, vec!["【PR】AI導入セミナー"]);
+    }
+
+    #[test]
+    fn test_filter_regex_matches_title_and_body() {
+        let filter = ItemFilter::new(&FilterConfig {
+            include: Vec::new(),
+            exclude: vec![FilterRule::Regex(r"^【.+】|ウェビナー".to_string())],
+        })
+        .unwrap();
+
+        let (kept, _) = filter.apply(vec![
+            item("【速報】新モデル発表", ""),
+            item("通常記事", "<p>ウェビナーのお知らせ</p>"),
+            item("通常記事2", "<p>本文</p>"),
+        ]);
+        assert_eq!(titles(&kept), vec!["通常記事2"]);
+    }
+
+    #[test]
+    fn test_filter_ignores_link_targets_and_markup() {
+        let filter = ItemFilter::new(&FilterConfig {
+            include: Vec::new(),
+            exclude: vec![
+                FilterRule::Keyword("sponsored".to_string()),
+                FilterRule::Regex("campaign".to_string()),
+            ],
+        })
+        .unwrap();
+
+        let (kept, _) = filter.apply(vec![
+            RssItem {
+                title: "リンクだけ".to_string(),
+                markdown: "詳細は[こちら](https://example.com/sponsored?utm_campaign=x)"
+                    .to_string(),
+                ..Default::default()
+            },
+            item(
+                "属性だけ",
+                r#"<p><a href="https://example.com/sponsored" class="campaign">記事</a></p>"#,
+            ),
+            RssItem {
+                title: "本文".to_string(),
+                markdown: "Sponsored by Example".to_string(),
+                ..Default::default()
+            },
+        ]);
+        assert_eq!(titles(&kept), vec!["リンクだけ", "属性だけ"]);
+    }
+
+    #[test]
+    fn test_filter_category_tag_and_author() {
+        let filter = ItemFilter::new(&FilterConfig {
+            include: vec![
+                FilterRule::Category("ビジネス".to_string()),
+                FilterRule::Tag("生成AI".to_string()),
+            ],
+            exclude: vec![FilterRule::Author("広告担当".to_string())],
+        })
+        .unwrap();
+
+        let items = vec![
+            RssItem {
+                title: "A".to_string(),
+                category: Some("ビジネス".to_string()),
+                ..Default::default()
+            },
+            RssItem {
+                title: "B".to_string(),
+                tags: vec!["生成AI".to_string()],
+                author: Some("広告担当".to_string()),
+                ..Default::default()
+            },
+            RssItem {
+                title: "C".to_string(),
+                category: Some("サイエンス".to_string()),
+                ..Default::default()
+            },
+        ];
+
+        let (kept, report) = filter.apply(items);
+        assert_eq!(titles(&kept), vec!["A"]);
+        assert_eq!(report.dropped[0].rule, "exclude author \"広告担当\"");
+    }
+
+    #[test]
+    fn test_filter_length_uses_reading_stats() {
+        let filter = ItemFilter::new(&FilterConfig {
+            include: Vec::new(),
+            exclude: vec![FilterRule::MaxLength(299), FilterRule::MinLength(5001)],
+        })
+        .unwrap();
+
+        let with_chars = |title: &str, char_count: usize| RssItem {
+            title: title.to_string(),
+            reading_stats: Some(ReadingStats {
+                char_count,
+                word_count: 0,
+                minutes: 1,
+            }),
+            ..Default::default()
+        };
+
+        let (kept, report) = filter.apply(vec![
+            with_chars("short", 100),
+            with_chars("normal", 1000),
+            with_chars("long", 10000),
+        ]);
+        assert_eq!(titles(&kept), vec!["normal"]);
+        assert_eq!(report.dropped[0].rule, "exclude at most 299 chars");
+        assert_eq!(report.dropped[1].rule, "exclude at least 5001 chars");
+
+        // The same rules keep exactly the dropped items when used as includes
+        let filter = ItemFilter::new(&FilterConfig {
+            include: vec![FilterRule::MaxLength(299), FilterRule::MinLength(5001)],
+            exclude: Vec::new(),
+        })
+        .unwrap();
+        let (kept, _) = filter.apply(vec![
+            with_chars("short", 100),
+            with_chars("normal", 1000),
+            with_chars("long", 10000),
+        ]);
+        assert_eq!(titles(&kept), vec!["short", "long"]);
+    }
+
+    #[test]
+    fn test_filter_rejects_invalid_regex() {
+        let result = ItemFilter::new(&FilterConfig {
+            include: Vec::new(),
+            exclude: vec![FilterRule::Regex("(".to_string())],
+        });
+        assert!(result.is_err());
+    }
+}
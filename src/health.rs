@@ -0,0 +1,142 @@
+use crate::config::HealthConfig;
+use crate::rss_item::RssItem;
+use crate::run_report::{ArticleReport, ArticleStatus};
+
+/// Describe every check the crawled items fail; empty when the crawl looks healthy
+pub fn check_health(
+    config: &HealthConfig,
+    items: &[RssItem],
+    articles: &[ArticleReport],
+) -> Vec<String> {
+    let mut failures = Vec::new();
+
+    if items.len() < config.min_items {
+        failures.push(format!(
+            "Only {} items, expected at least {}",
+            items.len(),
+            config.min_items
+        ));
+    }
+
+    if !items.is_empty() {
+        let total: usize = items.iter().map(|item| item.markdown.chars().count()).sum();
+        let average = total / items.len();
+        if average < config.min_average_chars {
+            failures.push(format!(
+                "Average body is {average} chars, expected at least {}",
+                config.min_average_chars
+            ));
+        }
+    }
+
+    let processed: Vec<&ArticleReport> = articles
+        .iter()
+        .filter(|article| article.status != ArticleStatus::Failed)
+        .collect();
+    if !processed.is_empty() {
+        let fallback = processed
+            .iter()
+            .filter(|article| article.date_strategy.is_none())
+            .count();
+        let ratio = fallback as f64 / processed.len() as f64;
+        if ratio > config.max_fallback_date_ratio {
+            failures.push(format!(
+                "{fallback} of {} articles use the listing date, expected at most {:.0}%",
+                processed.len(),
+                config.max_fallback_date_ratio * 100.0
+            ));
+        }
+    }
+
+    for item in items {
+        let body = item.markdown.trim_end();
+        if let Some(ending) = config
+            .suspicious_endings
+            .iter()
+            .find(|ending| body.ends_with(ending.as_str()))
+        {
+            failures.push(format!("{} ends with {ending:?}", item.link));
+        }
+    }
+
+    failures
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn item(slug: &str, markdown: &str) -> RssItem {
+        RssItem {
+            title: slug.to_string(),
+            link: format!("https://ledge.ai/articles/{slug}"),
+            markdown: markdown.to_string(),
+            ..Default::default()
+        }
+    }
+
+    fn article(slug: &str, date_strategy: Option<&str>) -> ArticleReport {
+        let mut article = ArticleReport::new(&format!("https://ledge.ai/articles/{slug}"), slug);
+        article.date_strategy = date_strategy.map(str::to_string);
+        article
+    }
+
+    #[test]
+    fn test_healthy_crawl() {
+        let items = vec![
+            item("a", &"本文".repeat(300)),
+            item("b", &"本文".repeat(300)),
+        ];
+        let articles = vec![article("a", Some("nuxt")), article("b", None)];
+        assert!(check_health(&HealthConfig::default(), &items, &articles).is_empty());
+    }
+
+    #[test]
+    fn test_too_few_items_and_short_bodies() {
+        let config = HealthConfig {
+            min_items: 3,
+            ..Default::default()
+        };
+        let items = vec![item("a", "短い"), item("b", &"本文".repeat(300))];
+        assert_eq!(
+            check_health(&config, &items, &[]),
+            vec![
+                "Only 2 items, expected at least 3",
+                "Average body is 301 chars, expected at least 500"
+            ]
+        );
+        assert_eq!(
+            check_health(&config, &[], &[]),
+            vec!["Only 0 items, expected at least 3"]
+        );
+    }
+
+    #[test]
+    fn test_fallback_date_ratio_ignores_failed_articles() {
+        let items = vec![item("a", &"本文".repeat(300))];
+        let mut failed = article("c", None);
+        failed.status = ArticleStatus::Failed;
+        let articles = vec![article("a", None), article("b", None), failed];
+        assert_eq!(
+            check_health(&HealthConfig::default(), &items, &articles),
+            vec!["2 of 2 articles use the listing date, expected at most 50%"]
+        );
+    }
+
+    #[test]
+    fn test_suspicious_endings() {
+        let body = "本文".repeat(300);
+        let items = vec![
+            item("a", &format!("{body}[リンク]{{target=\n")),
+            item("b", &format!("{body}\\")),
+            item("c", &body),
+        ];
+        assert_eq!(
+            check_health(&HealthConfig::default(), &items, &[]),
+            vec![
+                "https://ledge.ai/articles/a ends with \"{target=\"",
+                "https://ledge.ai/articles/b ends with \"\\\\\""
+            ]
+        );
+    }
+}
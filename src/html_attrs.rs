@@ -0,0 +1,95 @@
+use crate::rss_generator::escape_attr;
+use once_cell::sync::Lazy;
+use regex::Regex;
+
+// `name="value"`, `name='value'`, `name=value` or a bare boolean `name`
+static ATTR_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"([^\s"'<>/=]+)(?:\s*=\s*(?:"([^"]*)"|'([^']*)'|([^\s"'<>`]+)))?"#).unwrap()
+});
+
+/// Attributes of a start tag (e.g. `<img src=a.png alt='図' hidden>`) or of the
+/// text between its name and `>`, with lowercase names and decoded values.
+/// Boolean attributes get an empty value.
+pub fn parse_attributes(tag: &str) -> Vec<(String, String)> {
+    let attrs = match tag.strip_prefix('<') {
+        Some(rest) => rest.trim_start_matches(|c: char| !c.is_whitespace() && c != '>' && c != '/'),
+        None => tag,
+    };
+    ATTR_PATTERN
+        .captures_iter(attrs)
+        .map(|caps| {
+            let value = caps
+                .get(2)
+                .or(caps.get(3))
+                .or(caps.get(4))
+                .map_or("", |m| m.as_str());
+            (caps[1].to_ascii_lowercase(), decode_entities(value))
+        })
+        .collect()
+}
+
+pub fn attr<'a>(attrs: &'a [(String, String)], name: &str) -> Option<&'a str> {
+    attrs
+        .iter()
+        .find(|(key, _)| key == name)
+        .map(|(_, value)| value.as_str())
+}
+
+/// Render a start tag with double-quoted, escaped attribute values
+pub fn render_tag(name: &str, attrs: &[(String, String)]) -> String {
+    let rendered: Vec<String> = attrs
+        .iter()
+        .map(|(key, value)| format!("{key}=\"{}\"", escape_attr(value)))
+        .collect();
+    if rendered.is_empty() {
+        format!("<{name}>")
+    } else {
+        format!("<{name} {}>", rendered.join(" "))
+    }
+}
+
+fn decode_entities(value: &str) -> String {
+    value
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&#x27;", "'")
+        .replace("&apos;", "'")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&amp;", "&")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_attributes_quoting_styles() {
+        let attrs = parse_attributes(
+            r#"<img SRC=https://example.com/a.png?w=1&amp;h=2 alt='図 "1"' data-x="a&quot;b" hidden />"#,
+        );
+        assert_eq!(
+            attrs,
+            vec![
+                (
+                    "src".to_string(),
+                    "https://example.com/a.png?w=1&h=2".to_string()
+                ),
+                ("alt".to_string(), "図 \"1\"".to_string()),
+                ("data-x".to_string(), "a\"b".to_string()),
+                ("hidden".to_string(), String::new()),
+            ]
+        );
+        assert_eq!(attr(&attrs, "hidden"), Some(""));
+        assert_eq!(attr(&attrs, "title"), None);
+    }
+
+    #[test]
+    fn test_render_tag_escapes_values() {
+        let attrs = parse_attributes(r#" src="a.png?x=1&amp;y=2" alt='"図"' hidden"#);
+        assert_eq!(
+            render_tag("img", &attrs),
+            r#"<img src="a.png?x=1&amp;y=2" alt="&quot;図&quot;" hidden="">"#
+        );
+    }
+}
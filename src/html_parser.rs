@@ -1,43 +1,66 @@
+use crate::error::Error;
+use chrono::{DateTime, Utc};
 use regex::Regex;
+use reqwest::Url;
 use scraper::{Html, Selector};
 
+pub const LEDGE_AI_BASE_URL: &str = "https://ledge.ai";
+
 #[derive(Debug, PartialEq)]
 pub struct ArticleInfo {
     pub title: String,
     pub url: String,
     pub date: String,
+    /// Category shown next to the article on the listing page, if any
+    pub category: Option<String>,
+    /// Last modification time advertised by a sitemap
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+/// Links on a listing page that lead to more listings
+#[derive(Debug, Default, PartialEq)]
+pub struct ListingLinks {
+    pub next_page: Option<String>,
+    pub categories: Vec<String>,
+    pub tags: Vec<String>,
 }
 
-/// Remove category and date prefix from article title
-/// e.g., "ビジネス2026/1/14 [WED]Actual Title" -> "Actual Title"
-fn clean_article_title(title: &str) -> String {
+/// Remove category and date prefix from article title, returning the category
+/// e.g., "ビジネス2026/1/14 [WED]Actual Title" -> (Some("ビジネス"), "Actual Title")
+fn clean_article_title(title: &str) -> (Option<String>, String) {
     // Pattern matches: optional category name + date in format YYYY/M/D [DAY]
     // Categories include: ビジネス, エンジニアリング, etc.
     let pattern = Regex::new(
-        r"^(?:ビジネス|エンジニアリング|テクノロジー|サイエンス|社会|政治|経済|カルチャー|スポーツ|エンタメ|ライフ|その他)??\d{4}/\d{1,2}/\d{1,2}\s*\[[A-Z]{3}\]"
+        r"^(ビジネス|エンジニアリング|テクノロジー|サイエンス|社会|政治|経済|カルチャー|スポーツ|エンタメ|ライフ|その他)??\d{4}/\d{1,2}/\d{1,2}\s*\[[A-Z]{3}\]"
     ).unwrap();
 
-    pattern.replace(title, "").trim().to_string()
+    let category = pattern
+        .captures(title)
+        .and_then(|caps| caps.get(1))
+        .map(|m| m.as_str().to_string());
+    (category, pattern.replace(title, "").trim().to_string())
 }
 
-pub fn parse_articles_from_html(
-    html: &str,
-) -> Result<Vec<ArticleInfo>, Box<dyn std::error::Error>> {
+/// Articles listed on a page; `page_url` is only used to describe failures
+pub fn parse_articles_from_html(html: &str, page_url: &str) -> Result<Vec<ArticleInfo>, Error> {
     // First try to extract from Nuxt.js __NUXT__ object
-    if let Ok(articles) = extract_from_nuxt_data(html) {
-        if !articles.is_empty() {
-            return Ok(articles);
-        }
+    if let Some(articles) = extract_from_nuxt_data(html) {
+        return Ok(articles);
     }
 
     // Fallback to static HTML parsing
-    let static_articles = extract_from_static_html(html)?;
+    let static_articles = extract_from_static_html(html);
+    if static_articles.is_empty() {
+        return Err(Error::NoListingData {
+            url: page_url.to_string(),
+        });
+    }
     Ok(static_articles)
 }
 
-fn extract_from_nuxt_data(html: &str) -> Result<Vec<ArticleInfo>, Box<dyn std::error::Error>> {
+fn extract_from_nuxt_data(html: &str) -> Option<Vec<ArticleInfo>> {
     let document = Html::parse_document(html);
-    let script_selector = Selector::parse("script")?;
+    let script_selector = Selector::parse("script").unwrap();
 
     for script_element in document.select(&script_selector) {
         let script_text = script_element.text().collect::<String>();
@@ -47,13 +70,13 @@ fn extract_from_nuxt_data(html: &str) -> Result<Vec<ArticleInfo>, Box<dyn std::e
             // Try to extract any JSON objects containing article data
             if let Some(articles) = extract_articles_from_any_json(&script_text) {
                 if !articles.is_empty() {
-                    return Ok(articles);
+                    return Some(articles);
                 }
             }
         }
     }
 
-    Err("Nuxt data not found".into())
+    None
 }
 
 fn extract_articles_from_any_json(script_text: &str) -> Option<Vec<ArticleInfo>> {
@@ -62,6 +85,8 @@ fn extract_articles_from_any_json(script_text: &str) -> Option<Vec<ArticleInfo>>
     // Look for patterns like "title":"something", "slug":"something"
     let title_pattern = regex::Regex::new(r#""title":"([^"]+)""#).ok()?;
     let slug_pattern = regex::Regex::new(r#""slug":"([^"]+)""#).ok()?;
+    let date_pattern =
+        regex::Regex::new(r#""(?:publishedAt|published_at)":"(\d{4}-\d{2}-\d{2}T[^"]+)""#).ok()?;
 
     // Find all title matches
     let title_matches: Vec<_> = title_pattern.find_iter(script_text).collect();
@@ -79,18 +104,33 @@ fn extract_articles_from_any_json(script_text: &str) -> Option<Vec<ArticleInfo>>
 
             if let Some(slug_caps) = slug_pattern.captures(search_area) {
                 let slug = slug_caps.get(1)?.as_str();
-                let url = format!("https://ledge.ai/articles/{slug}");
+                let url = format!("{LEDGE_AI_BASE_URL}/articles/{slug}");
+                if articles
+                    .iter()
+                    .any(|article: &ArticleInfo| article.url == url)
+                {
+                    continue;
+                }
 
+                // Only trust a publication date that belongs to this article,
+                // i.e. appears before the next title
+                let own_area = match title_pattern.find(&search_area[title_match.len()..]) {
+                    Some(next_title) => &search_area[..title_match.len() + next_title.start()],
+                    None => search_area,
+                };
+                let date = date_pattern
+                    .captures(own_area)
+                    .map(|caps| caps[1].to_string())
+                    .unwrap_or_else(|| "2025/01/14 [MON]".to_string()); // Fallback date
+
+                let (category, title) = clean_article_title(title);
                 articles.push(ArticleInfo {
-                    title: clean_article_title(title),
+                    title,
                     url,
-                    date: "2025/01/14 [MON]".to_string(), // Fallback date
+                    date,
+                    category,
+                    lastmod: None,
                 });
-
-                if articles.len() >= 10 {
-                    // Limit to prevent too many duplicates
-                    break;
-                }
             }
         }
     }
@@ -102,7 +142,7 @@ fn extract_articles_from_any_json(script_text: &str) -> Option<Vec<ArticleInfo>>
     }
 }
 
-fn extract_from_static_html(html: &str) -> Result<Vec<ArticleInfo>, Box<dyn std::error::Error>> {
+fn extract_from_static_html(html: &str) -> Vec<ArticleInfo> {
     let document = Html::parse_document(html);
     let mut articles = Vec::new();
 
@@ -128,15 +168,18 @@ fn extract_from_static_html(html: &str) -> Result<Vec<ArticleInfo>, Box<dyn std:
                         if !title.is_empty() && title.len() > 5 {
                             // Skip very short titles
                             let url = if href.starts_with('/') {
-                                format!("https://ledge.ai{href}")
+                                format!("{LEDGE_AI_BASE_URL}{href}")
                             } else {
                                 href.to_string()
                             };
 
+                            let (category, title) = clean_article_title(&title);
                             articles.push(ArticleInfo {
-                                title: clean_article_title(&title),
+                                title,
                                 url,
                                 date: "2025/01/14 [MON]".to_string(), // Fallback date
+                                category,
+                                lastmod: None,
                             });
                             found_count += 1;
                         }
@@ -154,7 +197,88 @@ fn extract_from_static_html(html: &str) -> Result<Vec<ArticleInfo>, Box<dyn std:
     articles.sort_by(|a, b| a.url.cmp(&b.url));
     articles.dedup_by(|a, b| a.url == b.url);
 
-    Ok(articles)
+    articles
+}
+
+/// Find the next pagination page and category/tag listings linked from a listing page
+pub fn parse_listing_links(html: &str, page_url: &str) -> ListingLinks {
+    let Ok(base) = Url::parse(page_url) else {
+        return ListingLinks::default();
+    };
+    let document = Html::parse_document(html);
+
+    let hrefs = |selector: &str| -> Vec<Url> {
+        Selector::parse(selector)
+            .map(|selector| {
+                document
+                    .select(&selector)
+                    .filter_map(|element| element.value().attr("href"))
+                    .filter_map(|href| base.join(href).ok())
+                    .filter(|url| url.host_str() == base.host_str())
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+
+    let next_page = hrefs("link[rel=\"next\"], a[rel=\"next\"]")
+        .into_iter()
+        .next()
+        .or_else(|| next_page_from_nuxt(&document, &base))
+        .map(String::from);
+
+    let listing_links = |path_prefix: &str| {
+        let mut urls: Vec<String> = Vec::new();
+        for mut url in hrefs(&format!("a[href*=\"{path_prefix}\"]")) {
+            if !url.path().starts_with(path_prefix) {
+                continue;
+            }
+            url.set_query(None);
+            url.set_fragment(None);
+            let url = String::from(url);
+            if !urls.contains(&url) {
+                urls.push(url);
+            }
+        }
+        urls
+    };
+
+    ListingLinks {
+        next_page,
+        categories: listing_links("/categories/"),
+        tags: listing_links("/tags/"),
+    }
+}
+
+/// Build the next page URL from a Strapi style `pagination:{page:1,...,pageCount:5}`
+/// object in the __NUXT__ payload
+fn next_page_from_nuxt(document: &Html, base: &Url) -> Option<Url> {
+    let pagination_pattern = Regex::new(
+        r#""?pagination"?\s*:\s*\{[^}]*?"?page"?\s*:\s*(\d+)[^}]*?"?pageCount"?\s*:\s*(\d+)"#,
+    )
+    .ok()?;
+    let script_selector = Selector::parse("script").ok()?;
+
+    document.select(&script_selector).find_map(|element| {
+        let script = element.text().collect::<String>();
+        let caps = pagination_pattern.captures(&script)?;
+        let page: u32 = caps[1].parse().ok()?;
+        let page_count: u32 = caps[2].parse().ok()?;
+        if page >= page_count {
+            return None;
+        }
+
+        let mut next = base.clone();
+        let query: Vec<(String, String)> = base
+            .query_pairs()
+            .filter(|(key, _)| key != "page")
+            .map(|(key, value)| (key.into_owned(), value.into_owned()))
+            .collect();
+        next.query_pairs_mut()
+            .clear()
+            .extend_pairs(query)
+            .append_pair("page", &(page + 1).to_string());
+        Some(next)
+    })
 }
 
 #[cfg(test)]
@@ -176,7 +300,7 @@ mod tests {
             </html>
         "#;
 
-        let articles = parse_articles_from_html(html).unwrap();
+        let articles = parse_articles_from_html(html, LEDGE_AI_BASE_URL).unwrap();
         assert_eq!(articles.len(), 2);
 
         assert_eq!(articles[0].title, "Test Article 1");
@@ -209,7 +333,7 @@ mod tests {
         "#
         );
 
-        let articles = parse_articles_from_html(&html).unwrap();
+        let articles = parse_articles_from_html(&html, LEDGE_AI_BASE_URL).unwrap();
         assert_eq!(articles.len(), 2);
 
         assert_eq!(articles[0].title, "世界最強AI「Grok 4」公開");
@@ -227,6 +351,89 @@ mod tests {
         assert_eq!(articles[1].date, "2025/01/14 [MON]"); // Fallback date
     }
 
+    #[test]
+    fn test_parse_articles_from_html_captures_category_prefix() {
+        let html = r#"
+            <html>
+                <body>
+                    <a href="/articles/biz1">ビジネス2026/1/14 [WED]生成AIの導入事例</a>
+                    <a href="/articles/plain">Plain Article Title</a>
+                </body>
+            </html>
+        "#;
+
+        let articles = parse_articles_from_html(html, LEDGE_AI_BASE_URL).unwrap();
+        assert_eq!(articles[0].title, "生成AIの導入事例");
+        assert_eq!(articles[0].category.as_deref(), Some("ビジネス"));
+        assert_eq!(articles[1].title, "Plain Article Title");
+        assert_eq!(articles[1].category, None);
+    }
+
+    #[test]
+    fn test_parse_articles_from_nuxt_data_with_dates() {
+        let script_content = format!(
+            r#"const articles = [{{"title":"記事A","slug":"a","publishedAt":"2025-07-14T07:50:00.000Z"}},{{"title":"記事B","slug":"b"}},{{"title":"記事A","slug":"a"}}]; {}"#,
+            "// padding comment ".repeat(100)
+        );
+        let html = format!("<html><head><script>{script_content}</script></head></html>");
+
+        let articles = parse_articles_from_html(&html, LEDGE_AI_BASE_URL).unwrap();
+        assert_eq!(articles.len(), 2);
+        assert_eq!(articles[0].date, "2025-07-14T07:50:00.000Z");
+        assert_eq!(articles[1].date, "2025/01/14 [MON]");
+    }
+
+    #[test]
+    fn test_parse_articles_from_html_without_articles() {
+        let result = parse_articles_from_html(
+            "<html><body>メンテナンス中</body></html>",
+            "https://ledge.ai/",
+        );
+        assert!(matches!(
+            result,
+            Err(Error::NoListingData { url }) if url == "https://ledge.ai/"
+        ));
+    }
+
+    #[test]
+    fn test_parse_listing_links() {
+        let html = r#"
+            <html>
+                <head><link rel="next" href="/?page=2"></head>
+                <body>
+                    <a href="/categories/business">ビジネス</a>
+                    <a href="https://ledge.ai/categories/business#top">ビジネス</a>
+                    <a href="/tags/genai?sort=new">生成AI</a>
+                    <a href="https://example.com/categories/other">外部</a>
+                </body>
+            </html>
+        "#;
+
+        let links = parse_listing_links(html, "https://ledge.ai/");
+        assert_eq!(links.next_page.as_deref(), Some("https://ledge.ai/?page=2"));
+        assert_eq!(
+            links.categories,
+            vec!["https://ledge.ai/categories/business"]
+        );
+        assert_eq!(links.tags, vec!["https://ledge.ai/tags/genai"]);
+    }
+
+    #[test]
+    fn test_parse_listing_links_next_page_from_nuxt_pagination() {
+        let html = r#"<html><body><script>window.__NUXT__={articles:{data:[],meta:{pagination:{page:2,pageSize:20,pageCount:5,total:100}}}}</script></body></html>"#;
+        let links = parse_listing_links(html, "https://ledge.ai/categories/business?page=2");
+        assert_eq!(
+            links.next_page.as_deref(),
+            Some("https://ledge.ai/categories/business?page=3")
+        );
+
+        let last = html.replace("page:2,", "page:5,");
+        assert_eq!(
+            parse_listing_links(&last, "https://ledge.ai/").next_page,
+            None
+        );
+    }
+
     #[test]
     fn test_extract_from_nuxt_data_not_found() {
         let html = r#"
@@ -241,6 +448,6 @@ mod tests {
         "#;
 
         let result = extract_from_nuxt_data(html);
-        assert!(result.is_err());
+        assert!(result.is_none());
     }
 }
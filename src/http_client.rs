@@ -1,3 +1,4 @@
+use crate::error::{Error, Stage};
 use reqwest::Client;
 use std::time::Duration;
 
@@ -16,11 +17,108 @@ impl HttpClient {
         Self { client }
     }
 
-    pub async fn fetch_url(&self, url: &str) -> Result<String, Box<dyn std::error::Error>> {
-        let response = self.client.get(url).send().await?;
-        let text = response.text().await?;
+    /// Fetch a page, failing on error statuses
+    pub async fn fetch_url(&self, url: &str, stage: Stage) -> Result<String, Error> {
+        let (status, text) = self.fetch_page(url, stage).await?;
+        if !(200..300).contains(&status) {
+            return Err(Error::Http {
+                stage,
+                url: url.to_string(),
+                status,
+            });
+        }
         Ok(text)
     }
+
+    /// Fetch a page along with its HTTP status, without failing on error statuses
+    pub async fn fetch_page(&self, url: &str, stage: Stage) -> Result<(u16, String), Error> {
+        let request_error = |e| Error::request(stage, url, e);
+        let response = self.client.get(url).send().await.map_err(request_error)?;
+        let status = response.status().as_u16();
+        Ok((status, response.text().await.map_err(request_error)?))
+    }
+
+    /// POST a form, failing on error statuses
+    pub async fn post_form(
+        &self,
+        url: &str,
+        params: &[(&str, &str)],
+        stage: Stage,
+    ) -> Result<(), Error> {
+        let response = self
+            .client
+            .post(url)
+            .form(params)
+            .send()
+            .await
+            .map_err(|e| Error::request(stage, url, e))?;
+        let status = response.status().as_u16();
+        if !(200..300).contains(&status) {
+            return Err(Error::Http {
+                stage,
+                url: url.to_string(),
+                status,
+            });
+        }
+        Ok(())
+    }
+
+    /// POST a JSON body and return the response status
+    pub async fn post_json(
+        &self,
+        url: &str,
+        body: &serde_json::Value,
+        stage: Stage,
+    ) -> Result<u16, Error> {
+        let response = self
+            .client
+            .post(url)
+            .json(body)
+            .send()
+            .await
+            .map_err(|e| Error::request(stage, url, e))?;
+        Ok(response.status().as_u16())
+    }
+
+    /// Fetch a binary resource, refusing responses larger than `max_bytes`
+    pub async fn fetch_bytes(
+        &self,
+        url: &str,
+        max_bytes: usize,
+        stage: Stage,
+    ) -> Result<Vec<u8>, Error> {
+        let request_error = |e| Error::request(stage, url, e);
+        let too_large = |bytes| Error::TooLarge {
+            stage,
+            url: url.to_string(),
+            bytes,
+            limit: max_bytes,
+        };
+        let mut response = self.client.get(url).send().await.map_err(request_error)?;
+        let status = response.status().as_u16();
+        if !(200..300).contains(&status) {
+            return Err(Error::Http {
+                stage,
+                url: url.to_string(),
+                status,
+            });
+        }
+        if let Some(length) = response.content_length() {
+            if length > max_bytes as u64 {
+                return Err(too_large(length));
+            }
+        }
+
+        // Chunked responses announce no length, so stop reading once over the limit
+        let mut bytes = Vec::new();
+        while let Some(chunk) = response.chunk().await.map_err(request_error)? {
+            bytes.extend_from_slice(&chunk);
+            if bytes.len() > max_bytes {
+                return Err(too_large(bytes.len() as u64));
+            }
+        }
+        Ok(bytes)
+    }
 }
 
 #[cfg(test)]
@@ -33,10 +131,77 @@ mod tests {
 
         // This is a minimal test - in practice we would use a mock HTTP client
         // Test that client can be created and has the expected structure
-        let _result = client.fetch_url("https://example.com").await;
+        let _result = client
+            .fetch_url("https://example.com", Stage::Listing)
+            .await;
         // In a real test, we would mock the HTTP response
     }
 
+    #[tokio::test]
+    async fn test_fetch_url_fails_on_error_status() {
+        let base = crate::test_support::serve(vec![("/", "ok".to_string())]).await;
+        let client = HttpClient::new();
+        assert_eq!(
+            client
+                .fetch_url(&format!("{base}/"), Stage::Listing)
+                .await
+                .unwrap(),
+            "ok"
+        );
+
+        let result = client
+            .fetch_url(&format!("{base}/gone"), Stage::Listing)
+            .await;
+        assert!(matches!(
+            result,
+            Err(Error::Http {
+                stage: Stage::Listing,
+                status: 404,
+                ..
+            })
+        ));
+
+        let result = client
+            .fetch_url("http://127.0.0.1:1/", Stage::Article)
+            .await;
+        assert!(matches!(result, Err(Error::Network { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_fetch_bytes_limits_chunked_responses() {
+        use tokio::io::{AsyncReadExt, AsyncWriteExt};
+
+        // 64 chunks of 1 KiB without a Content-Length
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buffer = [0u8; 1024];
+                let _ = socket.read(&mut buffer).await;
+                let mut response =
+                    b"HTTP/1.1 200 OK\r\nTransfer-Encoding: chunked\r\nConnection: close\r\n\r\n"
+                        .to_vec();
+                for _ in 0..64 {
+                    response.extend_from_slice(b"400\r\n");
+                    response.extend_from_slice(&[b'x'; 1024]);
+                    response.extend_from_slice(b"\r\n");
+                }
+                response.extend_from_slice(b"0\r\n\r\n");
+                let _ = socket.write_all(&response).await;
+            }
+        });
+
+        let client = HttpClient::new();
+        let result = client.fetch_bytes(&base, 4096, Stage::Media).await;
+        assert!(matches!(
+            result,
+            Err(Error::TooLarge { bytes, limit: 4096, .. }) if bytes <= 4096 + 1024
+        ));
+
+        let bytes = client.fetch_bytes(&base, 64 * 1024, Stage::Media).await;
+        assert_eq!(bytes.unwrap().len(), 64 * 1024);
+    }
+
     #[test]
     fn test_http_client_creation() {
         let _client = HttpClient::new();
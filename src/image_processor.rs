@@ -0,0 +1,314 @@
+use crate::html_attrs::{attr, parse_attributes, render_tag};
+use once_cell::sync::Lazy;
+use regex::{Captures, Regex};
+use reqwest::Url;
+
+// Width requested from image CDNs when an article points to a small rendition
+const PREFERRED_IMAGE_WIDTH: u32 = 1200;
+// Largest `srcset` candidate worth sending to feed readers
+const MAX_IMAGE_WIDTH: u32 = 1600;
+
+const WIDTH_PARAMS: [&str; 2] = ["w", "width"];
+const HEIGHT_PARAMS: [&str; 2] = ["h", "height"];
+// Image CDNs that resize on the fly from `w`/`h` query parameters
+const RESIZING_CDN_HOSTS: [&str; 5] = [
+    "imgix.net",
+    "images.microcms-assets.io",
+    "images.ctfassets.net",
+    "cdn.sanity.io",
+    "images.unsplash.com",
+];
+
+static IMG_TAG_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<img\b[^>]*>").unwrap());
+// A paragraph holding a single image, optionally followed by an emphasized caption line
+static IMAGE_PARAGRAPH_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?is)<p>\s*(<img\b[^>]*>)\s*(?:<br\s*/?>)?\s*(?:<em>(.*?)</em>)?\s*</p>").unwrap()
+});
+
+#[derive(Debug, PartialEq)]
+struct SrcsetCandidate {
+    url: String,
+    width: Option<u32>,
+    density: Option<f32>,
+}
+
+/// Make article images display well in feed readers: pick the best `srcset`
+/// candidate, request a reasonable size from CDNs, add `loading`/`width`/`height`
+/// and wrap captioned images in `<figure>`
+pub fn process_images(html: &str) -> String {
+    let result = IMG_TAG_PATTERN.replace_all(html, |caps: &Captures| rewrite_img_tag(&caps[0]));
+    let result = IMAGE_PARAGRAPH_PATTERN.replace_all(&result, |caps: &Captures| {
+        let img = &caps[1];
+        let caption = caps
+            .get(2)
+            .map(|m| m.as_str().trim().to_string())
+            .filter(|caption| !caption.is_empty())
+            .or_else(|| {
+                attr(&parse_attributes(img), "title")
+                    .map(|title| title.trim().to_string())
+                    .filter(|title| !title.is_empty())
+            });
+
+        match caption {
+            Some(caption) => format!("<figure>{img}<figcaption>{caption}</figcaption></figure>"),
+            None => caps[0].to_string(),
+        }
+    });
+
+    result.into_owned()
+}
+
+fn rewrite_img_tag(tag: &str) -> String {
+    let mut attrs = parse_attributes(tag);
+
+    let mut width = attr(&attrs, "width").and_then(|w| w.parse::<u32>().ok());
+    let mut height = attr(&attrs, "height").and_then(|h| h.parse::<u32>().ok());
+
+    let mut src = attr(&attrs, "src").map(str::to_string);
+    if let Some(candidate) = attr(&attrs, "srcset").and_then(best_srcset_candidate) {
+        if candidate.width.is_some() {
+            width = candidate.width;
+            height = None;
+        }
+        src = Some(candidate.url);
+    }
+
+    if let Some(url) = src.take() {
+        let (url, cdn_width, cdn_height) = upgrade_cdn_size(&url);
+        if cdn_width.is_some() {
+            width = cdn_width;
+            height = cdn_height;
+        }
+        src = Some(url);
+    }
+
+    attrs.retain(|(key, _)| !matches!(key.as_str(), "srcset" | "sizes"));
+    if let Some(src) = src {
+        set_attr(&mut attrs, "src", &src);
+    }
+    if let Some(width) = width {
+        set_attr(&mut attrs, "width", &width.to_string());
+    }
+    match height {
+        Some(height) => set_attr(&mut attrs, "height", &height.to_string()),
+        None => attrs.retain(|(key, _)| key != "height"),
+    }
+    if attr(&attrs, "loading").is_none() {
+        attrs.push(("loading".to_string(), "lazy".to_string()));
+    }
+
+    render_tag("img", &attrs)
+}
+
+/// Pick the largest candidate that does not exceed `MAX_IMAGE_WIDTH`,
+/// falling back to the smallest one when all of them are larger
+fn best_srcset_candidate(srcset: &str) -> Option<SrcsetCandidate> {
+    let candidates: Vec<SrcsetCandidate> = srcset
+        .split(',')
+        .filter_map(|entry| {
+            let mut parts = entry.split_whitespace();
+            let url = parts.next()?.to_string();
+            let descriptor = parts.next().unwrap_or("1x");
+            let (width, density) = if let Some(w) = descriptor.strip_suffix('w') {
+                (w.parse::<u32>().ok(), None)
+            } else if let Some(x) = descriptor.strip_suffix('x') {
+                (None, x.parse::<f32>().ok())
+            } else {
+                (None, Some(1.0))
+            };
+            Some(SrcsetCandidate {
+                url,
+                width,
+                density,
+            })
+        })
+        .collect();
+
+    if candidates.iter().any(|c| c.width.is_some()) {
+        let mut sized: Vec<SrcsetCandidate> = candidates
+            .into_iter()
+            .filter(|c| c.width.is_some())
+            .collect();
+        sized.sort_by_key(|c| c.width);
+        let index = sized
+            .iter()
+            .rposition(|c| c.width.unwrap_or(0) <= MAX_IMAGE_WIDTH)
+            .unwrap_or(0);
+        return Some(sized.swap_remove(index));
+    }
+
+    candidates.into_iter().max_by(|a, b| {
+        a.density
+            .unwrap_or(1.0)
+            .partial_cmp(&b.density.unwrap_or(1.0))
+            .unwrap_or(std::cmp::Ordering::Equal)
+    })
+}
+
+/// Raise CDN width parameters (e.g. `?w=200&h=100`) to `PREFERRED_IMAGE_WIDTH`,
+/// keeping the aspect ratio, and return the resulting dimensions when known.
+/// Only URLs on `RESIZING_CDN_HOSTS` are touched.
+fn upgrade_cdn_size(url: &str) -> (String, Option<u32>, Option<u32>) {
+    let Some(mut parsed) = Url::parse(url).ok().filter(is_resizing_cdn) else {
+        return (url.to_string(), None, None);
+    };
+
+    let pairs: Vec<(String, String)> = parsed
+        .query_pairs()
+        .map(|(key, value)| (key.into_owned(), value.into_owned()))
+        .collect();
+    let find = |keys: &[&str]| {
+        pairs
+            .iter()
+            .find(|(key, _)| keys.contains(&key.as_str()))
+            .and_then(|(_, value)| value.parse::<u32>().ok())
+    };
+
+    let Some(width) = find(&WIDTH_PARAMS).filter(|w| *w > 0) else {
+        return (url.to_string(), None, None);
+    };
+    let height = find(&HEIGHT_PARAMS);
+    if width >= PREFERRED_IMAGE_WIDTH {
+        return (url.to_string(), Some(width), height);
+    }
+
+    let new_height =
+        height.map(|h| (u64::from(h) * u64::from(PREFERRED_IMAGE_WIDTH) / u64::from(width)) as u32);
+    parsed
+        .query_pairs_mut()
+        .clear()
+        .extend_pairs(pairs.iter().map(|(key, value)| {
+            let value = if WIDTH_PARAMS.contains(&key.as_str()) {
+                PREFERRED_IMAGE_WIDTH.to_string()
+            } else if HEIGHT_PARAMS.contains(&key.as_str()) {
+                new_height.map_or(value.clone(), |h| h.to_string())
+            } else {
+                value.clone()
+            };
+            (key.clone(), value)
+        }));
+
+    (parsed.to_string(), Some(PREFERRED_IMAGE_WIDTH), new_height)
+}
+
+fn is_resizing_cdn(url: &Url) -> bool {
+    url.host_str().is_some_and(|host| {
+        RESIZING_CDN_HOSTS
+            .iter()
+            .any(|cdn| host == *cdn || host.ends_with(&format!(".{cdn}")))
+    })
+}
+
+fn set_attr(attrs: &mut Vec<(String, String)>, name: &str, value: &str) {
+    match attrs.iter_mut().find(|(key, _)| key == name) {
+        Some(entry) => entry.1 = value.to_string(),
+        None => attrs.push((name.to_string(), value.to_string())),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_best_srcset_candidate_prefers_largest_within_limit() {
+        let srcset = "https://img.example.com/a-400.jpg 400w, https://img.example.com/a-3000.jpg 3000w, https://img.example.com/a-1200.jpg 1200w";
+        let candidate = best_srcset_candidate(srcset).unwrap();
+        assert_eq!(candidate.url, "https://img.example.com/a-1200.jpg");
+        assert_eq!(candidate.width, Some(1200));
+    }
+
+    #[test]
+    fn test_best_srcset_candidate_density_descriptors() {
+        let srcset = "https://img.example.com/a.jpg, https://img.example.com/a@2x.jpg 2x";
+        let candidate = best_srcset_candidate(srcset).unwrap();
+        assert_eq!(candidate.url, "https://img.example.com/a@2x.jpg");
+    }
+
+    #[test]
+    fn test_upgrade_cdn_size_keeps_aspect_ratio() {
+        let (url, width, height) =
+            upgrade_cdn_size("https://ledge.imgix.net/image.png?w=300&h=200&fit=crop");
+        assert_eq!(
+            url,
+            "https://ledge.imgix.net/image.png?w=1200&h=800&fit=crop"
+        );
+        assert_eq!(width, Some(1200));
+        assert_eq!(height, Some(800));
+    }
+
+    #[test]
+    fn test_upgrade_cdn_size_ignores_unknown_hosts() {
+        let url = "https://example.com/thumb.php?w=300&h=200";
+        assert_eq!(upgrade_cdn_size(url), (url.to_string(), None, None));
+    }
+
+    #[test]
+    fn test_upgrade_cdn_size_without_params() {
+        let (url, width, height) = upgrade_cdn_size("https://cdn.example.com/image.png");
+        assert_eq!(url, "https://cdn.example.com/image.png");
+        assert_eq!(width, None);
+        assert_eq!(height, None);
+    }
+
+    #[test]
+    fn test_process_images_adds_lazy_loading_and_dimensions() {
+        let html = r#"<p>本文 <img src="https://images.microcms-assets.io/a.png?w=600&amp;h=400" alt="図" /> 続き</p>"#;
+        let result = process_images(html);
+        assert_eq!(
+            result,
+            r#"<p>本文 <img src="https://images.microcms-assets.io/a.png?w=1200&amp;h=800" alt="図" width="1200" height="800" loading="lazy"> 続き</p>"#
+        );
+    }
+
+    #[test]
+    fn test_process_images_keeps_unquoted_and_boolean_attributes() {
+        let html =
+            r#"<img src=https://example.com/a.png alt='図 "1"' class=wide decoding=async ismap>"#;
+        let result = process_images(html);
+        assert_eq!(
+            result,
+            r#"<img src="https://example.com/a.png" alt="図 &quot;1&quot;" class="wide" decoding="async" ismap="" loading="lazy">"#
+        );
+    }
+
+    #[test]
+    fn test_process_images_wraps_captioned_image_in_figure() {
+        let html =
+            r#"<p><img src="https://example.com/a.png" alt="図" title="出典：Ledge.ai" /></p>"#;
+        let result = process_images(html);
+        assert_eq!(
+            result,
+            r#"<figure><img src="https://example.com/a.png" alt="図" title="出典：Ledge.ai" loading="lazy"><figcaption>出典：Ledge.ai</figcaption></figure>"#
+        );
+    }
+
+    #[test]
+    fn test_process_images_uses_emphasized_line_as_caption() {
+        let html =
+            "<p><img src=\"https://example.com/a.png\" alt=\"\" />\n<em>図1 システム構成</em></p>";
+        let result = process_images(html);
+        assert!(result.starts_with("<figure>"));
+        assert!(result.contains("<figcaption>図1 システム構成</figcaption>"));
+    }
+
+    #[test]
+    fn test_process_images_replaces_srcset() {
+        let html = r#"<img src="https://example.com/a-200.jpg" srcset="https://example.com/a-200.jpg 200w, https://example.com/a-800.jpg 800w" sizes="100vw" alt="">"#;
+        let result = process_images(html);
+        assert_eq!(
+            result,
+            r#"<img src="https://example.com/a-800.jpg" alt="" width="800" loading="lazy">"#
+        );
+    }
+
+    #[test]
+    fn test_process_images_leaves_uncaptioned_paragraph() {
+        let html = r#"<p><img src="https://example.com/a.png" alt="図" /></p>"#;
+        let result = process_images(html);
+        assert_eq!(
+            result,
+            r#"<p><img src="https://example.com/a.png" alt="図" loading="lazy"></p>"#
+        );
+    }
+}
@@ -0,0 +1,158 @@
+use crate::error::Error;
+use crate::rss_item::RssItem;
+use crate::source::{ChannelInfo, FeedLinks};
+use serde_json::{json, Map, Value};
+
+const JSON_FEED_VERSION: &str = "https://jsonfeed.org/version/1.1";
+
+/// Generate a JSON Feed 1.1 document. Metadata we compute ourselves is placed
+/// in the `_ledge` extension object, as the spec requires for custom fields.
+pub fn generate_json_feed(
+    channel: &ChannelInfo,
+    links: FeedLinks,
+    items: Vec<RssItem>,
+) -> Result<String, Error> {
+    let json_items: Vec<Value> = items.into_iter().map(json_item).collect();
+
+    let mut feed = json!({
+        "version": JSON_FEED_VERSION,
+        "title": channel.title,
+        "home_page_url": channel.link,
+        "description": channel.description,
+        "language": channel.language,
+        "items": json_items,
+    });
+    if let Some(feed_url) = links.self_url {
+        feed["feed_url"] = json!(feed_url);
+    }
+    if let Some(hub) = links.hub {
+        feed["hubs"] = json!([{ "type": "WebSub", "url": hub }]);
+    }
+
+    serde_json::to_string_pretty(&feed).map_err(Error::feed_write("json"))
+}
+
+fn json_item(item: RssItem) -> Value {
+    let mut object = Map::new();
+    object.insert("id".to_string(), json!(item.guid()));
+    object.insert("url".to_string(), json!(item.link));
+    object.insert("title".to_string(), json!(item.title));
+    object.insert("content_html".to_string(), json!(item.description));
+    if let Some(summary) = &item.summary {
+        object.insert("summary".to_string(), json!(summary));
+    }
+    if let Some(author) = &item.author {
+        object.insert("authors".to_string(), json!([{ "name": author }]));
+    }
+    let tags: Vec<&String> = item.category.iter().chain(item.tags.iter()).collect();
+    if !tags.is_empty() {
+        object.insert("tags".to_string(), json!(tags));
+    }
+    object.insert(
+        "date_published".to_string(),
+        json!(item.pub_date.to_rfc3339()),
+    );
+    if let Some(updated) = item.updated {
+        object.insert("date_modified".to_string(), json!(updated.to_rfc3339()));
+    }
+
+    if let Some(stats) = &item.reading_stats {
+        object.insert(
+            "_ledge".to_string(),
+            json!({
+                "char_count": stats.char_count,
+                "word_count": stats.word_count,
+                "reading_time_minutes": stats.minutes,
+            }),
+        );
+    }
+
+    Value::Object(object)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reading_stats::ReadingStats;
+    use crate::source::{LedgeAi, Source};
+    use chrono::{DateTime, Utc};
+
+    #[test]
+    fn test_generate_json_feed() {
+        let date = DateTime::parse_from_rfc3339("2025-01-14T10:00:00+09:00")
+            .unwrap()
+            .with_timezone(&Utc);
+        let items = vec![RssItem {
+            title: "Article 1".to_string(),
+            link: "https://example.com/1".to_string(),
+            description: "<p>Content 1</p>".to_string(),
+            markdown: "Content 1".to_string(),
+            pub_date: date,
+            updated: Some(date + chrono::Duration::days(2)),
+            reading_stats: Some(ReadingStats {
+                char_count: 1200,
+                word_count: 3,
+                minutes: 3,
+            }),
+            summary: Some("要約です。".to_string()),
+            category: Some("ビジネス".to_string()),
+            tags: vec!["生成AI".to_string()],
+            author: Some("Ledge編集部".to_string()),
+            resurfaced: false,
+        }];
+
+        let feed: Value = serde_json::from_str(
+            &generate_json_feed(&LedgeAi.channel(), FeedLinks::default(), items).unwrap(),
+        )
+        .unwrap();
+        assert_eq!(feed["version"], JSON_FEED_VERSION);
+        assert_eq!(feed["title"], "Ledge.ai 新着記事");
+        assert!(feed.get("feed_url").is_none());
+
+        let item = &feed["items"][0];
+        assert_eq!(item["id"], "https://example.com/1");
+        assert_eq!(item["content_html"], "<p>Content 1</p>");
+        assert_eq!(item["summary"], "要約です。");
+        assert_eq!(item["tags"], json!(["ビジネス", "生成AI"]));
+        assert_eq!(item["authors"], json!([{"name": "Ledge編集部"}]));
+        assert_eq!(item["date_published"], "2025-01-14T01:00:00+00:00");
+        assert_eq!(item["date_modified"], "2025-01-16T01:00:00+00:00");
+        assert_eq!(item["_ledge"]["char_count"], 1200);
+        assert_eq!(item["_ledge"]["reading_time_minutes"], 3);
+    }
+
+    #[test]
+    fn test_generate_json_feed_without_reading_stats() {
+        let items = vec![RssItem {
+            title: "Article".to_string(),
+            link: "https://example.com/a".to_string(),
+            ..Default::default()
+        }];
+
+        let feed: Value = serde_json::from_str(
+            &generate_json_feed(&LedgeAi.channel(), FeedLinks::default(), items).unwrap(),
+        )
+        .unwrap();
+        assert!(feed["items"][0].get("_ledge").is_none());
+        assert!(feed["items"][0].get("date_modified").is_none());
+        assert!(feed.get("hubs").is_none());
+    }
+
+    #[test]
+    fn test_generate_json_feed_includes_hub() {
+        let links = FeedLinks {
+            self_url: Some("https://example.com/feed.json"),
+            hub: Some("https://hub.example.com/"),
+        };
+        let feed: Value = serde_json::from_str(
+            &generate_json_feed(&LedgeAi.channel(), links, Vec::new()).unwrap(),
+        )
+        .unwrap();
+
+        assert_eq!(feed["feed_url"], "https://example.com/feed.json");
+        assert_eq!(
+            feed["hubs"],
+            json!([{"type": "WebSub", "url": "https://hub.example.com/"}])
+        );
+    }
+}
@@ -0,0 +1,95 @@
+use crate::cli::LogFormat;
+use std::io::IsTerminal;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::EnvFilter;
+
+/// Used when `RUST_LOG` is not set: our own events from `info`, dependencies
+/// only when something goes wrong
+const DEFAULT_FILTER: &str = "warn,ledge_ai_feed=info";
+
+/// Install the global subscriber for the whole process
+pub fn init(format: LogFormat) {
+    let filter =
+        EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(DEFAULT_FILTER));
+    match format {
+        LogFormat::Text => tracing_subscriber::fmt()
+            .with_env_filter(filter)
+            .with_target(false)
+            .with_ansi(std::io::stdout().is_terminal())
+            .init(),
+        LogFormat::Json => json_subscriber(filter, std::io::stdout).init(),
+    }
+}
+
+/// One JSON object per line, with the event's fields at the top level and
+/// the fields of the enclosing span (e.g. the article URL) under `span`
+fn json_subscriber<W>(
+    filter: EnvFilter,
+    writer: W,
+) -> tracing_subscriber::fmt::SubscriberBuilder<
+    tracing_subscriber::fmt::format::JsonFields,
+    tracing_subscriber::fmt::format::Format<tracing_subscriber::fmt::format::Json>,
+    EnvFilter,
+    W,
+>
+where
+    W: for<'writer> MakeWriter<'writer> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .json()
+        .flatten_event(true)
+        .with_current_span(true)
+        .with_span_list(false)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::io::Write;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> std::io::Result<usize> {
+            self.0.lock().unwrap().write(bytes)
+        }
+
+        fn flush(&mut self) -> std::io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_json_events_carry_fields_and_span() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = json_subscriber(EnvFilter::new("info"), move || writer.clone()).finish();
+
+        tracing::subscriber::with_default(subscriber, || {
+            let span = tracing::info_span!("article", url = "https://ledge.ai/articles/a");
+            let _entered = span.enter();
+            tracing::info!(
+                stage = "fetch",
+                bytes = 1234,
+                duration_ms = 56,
+                "Fetched article"
+            );
+            tracing::debug!("Not logged at info");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+        let event: serde_json::Value = serde_json::from_str(lines[0]).unwrap();
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["message"], "Fetched article");
+        assert_eq!(event["stage"], "fetch");
+        assert_eq!(event["bytes"], 1234);
+        assert_eq!(event["duration_ms"], 56);
+        assert_eq!(event["span"]["url"], "https://ledge.ai/articles/a");
+    }
+}
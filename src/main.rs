@@ -1,150 +1,992 @@
+mod archive;
+mod article_diff;
 mod article_extractor;
+mod article_pipeline;
+mod atom_generator;
+mod backfill;
+mod category_split;
+mod cli;
+mod config;
+mod crawler;
+mod digest;
+mod embed_rewriter;
+mod error;
+mod filter;
+mod health;
+mod html_attrs;
 mod html_parser;
 mod http_client;
+mod image_processor;
+mod json_feed_generator;
+mod logging;
+mod media_mirror;
+mod metrics;
+mod notifier;
+mod reading_stats;
 mod rss_generator;
 mod rss_item;
+mod run_report;
+mod scheduler;
+mod server;
+mod sitemap;
+mod source;
+mod state_store;
+mod summarizer;
+#[cfg(test)]
+mod test_support;
+mod websub;
 
-use article_extractor::{extract_article_content, extract_article_date, markdown_to_html};
-use chrono::{DateTime, Utc};
-use html_parser::parse_articles_from_html;
+use archive::write_archive;
+use article_diff::{history_html, revision_items};
+use article_pipeline::process_article;
+use atom_generator::generate_atom;
+use backfill::run_backfill;
+use category_split::split_items;
+use chrono::{DateTime, Local, Utc};
+use cli::{
+    parse_args, BackfillOptions, Command, DaemonOptions, LogFormat, Options, ServeOptions, USAGE,
+};
+use config::{Config, Discovery, FeedConfig, FeedFormat, OutputConfig};
+use crawler::{crawl_listings, merge_articles};
+use digest::{build_message, deliver, digest_items};
+use error::Error;
+use filter::{FilterReport, ItemFilter};
+use health::check_health;
+use html_parser::ArticleInfo;
 use http_client::HttpClient;
+use json_feed_generator::generate_json_feed;
+use media_mirror::MediaMirror;
+use metrics::SharedMetrics;
+use notifier::notify_new_items;
 use rss_generator::generate_rss;
 use rss_item::RssItem;
+use run_report::{ArticleReport, ArticleStatus, FeedReport, RunReport};
+use scheduler::{jitter, Schedule};
+use server::{run_server, served_feeds};
+use sitemap::discover_from_sitemaps;
+use source::{ChannelInfo, FeedLinks, Source};
+use state_store::{Change, StateLock, StateStore};
+use std::collections::{HashMap, HashSet};
 use std::fs;
+use std::net::SocketAddr;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+use std::time::Instant;
+use tokio::net::TcpListener;
+use tokio::signal::unix::{signal, SignalKind};
+use tokio::sync::watch;
+use tracing::{error, info, info_span, warn, Instrument};
+use websub::publish;
 
-async fn fetch_and_generate_rss() -> Result<(), Box<dyn std::error::Error>> {
-    let client = HttpClient::new();
+fn load_config(options: &Options) -> Result<Config, Error> {
+    match &options.config_path {
+        Some(path) => Config::load(path),
+        None => Ok(Config::default()),
+    }
+}
 
-    // Fetch the main page
-    println!("Fetching Ledge.ai main page...");
-    let main_page_html = client.fetch_url("https://ledge.ai/").await?;
+fn build_media_mirror(options: &Options) -> Option<MediaMirror> {
+    options.media.as_ref().map(|media| {
+        MediaMirror::new(
+            media.output_dir.clone(),
+            &media.public_base_url,
+            media.max_bytes,
+        )
+    })
+}
 
-    // Parse articles from the main page
-    println!("Parsing articles from HTML...");
-    let articles = parse_articles_from_html(&main_page_html)?;
-    println!("Found {} articles", articles.len());
+/// What stays alive across feeds and, in daemon mode, across runs: the HTTP
+/// connection pool, the state store and the media mirror
+struct RunContext {
+    client: HttpClient,
+    media_mirror: Option<MediaMirror>,
+    state: Option<StateStore>,
+    /// Article pages fetched during the current run, shared by every feed
+    page_cache: HashMap<String, String>,
+    /// WebSub hub pinged when a feed with a public URL changes
+    hub: Option<String>,
+    /// The state store started empty: record articles without announcing them
+    seeding: bool,
+    notify_dry_run: bool,
+    /// Diagnostics of the current run
+    report: RunReport,
+    report_path: Option<PathBuf>,
+    /// Print the report's summary table (text logs only; it would break JSON lines)
+    print_summary: bool,
+    /// Shared with the HTTP server. In `serve` mode each run gets a new
+    /// context, but the metrics are kept for the life of the process.
+    metrics: SharedMetrics,
+    metrics_path: Option<PathBuf>,
+    /// Held while the state store is in use
+    _lock: Option<StateLock>,
+}
 
-    let mut rss_items = Vec::new();
+impl RunContext {
+    fn new(options: &Options, config: &Config, metrics: SharedMetrics) -> Result<Self, Error> {
+        let (state, lock) = match &options.state_path {
+            Some(path) => {
+                let lock = StateLock::acquire(path)?;
+                (Some(StateStore::load(path)?), Some(lock))
+            }
+            None => (None, None),
+        };
+        Ok(Self {
+            seeding: state.as_ref().is_some_and(|state| state.len() == 0),
+            client: HttpClient::new(),
+            media_mirror: build_media_mirror(options),
+            state,
+            page_cache: HashMap::new(),
+            hub: config.websub.as_ref().map(|websub| websub.hub.clone()),
+            notify_dry_run: options.notify_dry_run,
+            report: RunReport::new(Utc::now()),
+            report_path: options.report_path.clone(),
+            print_summary: options.log_format == LogFormat::Text,
+            metrics,
+            metrics_path: options.metrics_path.clone(),
+            _lock: lock,
+        })
+    }
 
-    // Fetch content for each article
-    println!(
-        "Fetching article content for {} articles...",
-        articles.len()
+    /// Count a feed whose run failed, and so kept its previous outputs, under
+    /// the failure's reason
+    fn record_failure(&self, feed: &FeedConfig, error: &Error) {
+        self.metrics
+            .lock()
+            .unwrap()
+            .record_failure(&feed.id(), error.reason());
+    }
+
+    /// Persist the state store, refresh the archive and emit the run report
+    /// and metrics
+    fn finish_run(&mut self, config: &Config) -> Result<(), Error> {
+        self.seeding = false;
+        let mut report = std::mem::replace(&mut self.report, RunReport::new(Utc::now()));
+        report.duration_ms = (Utc::now() - report.started_at).num_milliseconds().max(0) as u64;
+        info!(
+            articles = report.articles().count(),
+            failed = report
+                .articles()
+                .filter(|article| article.status == ArticleStatus::Failed)
+                .count(),
+            warnings = report
+                .articles()
+                .map(|article| article.warnings.len())
+                .sum::<usize>(),
+            duration_ms = report.duration_ms,
+            "Run finished"
+        );
+        if self.print_summary && !report.feeds.is_empty() {
+            print!("{}", report.summary_table());
+        }
+        {
+            let mut metrics = self.metrics.lock().unwrap();
+            metrics.record_run(&report);
+            if let Some(path) = &self.metrics_path {
+                metrics.write_textfile(path)?;
+            }
+        }
+        if let Some(path) = &self.report_path {
+            report.write(path)?;
+            info!(path = %path.display(), "Wrote run report");
+        }
+
+        match &self.state {
+            Some(state) => {
+                state.save()?;
+                archive_articles(config, state)
+            }
+            None if config.archive.is_some() => {
+                warn!("archive is ignored without --state");
+                Ok(())
+            }
+            None => Ok(()),
+        }
+    }
+}
+
+async fn fetch_and_generate_rss(options: &Options, metrics: &SharedMetrics) -> Result<(), Error> {
+    let config = load_config(options)?;
+    let mut context = RunContext::new(options, &config, Arc::clone(metrics))?;
+
+    // Failed and unhealthy feeds keep their previous outputs; the others are still written
+    let mut unhealthy = Vec::new();
+    let mut failures = Vec::new();
+    let mut errors = Vec::new();
+    for feed in &config.feeds {
+        if let Err(e) = generate_feed(&mut context, feed).await {
+            context.record_failure(feed, &e);
+            match &e {
+                Error::Unhealthy {
+                    feed,
+                    failures: feed_failures,
+                } => {
+                    error!(error = %e, "Keeping the previous feed");
+                    unhealthy.push(feed.clone());
+                    failures.extend(feed_failures.iter().cloned());
+                }
+                _ => errors.push(e),
+            }
+        }
+    }
+    // The report, metrics and state cover failed runs too
+    context.finish_run(&config)?;
+    let mut errors = errors.into_iter();
+    if let Some(first) = errors.next() {
+        for e in errors {
+            error!(error = %e, "Feed generation failed");
+        }
+        return Err(first);
+    }
+    if !unhealthy.is_empty() {
+        return Err(Error::Unhealthy {
+            feed: unhealthy.join(", "),
+            failures,
+        });
+    }
+    Ok(())
+}
+
+/// Crawl one configured feed and write all of its outputs
+#[tracing::instrument(name = "feed", skip_all, fields(source = %feed.source))]
+async fn generate_feed(context: &mut RunContext, feed: &FeedConfig) -> Result<(), Error> {
+    let started = Instant::now();
+    let source = feed.build_source()?;
+    let mut articles = Vec::new();
+    let collected = collect_items(
+        &context.client,
+        feed,
+        source.as_ref(),
+        &mut context.media_mirror,
+        &mut context.page_cache,
+        &mut articles,
+    )
+    .await;
+    let health_failures = match (&feed.health, &collected) {
+        (Some(health), Ok(items)) => check_health(health, items, &articles),
+        _ => Vec::new(),
+    };
+    context.report.feeds.push(FeedReport {
+        source: feed.source.clone(),
+        feed: feed.id(),
+        duration_ms: started.elapsed().as_millis() as u64,
+        articles,
+        health_failures: health_failures.clone(),
+        error: collected.as_ref().err().map(|e| e.to_string()),
+    });
+    let mut rss_items = collected?;
+    // Checked before the state is updated so the next run sees the same articles as new
+    if !health_failures.is_empty() {
+        return Err(Error::Unhealthy {
+            feed: feed.source.clone(),
+            failures: health_failures,
+        });
+    }
+
+    let new_links = match context.state.as_mut() {
+        Some(state) => record_items(state, feed, &mut rss_items, context.seeding),
+        None => {
+            if feed.resurface.is_some() || feed.diff.is_some() || !feed.webhooks.is_empty() {
+                warn!("resurface, diff and webhooks are ignored without --state");
+            }
+            HashSet::new()
+        }
+    };
+
+    let (mut rss_items, report) = ItemFilter::new(&feed.filters)?.apply(rss_items);
+    log_filter_report(&report);
+
+    // Filtered-out articles are not announced
+    let announcements = match context.state.as_mut() {
+        Some(state) if !feed.webhooks.is_empty() && !context.seeding => {
+            pending_announcements(state, feed, &rss_items, &new_links)
+        }
+        _ => Vec::new(),
+    };
+
+    let channel = feed.channel(source.as_ref());
+    // Public URLs of the outputs whose content changed in this run
+    let mut changed = Vec::new();
+    if let Some(split) = &feed.split {
+        for category_feed in split_items(&rss_items, split) {
+            info!(
+                category = %category_feed.name,
+                slug = %category_feed.slug,
+                items = category_feed.items.len(),
+                "Writing category feed"
+            );
+            let mut items = category_feed.items;
+            if let Some(max_items) = feed.max_items {
+                items.truncate(max_items);
+            }
+            let category_channel = ChannelInfo {
+                title: format!("{}（{}）", channel.title, category_feed.name),
+                ..channel.clone()
+            };
+            changed.extend(write_outputs(
+                &category_channel,
+                &category_feed.outputs,
+                items,
+                context.hub.as_deref(),
+            )?);
+        }
+    }
+
+    if let (Some(diff), Some(state)) = (&feed.diff, &context.state) {
+        if !diff.outputs.is_empty() {
+            let mut changes: Vec<RssItem> = rss_items
+                .iter()
+                .filter_map(|item| state.get(&item.link))
+                .flat_map(|stored| revision_items(&stored.item, &stored.revisions))
+                .collect();
+            changes.sort_by_key(|item| std::cmp::Reverse(item.sort_date()));
+            if let Some(max_items) = feed.max_items {
+                changes.truncate(max_items);
+            }
+            info!(revisions = changes.len(), "Writing changes feed");
+            let changes_channel = ChannelInfo {
+                title: format!("{}（更新履歴）", channel.title),
+                ..channel.clone()
+            };
+            changed.extend(write_outputs(
+                &changes_channel,
+                &diff.outputs,
+                changes,
+                context.hub.as_deref(),
+            )?);
+        }
+    }
+
+    if let Some(max_items) = feed.max_items {
+        rss_items.truncate(max_items);
+    }
+    changed.extend(write_outputs(
+        &channel,
+        &feed.outputs,
+        rss_items,
+        context.hub.as_deref(),
+    )?);
+
+    if !feed.webhooks.is_empty() && context.seeding {
+        info!("State store was empty; recorded articles without notifications");
+    }
+    if let (false, Some(state)) = (announcements.is_empty(), context.state.as_mut()) {
+        // Saved first: a run that dies while sending repeats announcements
+        // on the next run instead of losing them
+        state.save()?;
+        let delivered = notify_new_items(
+            &context.client,
+            &feed.webhooks,
+            &announcements,
+            context.notify_dry_run,
+        )
+        .await;
+        for (link, url) in &delivered {
+            state.mark_delivered(link, url);
+        }
+        let queued: usize = announcements.iter().map(|(_, urls)| urls.len()).sum();
+        info!(
+            sent = delivered.len(),
+            pending = queued - delivered.len(),
+            "Sent webhook notifications"
+        );
+    }
+
+    if let Some(hub) = &context.hub {
+        for topic in &changed {
+            match publish(&context.client, hub, topic).await {
+                Ok(()) => info!(topic = %topic, "Notified WebSub hub"),
+                Err(e) => warn!(topic = %topic, error = %e, "WebSub ping failed"),
+            }
+        }
+    }
+    context
+        .metrics
+        .lock()
+        .unwrap()
+        .record_success(&feed.id(), Utc::now());
+    info!(
+        duration_ms = started.elapsed().as_millis() as u64,
+        "Feed generated"
     );
-    for (i, article) in articles.iter().enumerate() {
-        println!(
-            "Processing article {}/{}: {}",
-            i + 1,
-            articles.len(),
-            article.title
+    Ok(())
+}
+
+/// Queue the feed's webhooks for its new articles and return every listed
+/// article that still has deliveries pending, with those webhook URLs.
+/// Failed deliveries are retried on each run while the article stays listed.
+fn pending_announcements(
+    state: &mut StateStore,
+    feed: &FeedConfig,
+    rss_items: &[RssItem],
+    new_links: &HashSet<String>,
+) -> Vec<(RssItem, Vec<String>)> {
+    let urls: Vec<String> = feed
+        .webhooks
+        .iter()
+        .map(|webhook| webhook.url.clone())
+        .collect();
+    rss_items
+        .iter()
+        .filter_map(|item| {
+            if new_links.contains(&item.link) && !item.resurfaced {
+                state.add_pending_webhooks(&item.link, &urls);
+            }
+            let pending: Vec<String> = state
+                .pending_webhooks(&item.link)
+                .iter()
+                .filter(|url| urls.contains(url))
+                .cloned()
+                .collect();
+            (!pending.is_empty()).then(|| (item.clone(), pending))
+        })
+        .collect()
+}
+
+/// Record the items in the state store, detecting revised articles and
+/// re-surfacing the significantly changed ones when the feed asks for it.
+/// Returns the links of articles seen for the first time.
+fn record_items(
+    state: &mut StateStore,
+    feed: &FeedConfig,
+    rss_items: &mut [RssItem],
+    seeding: bool,
+) -> HashSet<String> {
+    let now = Utc::now();
+    let mut new_links = HashSet::new();
+    let mut changed_items = 0;
+
+    for item in rss_items.iter_mut() {
+        // Articles found by the first run are kept out of digests, like backfilled ones
+        match state.record(item, now, seeding) {
+            Change::New => {
+                new_links.insert(item.link.clone());
+            }
+            Change::Unchanged => {}
+            Change::Changed { ratio } => {
+                changed_items += 1;
+                info!(url = %item.link, change_ratio = ratio, "Article body changed");
+                if feed
+                    .resurface
+                    .as_ref()
+                    .is_some_and(|resurface| ratio >= resurface.min_change_ratio)
+                {
+                    item.resurfaced = true;
+                    state.mark_resurfaced(&item.link);
+                }
+            }
+        }
+
+        match &feed.resurface {
+            Some(resurface) if item.resurfaced => {
+                item.title = format!("{}{}", resurface.title_prefix, item.title);
+            }
+            _ => item.resurfaced = false,
+        }
+        if feed.diff.as_ref().is_some_and(|diff| diff.history) {
+            item.description
+                .push_str(&history_html(state.revisions(&item.link)));
+        }
+    }
+
+    rss_items.sort_by_key(|item| std::cmp::Reverse(item.sort_date()));
+    info!(
+        new = new_links.len(),
+        changed = changed_items,
+        "Recorded articles in the state store"
+    );
+    new_links
+}
+
+async fn backfill(options: &Options, backfill_options: &BackfillOptions) -> Result<(), Error> {
+    let config = load_config(options)?;
+    let state_path = options
+        .state_path
+        .as_ref()
+        .ok_or_else(|| Error::usage("backfill requires --state <FILE>"))?;
+    let _lock = StateLock::acquire(state_path)?;
+    let mut state = StateStore::load(state_path)?;
+    let mut media_mirror = build_media_mirror(options);
+
+    let imported = run_backfill(
+        &HttpClient::new(),
+        &config,
+        backfill_options,
+        &mut state,
+        &mut media_mirror,
+    )
+    .await?;
+    info!(
+        imported,
+        stored = state.len(),
+        state = %state_path.display(),
+        "Backfill finished"
+    );
+    archive_articles(&config, &state)
+}
+
+/// Email the articles recorded since the last digest. Reads only the state
+/// store, so run it after the feeds have been generated.
+async fn digest(options: &Options) -> Result<(), Error> {
+    let config = load_config(options)?;
+    let digest = config
+        .digest
+        .as_ref()
+        .ok_or_else(|| Error::config("digest requires a digest section in the config"))?;
+    let state_path = options
+        .state_path
+        .as_ref()
+        .ok_or_else(|| Error::usage("digest requires --state <FILE>"))?;
+    let _lock = StateLock::acquire(state_path)?;
+    let mut state = StateStore::load(state_path)?;
+
+    let now = Utc::now();
+    let filters = config
+        .feeds
+        .iter()
+        .map(|feed| ItemFilter::new(&feed.filters))
+        .collect::<Result<Vec<_>, _>>()?;
+    let selected = digest_items(&state, &filters, digest.max_items, now);
+    let Some(seen_until) = selected.seen_until else {
+        info!("No new articles since the last digest");
+        return Ok(());
+    };
+    let message = build_message(digest, &selected.items, now)?;
+    let destination = deliver(&digest.transport, message, now).await?;
+    info!(articles = selected.items.len(), destination = %destination, "Sent digest");
+
+    state.set_last_digest(seen_until);
+    state.save()
+}
+
+/// Serve the configured feeds while regenerating them every `interval`.
+/// A failed run is reported and the previously generated files stay served.
+async fn serve(options: &Options, serve_options: &ServeOptions) -> Result<(), Error> {
+    let config = Arc::new(load_config(options)?);
+    let (listener, address) = bind(serve_options.listen).await?;
+    info!(address = %address, "Serving feeds");
+    for feed in served_feeds(&config) {
+        info!(path = %feed.url_path, file = %feed.file.display(), "Serving feed");
+    }
+    let metrics = SharedMetrics::default();
+    tokio::spawn(run_server(listener, config, Arc::clone(&metrics)));
+
+    loop {
+        if let Err(e) = fetch_and_generate_rss(options, &metrics).await {
+            error!(error = %e, "Error generating RSS feed");
+        }
+        info!(
+            interval_minutes = serve_options.interval.as_secs() / 60,
+            "Waiting for the next run"
         );
+        tokio::time::sleep(serve_options.interval).await;
+    }
+}
 
-        match client.fetch_url(&article.url).await {
-            Ok(article_html) => {
-                if let Ok(markdown_content) = extract_article_content(&article_html) {
-                    println!("  ✓ Extracted content ({} chars)", markdown_content.len());
+/// A feed's schedule in daemon mode and when it is due next
+struct ScheduledFeed<'a> {
+    feed: &'a FeedConfig,
+    schedule: Schedule,
+    jitter_seconds: u64,
+    next_run: DateTime<Utc>,
+}
 
-                    let html_content = markdown_to_html(&markdown_content);
-                    println!("  ✓ Converted markdown to HTML (with integrated content filtering)");
+/// Generate every feed on its own schedule until SIGTERM or Ctrl-C. The state
+/// store, HTTP client and media mirror stay in memory between runs. Runs never
+/// overlap: a feed whose run took longer than its schedule skips the missed
+/// times and is planned again from the moment it finished.
+async fn daemon(options: &Options, daemon_options: &DaemonOptions) -> Result<(), Error> {
+    let config = Arc::new(load_config(options)?);
+    let metrics = SharedMetrics::default();
+    let mut context = RunContext::new(options, &config, Arc::clone(&metrics))?;
+    let mut shutdown = shutdown_signal()?;
+    if let Some(listen) = daemon_options.listen {
+        let (listener, address) = bind(listen).await?;
+        info!(address = %address, "Serving feeds and metrics");
+        tokio::spawn(run_server(listener, Arc::clone(&config), metrics));
+    }
 
-                    // Try to extract actual publication date from article page
-                    let actual_date = extract_article_date(&article_html);
-                    let date_to_use = actual_date.as_deref().unwrap_or(&article.date);
+    let started = Utc::now();
+    let mut feeds = config
+        .feeds
+        .iter()
+        .map(|feed| {
+            let (schedule, jitter_seconds) = match &feed.schedule {
+                Some(schedule) => (Schedule::from_config(schedule)?, schedule.jitter_seconds),
+                None => (Schedule::Interval(daemon_options.interval), 0),
+            };
+            Ok(ScheduledFeed {
+                feed,
+                schedule,
+                jitter_seconds,
+                next_run: started + jitter(jitter_seconds),
+            })
+        })
+        .collect::<Result<Vec<_>, Error>>()?;
+    info!(feeds = feeds.len(), "Daemon started");
 
-                    if actual_date.is_some() {
-                        println!("  ✓ Extracted publication date: {date_to_use}");
-                    } else {
-                        println!("  ! Using fallback date: {date_to_use}");
+    loop {
+        let wait = feeds
+            .iter()
+            .map(|scheduled| scheduled.next_run)
+            .min()
+            .map_or(std::time::Duration::MAX, |next_run| {
+                (next_run - Utc::now()).to_std().unwrap_or_default()
+            });
+        tokio::select! {
+            _ = tokio::time::sleep(wait) => {}
+            _ = shutdown.changed() => break,
+        }
+
+        // Article pages may change between runs; only the current run shares them
+        context.page_cache.clear();
+        context.report = RunReport::new(Utc::now());
+        let mut stopping = false;
+        for scheduled in feeds
+            .iter_mut()
+            .filter(|scheduled| scheduled.next_run <= Utc::now())
+        {
+            info!(
+                source = %scheduled.feed.source,
+                output = %scheduled.feed.outputs[0].path.display(),
+                "Running scheduled feed"
+            );
+            let result = {
+                let run = generate_feed(&mut context, scheduled.feed);
+                tokio::pin!(run);
+                tokio::select! {
+                    result = &mut run => result,
+                    _ = shutdown.changed() => {
+                        info!("Shutdown requested, finishing the current feed");
+                        stopping = true;
+                        run.await
                     }
+                }
+            };
+            if let Err(e) = result {
+                error!(source = %scheduled.feed.source, error = %e, "Error generating RSS feed");
+                context.record_failure(scheduled.feed, &e);
+            }
+
+            scheduled.next_run =
+                scheduled.schedule.next_after(Utc::now()) + jitter(scheduled.jitter_seconds);
+            info!(
+                source = %scheduled.feed.source,
+                next_run = %scheduled.next_run.with_timezone(&Local).format("%Y-%m-%d %H:%M:%S"),
+                "Next run scheduled"
+            );
+            if stopping {
+                break;
+            }
+        }
 
-                    let rss_item = RssItem {
-                        title: article.title.clone(),
-                        link: article.url.clone(),
-                        description: html_content,
-                        pub_date: parse_iso_date(date_to_use),
-                    };
+        if let Err(e) = context.finish_run(&config) {
+            error!(error = %e, "Error saving state");
+        }
+        if stopping {
+            break;
+        }
+    }
 
-                    rss_items.push(rss_item);
-                } else {
-                    eprintln!("  ✗ Failed to extract content from: {}", article.url);
-                }
+    info!("Daemon stopped");
+    Ok(())
+}
+
+/// Listen on `addr` and return the bound address (the real port when it is 0)
+async fn bind(addr: SocketAddr) -> Result<(TcpListener, SocketAddr), Error> {
+    let listen_error = |source| Error::Listen { addr, source };
+    let listener = TcpListener::bind(addr).await.map_err(listen_error)?;
+    let bound = listener.local_addr().map_err(listen_error)?;
+    Ok((listener, bound))
+}
+
+/// Flips to `true` on SIGTERM or Ctrl-C
+fn shutdown_signal() -> Result<watch::Receiver<bool>, Error> {
+    let (sender, receiver) = watch::channel(false);
+    let mut terminate =
+        signal(SignalKind::terminate()).map_err(|source| Error::Signal { source })?;
+    tokio::spawn(async move {
+        tokio::select! {
+            _ = terminate.recv() => {}
+            _ = tokio::signal::ctrl_c() => {}
+        }
+        let _ = sender.send(true);
+    });
+    Ok(receiver)
+}
+
+fn archive_articles(config: &Config, state: &StateStore) -> Result<(), Error> {
+    if let Some(archive) = &config.archive {
+        let written = write_archive(archive, state)?;
+        info!(
+            articles = state.len(),
+            dir = %archive.dir.display(),
+            files_updated = written,
+            "Archived articles"
+        );
+    }
+    Ok(())
+}
+
+async fn collect_items(
+    client: &HttpClient,
+    feed: &FeedConfig,
+    source: &dyn Source,
+    media_mirror: &mut Option<MediaMirror>,
+    page_cache: &mut HashMap<String, String>,
+    reports: &mut Vec<ArticleReport>,
+) -> Result<Vec<RssItem>, Error> {
+    let cutoff = feed.crawl.cutoff(Utc::now());
+    let articles = discover_articles(client, feed, source, cutoff).await?;
+    info!(articles = articles.len(), "Found articles");
+
+    let mut rss_items = Vec::new();
+
+    // Fetch content for each article
+    for (i, article) in articles.iter().enumerate() {
+        let span = info_span!("article", url = %article.url, index = i + 1, total = articles.len());
+        let mut report = ArticleReport::new(&article.url, &article.title);
+        match process_article(
+            client,
+            source,
+            article,
+            cutoff,
+            media_mirror,
+            page_cache,
+            &mut report,
+        )
+        .instrument(span.clone())
+        .await
+        {
+            Ok(Some(rss_item)) => {
+                report.title = rss_item.title.clone();
+                rss_items.push(rss_item);
+            }
+            Ok(None) => {
+                span.in_scope(|| info!("Skipping article older than the crawl cutoff"));
+                report.status = ArticleStatus::Skipped;
             }
             Err(e) => {
-                eprintln!("  ✗ Failed to fetch article {}: {}", article.url, e);
+                span.in_scope(|| warn!(error = %e, "Skipping article"));
+                report.fail(&e);
             }
         }
+        reports.push(report);
     }
 
     // Sort RSS items by publication date (newest first)
+    #[allow(clippy::unnecessary_sort_by)]
     rss_items.sort_by(|a, b| b.pub_date.cmp(&a.pub_date));
 
-    // Generate RSS
-    println!("Generating RSS feed with {} items...", rss_items.len());
-    let rss_xml = generate_rss(rss_items)?;
+    Ok(rss_items)
+}
 
-    // Write to file
-    fs::write("rss.xml", &rss_xml)?;
+async fn discover_articles(
+    client: &HttpClient,
+    feed: &FeedConfig,
+    source: &dyn Source,
+    cutoff: Option<DateTime<Utc>>,
+) -> Result<Vec<ArticleInfo>, Error> {
+    let crawl = || {
+        crawl_listings(
+            client,
+            source,
+            feed.listing_urls(source),
+            &feed.crawl,
+            cutoff,
+        )
+    };
+    let from_sitemaps = || {
+        discover_from_sitemaps(
+            client,
+            source,
+            feed.sitemap_urls(source),
+            cutoff,
+            feed.crawl.max_sitemap_urls,
+        )
+    };
 
-    println!(
-        "RSS feed generated successfully as 'rss.xml' ({} bytes)",
-        rss_xml.len()
-    );
-    Ok(())
+    match feed.discovery {
+        Discovery::Listing => Ok(crawl().await?),
+        Discovery::Sitemap => match from_sitemaps().await {
+            Ok(articles) if !articles.is_empty() => Ok(articles),
+            Ok(_) => {
+                warn!("Sitemaps listed no articles, falling back to listing pages");
+                Ok(crawl().await?)
+            }
+            Err(e) => {
+                warn!(error = %e, "Sitemap discovery failed, falling back to listing pages");
+                Ok(crawl().await?)
+            }
+        },
+        Discovery::Both => {
+            let mut articles = crawl().await?;
+            match from_sitemaps().await {
+                Ok(found) => merge_articles(&mut articles, found),
+                Err(e) => warn!(error = %e, "Sitemap discovery failed"),
+            }
+            Ok(articles)
+        }
+    }
 }
 
-fn parse_iso_date(date_str: &str) -> DateTime<Utc> {
-    // Try ISO 8601 format (from extract_article_date)
-    if let Ok(parsed) = DateTime::parse_from_rfc3339(date_str) {
-        return parsed.with_timezone(&Utc);
-    }
+/// Write every output and return the public URLs of those whose content changed
+fn write_outputs(
+    channel: &ChannelInfo,
+    outputs: &[OutputConfig],
+    rss_items: Vec<RssItem>,
+    hub: Option<&str>,
+) -> Result<Vec<String>, Error> {
+    let mut changed = Vec::new();
+    for output in outputs {
+        let filter = ItemFilter::new(&output.filters)?;
+        let items = if filter.is_empty() {
+            rss_items.clone()
+        } else {
+            let (items, report) = filter.apply(rss_items.clone());
+            log_filter_report(&report);
+            items
+        };
+
+        let links = FeedLinks {
+            self_url: output.url.as_deref(),
+            hub,
+        };
+        let item_count = items.len();
+        let (label, content) = match output.format {
+            FeedFormat::Rss => ("rss", generate_rss(channel, links, items)?),
+            FeedFormat::Atom => ("atom", generate_atom(channel, links, items)?),
+            FeedFormat::Json => ("json", generate_json_feed(channel, links, items)?),
+        };
 
-    // Try fallback format "2025/01/14 [MON]"
-    if let Some(date_part) = date_str.split(' ').next() {
-        if let Ok(parsed) = chrono::NaiveDate::parse_from_str(date_part, "%Y/%m/%d") {
-            let datetime = parsed.and_hms_opt(12, 0, 0).unwrap();
-            return DateTime::from_naive_utc_and_offset(datetime, Utc);
+        // Write to file
+        if let Some(parent) = output.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(Error::io(parent))?;
+        }
+        let unchanged = fs::read_to_string(&output.path).is_ok_and(|old| old == content);
+        write_atomically(&output.path, &content)?;
+        match (&output.url, hub) {
+            (Some(url), Some(_)) if !unchanged => changed.push(url.clone()),
+            (None, Some(_)) => warn!(
+                path = %output.path.display(),
+                "Output has no url, so it is not announced to the WebSub hub"
+            ),
+            _ => {}
         }
+
+        info!(
+            format = label,
+            path = %output.path.display(),
+            items = item_count,
+            bytes = content.len(),
+            changed = !unchanged,
+            "Feed written"
+        );
     }
+    Ok(changed)
+}
 
-    // Fallback to current time if parsing fails
-    Utc::now()
+/// Write through a temporary file next to `path`, so readers and the server
+/// never see a half-written feed
+fn write_atomically(path: &Path, content: &str) -> Result<(), Error> {
+    let mut temp_path = path.as_os_str().to_owned();
+    temp_path.push(".tmp");
+    let temp_path = PathBuf::from(temp_path);
+    fs::write(&temp_path, content).map_err(Error::io(&temp_path))?;
+    fs::rename(&temp_path, path).map_err(Error::io(path))
+}
+
+fn log_filter_report(report: &FilterReport) {
+    if report.total() == 0 {
+        return;
+    }
+    info!(items = report.total(), "Filtered out items");
+    for dropped in &report.dropped {
+        info!(
+            rule = %dropped.rule,
+            items = dropped.titles.len(),
+            titles = ?dropped.titles,
+            "Filter rule dropped items"
+        );
+    }
 }
 
 #[tokio::main]
 async fn main() {
-    if let Err(e) = fetch_and_generate_rss().await {
-        eprintln!("Error generating RSS feed: {e}");
-        std::process::exit(1);
+    let options = match parse_args(std::env::args().skip(1)) {
+        Ok(options) => options,
+        Err(e) => {
+            eprintln!("{e}\n\n{USAGE}");
+            std::process::exit(e.exit_code());
+        }
+    };
+    if options.help {
+        println!("{USAGE}");
+        return;
+    }
+    logging::init(options.log_format);
+
+    let result = match &options.command {
+        Command::Generate => fetch_and_generate_rss(&options, &SharedMetrics::default()).await,
+        Command::Backfill(backfill_options) => backfill(&options, backfill_options).await,
+        Command::Serve(serve_options) => serve(&options, serve_options).await,
+        Command::Daemon(daemon_options) => daemon(&options, daemon_options).await,
+        Command::Digest => digest(&options).await,
+    };
+    if let Err(e) = result {
+        match options.command {
+            Command::Generate => error!(error = %e, "Error generating RSS feed"),
+            Command::Backfill(_) => error!(error = %e, "Error during backfill"),
+            Command::Serve(_) => error!(error = %e, "Error starting server"),
+            Command::Daemon(_) => error!(error = %e, "Error starting daemon"),
+            Command::Digest => error!(error = %e, "Error sending digest"),
+        }
+        std::process::exit(e.exit_code());
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
-    use chrono::Datelike;
-
-    #[test]
-    fn test_parse_iso_date() {
-        let date_str = "2025/1/14 [TUE]";
-        let parsed = parse_iso_date(date_str);
-        assert_eq!(parsed.format("%Y-%m-%d").to_string(), "2025-01-14");
-    }
-
-    #[test]
-    fn test_parse_iso_date_invalid() {
-        let date_str = "invalid date";
-        let parsed = parse_iso_date(date_str);
-        // Should not panic and return current time as fallback
-        assert!(parsed.year() >= 2025);
-    }
-
-    #[test]
-    fn test_parse_iso_date_iso8601() {
-        let date_str = "2025-07-14T07:50:00.000Z";
-        let parsed = parse_iso_date(date_str);
-        assert_eq!(
-            parsed.format("%Y-%m-%d %H:%M:%S").to_string(),
-            "2025-07-14 07:50:00"
-        );
+    use crate::test_support::{serve, temp_dir};
+
+    #[tokio::test]
+    async fn test_failed_feed_still_finishes_run() {
+        // Every path is a 404, so the listing page cannot be fetched
+        let base = serve(Vec::new()).await;
+        let dir = temp_dir("failed-feed");
+        let config_path = dir.join("feeds.json");
+        let config = serde_json::json!({
+            "feeds": [{
+                "source": "ledge_ai",
+                "discovery": "listing",
+                "listing_urls": [format!("{base}/categories/business")],
+                "outputs": [{"path": dir.join("rss.xml"), "format": "rss"}]
+            }]
+        });
+        fs::write(&config_path, config.to_string()).unwrap();
+        let options = Options {
+            config_path: Some(config_path),
+            state_path: Some(dir.join("state.json")),
+            report_path: Some(dir.join("report.json")),
+            metrics_path: Some(dir.join("ledge_ai_feed.prom")),
+            ..Options::default()
+        };
+
+        let result = fetch_and_generate_rss(&options, &SharedMetrics::default()).await;
+        assert!(result.unwrap_err().to_string().contains("404"));
+        assert!(!dir.join("rss.xml").exists());
+        assert!(dir.join("state.json").exists());
+        let report: serde_json::Value =
+            serde_json::from_str(&fs::read_to_string(dir.join("report.json")).unwrap()).unwrap();
+        assert!(report["feeds"][0]["error"]
+            .as_str()
+            .is_some_and(|error| error.contains("404")));
+        let metrics = fs::read_to_string(dir.join("ledge_ai_feed.prom")).unwrap();
+        let label = dir.join("rss.xml").display().to_string();
+        assert!(metrics.contains(&format!(
+            "ledge_ai_feed_feeds_failed_total{{feed=\"{label}\",reason=\"http_status\"}} 1"
+        )));
+
+        let _ = fs::remove_dir_all(&dir);
     }
 }
@@ -0,0 +1,221 @@
+use crate::error::{Error, Stage};
+use crate::http_client::HttpClient;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::path::PathBuf;
+use tracing::{debug, warn};
+
+pub const DEFAULT_MAX_MEDIA_BYTES: usize = 5 * 1024 * 1024;
+
+static IMG_SRC_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?i)<img\b[^>]*?\bsrc="(https?://[^"]+)""#).unwrap());
+
+/// Downloads article images into `<output_dir>/media/` and rewrites article
+/// HTML to point at the mirrored copies under `public_base_url`
+pub struct MediaMirror {
+    output_dir: PathBuf,
+    public_base_url: String,
+    max_bytes: usize,
+    // Original URL -> mirrored URL, shared across articles within a run
+    mirrored: HashMap<String, String>,
+}
+
+impl MediaMirror {
+    pub fn new(output_dir: PathBuf, public_base_url: &str, max_bytes: usize) -> Self {
+        Self {
+            output_dir,
+            public_base_url: public_base_url.trim_end_matches('/').to_string(),
+            max_bytes,
+            mirrored: HashMap::new(),
+        }
+    }
+
+    /// Mirror every image referenced by `html`, returning the rewritten HTML and
+    /// the number of images now served from the mirror. Images that cannot be
+    /// downloaded keep their original URL.
+    pub async fn mirror_html(&mut self, client: &HttpClient, html: &str) -> (String, usize) {
+        let mut sources: Vec<String> = IMG_SRC_PATTERN
+            .captures_iter(html)
+            .map(|caps| caps[1].to_string())
+            .collect();
+        sources.sort();
+        sources.dedup();
+
+        let mut result = html.to_string();
+        let mut count = 0;
+        for escaped_src in sources {
+            let src = escaped_src.replace("&amp;", "&");
+            if !self.mirrored.contains_key(&src) {
+                match self.download(client, &src).await {
+                    Ok(file_name) => {
+                        let url = format!("{}/media/{file_name}", self.public_base_url);
+                        self.mirrored.insert(src.clone(), url);
+                    }
+                    Err(e) => {
+                        warn!(src = %src, error = %e, "Failed to mirror image");
+                        continue;
+                    }
+                }
+            }
+
+            if let Some(mirrored_url) = self.mirrored.get(&src) {
+                result = result.replace(
+                    &format!("src=\"{escaped_src}\""),
+                    &format!("src=\"{mirrored_url}\""),
+                );
+                count += 1;
+            }
+        }
+
+        (result, count)
+    }
+
+    async fn download(&self, client: &HttpClient, url: &str) -> Result<String, Error> {
+        let bytes = client
+            .fetch_bytes(url, self.max_bytes, Stage::Media)
+            .await?;
+        debug!(src = url, bytes = bytes.len(), "Downloaded image");
+        self.store(url, &bytes)
+    }
+
+    /// Write the image downloaded from `url` under a content-addressed file
+    /// name and return that name
+    fn store(&self, url: &str, bytes: &[u8]) -> Result<String, Error> {
+        let (_, extension) = sniff_image_type(bytes).ok_or_else(|| Error::Decode {
+            url: url.to_string(),
+            reason: "not a supported image type".to_string(),
+        })?;
+        let file_name = format!("{}.{extension}", content_address(bytes));
+
+        let media_dir = self.output_dir.join("media");
+        fs::create_dir_all(&media_dir).map_err(Error::io(&media_dir))?;
+        let path = media_dir.join(&file_name);
+        if !path.exists() {
+            fs::write(&path, bytes).map_err(Error::io(&path))?;
+        }
+
+        Ok(file_name)
+    }
+}
+
+/// Detect the image MIME type and file extension from the leading bytes
+pub fn sniff_image_type(bytes: &[u8]) -> Option<(&'static str, &'static str)> {
+    if bytes.starts_with(b"\x89PNG\r\n\x1a\n") {
+        Some(("image/png", "png"))
+    } else if bytes.starts_with(&[0xFF, 0xD8, 0xFF]) {
+        Some(("image/jpeg", "jpg"))
+    } else if bytes.starts_with(b"GIF87a") || bytes.starts_with(b"GIF89a") {
+        Some(("image/gif", "gif"))
+    } else if bytes.len() >= 12 && &bytes[0..4] == b"RIFF" && &bytes[8..12] == b"WEBP" {
+        Some(("image/webp", "webp"))
+    } else if bytes.len() >= 12
+        && &bytes[4..8] == b"ftyp"
+        && matches!(&bytes[8..12], b"avif" | b"avis")
+    {
+        Some(("image/avif", "avif"))
+    } else {
+        // SVG is left out on purpose: it can carry scripts we would then serve
+        None
+    }
+}
+
+fn content_address(bytes: &[u8]) -> String {
+    Sha256::digest(bytes)
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::temp_dir;
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use tokio::net::TcpListener;
+
+    const PNG_BYTES: &[u8] = b"\x89PNG\r\n\x1a\n\x00\x00\x00\rIHDR";
+
+    #[test]
+    fn test_sniff_image_type() {
+        assert_eq!(sniff_image_type(PNG_BYTES), Some(("image/png", "png")));
+        assert_eq!(
+            sniff_image_type(&[0xFF, 0xD8, 0xFF, 0xE0]),
+            Some(("image/jpeg", "jpg"))
+        );
+        assert_eq!(sniff_image_type(b"GIF89a...."), Some(("image/gif", "gif")));
+        assert_eq!(
+            sniff_image_type(b"RIFF\x00\x00\x00\x00WEBPVP8 "),
+            Some(("image/webp", "webp"))
+        );
+        assert_eq!(
+            sniff_image_type(b"<?xml version=\"1.0\"?><svg xmlns=\"http://www.w3.org/2000/svg\"/>"),
+            None
+        );
+        assert_eq!(sniff_image_type(b"<!DOCTYPE html><html>"), None);
+    }
+
+    #[test]
+    fn test_store_uses_content_addressed_name() {
+        let dir = temp_dir("media-store");
+        let mirror = MediaMirror::new(dir.clone(), "https://example.com/feed/", 1024);
+
+        let file_name = mirror
+            .store("https://example.com/a.png", PNG_BYTES)
+            .unwrap();
+        assert_eq!(file_name, format!("{}.png", content_address(PNG_BYTES)));
+        assert_eq!(
+            fs::read(dir.join("media").join(&file_name)).unwrap(),
+            PNG_BYTES
+        );
+
+        assert!(matches!(
+            mirror.store("https://example.com/a.png", b"<html>not an image</html>"),
+            Err(Error::Decode { .. })
+        ));
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_mirror_html_rewrites_image_sources() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            while let Ok((mut socket, _)) = listener.accept().await {
+                let mut buffer = [0u8; 1024];
+                let _ = socket.read(&mut buffer).await;
+                let request = String::from_utf8_lossy(&buffer);
+                let body: &[u8] = if request.starts_with("GET /large.png") {
+                    &[0x89; 64]
+                } else {
+                    PNG_BYTES
+                };
+                let header = format!(
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n",
+                    body.len()
+                );
+                let _ = socket.write_all(header.as_bytes()).await;
+                let _ = socket.write_all(body).await;
+            }
+        });
+
+        let dir = temp_dir("media-mirror");
+        let mut mirror = MediaMirror::new(dir.clone(), "https://example.com/feed", 32);
+        let html = format!(
+            r#"<p><img src="http://{addr}/a.png" alt="図"></p><p><img src="http://{addr}/large.png"></p>"#
+        );
+
+        let (result, count) = mirror.mirror_html(&HttpClient::new(), &html).await;
+        assert_eq!(count, 1);
+        let expected = format!(
+            r#"<img src="https://example.com/feed/media/{}.png" alt="図">"#,
+            content_address(PNG_BYTES)
+        );
+        assert!(result.contains(&expected));
+        // Images over the size limit keep pointing at the origin
+        assert!(result.contains(&format!(r#"src="http://{addr}/large.png""#)));
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
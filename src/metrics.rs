@@ -0,0 +1,397 @@
+use crate::error::Error;
+use crate::run_report::{ArticleStatus, RunReport};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+/// Counters shared between the runs and the `/metrics` endpoint
+pub type SharedMetrics = Arc<Mutex<Metrics>>;
+
+/// Content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4; charset=utf-8";
+
+const PREFIX: &str = "ledge_ai_feed";
+const FETCH_SECONDS_BUCKETS: &[f64] = &[0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+const BODY_CHARS_BUCKETS: &[f64] = &[250.0, 500.0, 1000.0, 2000.0, 5000.0, 10000.0, 20000.0];
+const RUN_SECONDS_BUCKETS: &[f64] = &[10.0, 30.0, 60.0, 120.0, 300.0, 600.0, 1800.0];
+
+#[derive(Debug, Clone)]
+struct Histogram {
+    buckets: &'static [f64],
+    /// Observations per bucket, not cumulative
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    fn new(buckets: &'static [f64]) -> Self {
+        Self {
+            buckets,
+            counts: vec![0; buckets.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    fn observe(&mut self, value: f64) {
+        if let Some(index) = self.buckets.iter().position(|bound| value <= *bound) {
+            self.counts[index] += 1;
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+}
+
+/// Counters and histograms about extraction, kept for the life of the process
+#[derive(Debug)]
+pub struct Metrics {
+    discovered: BTreeMap<String, u64>,
+    fetched: BTreeMap<String, u64>,
+    extracted: BTreeMap<String, u64>,
+    failed: BTreeMap<(String, &'static str), u64>,
+    /// Feed runs that kept the previous outputs, by reason
+    feeds_failed: BTreeMap<(String, &'static str), u64>,
+    fetch_seconds: BTreeMap<String, Histogram>,
+    body_chars: BTreeMap<String, Histogram>,
+    run_seconds: Histogram,
+    /// Unix time each feed last wrote its outputs
+    last_success: BTreeMap<String, i64>,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            discovered: BTreeMap::new(),
+            fetched: BTreeMap::new(),
+            extracted: BTreeMap::new(),
+            failed: BTreeMap::new(),
+            feeds_failed: BTreeMap::new(),
+            fetch_seconds: BTreeMap::new(),
+            body_chars: BTreeMap::new(),
+            run_seconds: Histogram::new(RUN_SECONDS_BUCKETS),
+            last_success: BTreeMap::new(),
+        }
+    }
+}
+
+impl Metrics {
+    /// Count every article of a finished run
+    pub fn record_run(&mut self, report: &RunReport) {
+        for feed in &report.feeds {
+            let source = &feed.feed;
+            for article in &feed.articles {
+                *self.discovered.entry(source.clone()).or_default() += 1;
+                if let Some(fetch_ms) = article.fetch_ms {
+                    *self.fetched.entry(source.clone()).or_default() += 1;
+                    self.fetch_seconds
+                        .entry(source.clone())
+                        .or_insert_with(|| Histogram::new(FETCH_SECONDS_BUCKETS))
+                        .observe(fetch_ms as f64 / 1000.0);
+                }
+                if article.status == ArticleStatus::Failed {
+                    *self
+                        .failed
+                        .entry((source.clone(), article.reason.unwrap_or("unknown")))
+                        .or_default() += 1;
+                } else if let Some(chars) = article.content_chars {
+                    *self.extracted.entry(source.clone()).or_default() += 1;
+                    self.body_chars
+                        .entry(source.clone())
+                        .or_insert_with(|| Histogram::new(BODY_CHARS_BUCKETS))
+                        .observe(chars as f64);
+                }
+            }
+        }
+        self.run_seconds.observe(report.duration_ms as f64 / 1000.0);
+    }
+
+    /// A feed passed its health checks and wrote its outputs
+    pub fn record_success(&mut self, source: &str, at: DateTime<Utc>) {
+        self.last_success.insert(source.to_string(), at.timestamp());
+    }
+
+    /// A feed could not be generated or failed its health checks
+    pub fn record_failure(&mut self, feed: &str, reason: &'static str) {
+        *self
+            .feeds_failed
+            .entry((feed.to_string(), reason))
+            .or_default() += 1;
+    }
+
+    /// Everything in the Prometheus text format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        counter(
+            &mut out,
+            "articles_discovered_total",
+            "Articles found on listing pages and sitemaps",
+            &self.discovered,
+        );
+        counter(
+            &mut out,
+            "articles_fetched_total",
+            "Article pages downloaded, excluding pages reused within a run",
+            &self.fetched,
+        );
+        counter(
+            &mut out,
+            "articles_extracted_total",
+            "Articles whose body was extracted",
+            &self.extracted,
+        );
+
+        let name = format!("{PREFIX}_articles_failed_total");
+        header(
+            &mut out,
+            &name,
+            "Articles that could not be processed",
+            "counter",
+        );
+        for ((source, reason), value) in &self.failed {
+            out.push_str(&format!(
+                "{name}{{feed=\"{}\",reason=\"{reason}\"}} {value}\n",
+                escape_label(source)
+            ));
+        }
+        let name = format!("{PREFIX}_feeds_failed_total");
+        header(
+            &mut out,
+            &name,
+            "Feed runs that kept the previous outputs",
+            "counter",
+        );
+        for ((source, reason), value) in &self.feeds_failed {
+            out.push_str(&format!(
+                "{name}{{feed=\"{}\",reason=\"{reason}\"}} {value}\n",
+                escape_label(source)
+            ));
+        }
+
+        histograms(
+            &mut out,
+            "fetch_duration_seconds",
+            "Time to download an article page",
+            &self.fetch_seconds,
+        );
+        histograms(
+            &mut out,
+            "article_body_chars",
+            "Length of extracted article bodies in characters",
+            &self.body_chars,
+        );
+        let name = format!("{PREFIX}_run_duration_seconds");
+        header(
+            &mut out,
+            &name,
+            "Time to generate every due feed",
+            "histogram",
+        );
+        histogram(&mut out, &name, "", &self.run_seconds);
+
+        let name = format!("{PREFIX}_last_success_timestamp_seconds");
+        header(
+            &mut out,
+            &name,
+            "Unix time the feed last passed its health checks and wrote its outputs",
+            "gauge",
+        );
+        for (source, timestamp) in &self.last_success {
+            out.push_str(&format!(
+                "{name}{{feed=\"{}\"}} {timestamp}\n",
+                escape_label(source)
+            ));
+        }
+        out
+    }
+
+    /// Write a node-exporter textfile. Feeds that did not succeed in this
+    /// process keep the success time recorded in the previous file.
+    pub fn write_textfile(&mut self, path: &Path) -> Result<(), Error> {
+        if let Ok(previous) = fs::read_to_string(path) {
+            for (source, timestamp) in parse_last_success(&previous) {
+                self.last_success.entry(source).or_insert(timestamp);
+            }
+        }
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(Error::io(parent))?;
+        }
+        // node-exporter may read the file at any moment; never show it half written
+        let temp = path.with_extension("prom.tmp");
+        fs::write(&temp, self.render()).map_err(Error::io(&temp))?;
+        fs::rename(&temp, path).map_err(Error::io(path))
+    }
+}
+
+fn header(out: &mut String, name: &str, help: &str, kind: &str) {
+    out.push_str(&format!("# HELP {name} {help}\n# TYPE {name} {kind}\n"));
+}
+
+fn counter(out: &mut String, name: &str, help: &str, values: &BTreeMap<String, u64>) {
+    let name = format!("{PREFIX}_{name}");
+    header(out, &name, help, "counter");
+    for (source, value) in values {
+        out.push_str(&format!(
+            "{name}{{feed=\"{}\"}} {value}\n",
+            escape_label(source)
+        ));
+    }
+}
+
+fn histograms(out: &mut String, name: &str, help: &str, values: &BTreeMap<String, Histogram>) {
+    let name = format!("{PREFIX}_{name}");
+    header(out, &name, help, "histogram");
+    for (source, histogram_value) in values {
+        let labels = format!("feed=\"{}\",", escape_label(source));
+        histogram(out, &name, &labels, histogram_value);
+    }
+}
+
+/// `labels` is empty or ends with a comma, ready to be followed by `le`
+fn histogram(out: &mut String, name: &str, labels: &str, histogram: &Histogram) {
+    let mut cumulative = 0;
+    for (bound, count) in histogram.buckets.iter().zip(&histogram.counts) {
+        cumulative += count;
+        out.push_str(&format!(
+            "{name}_bucket{{{labels}le=\"{bound}\"}} {cumulative}\n"
+        ));
+    }
+    out.push_str(&format!(
+        "{name}_bucket{{{labels}le=\"+Inf\"}} {}\n",
+        histogram.count
+    ));
+    let labels = labels.trim_end_matches(',');
+    let labels = if labels.is_empty() {
+        String::new()
+    } else {
+        format!("{{{labels}}}")
+    };
+    out.push_str(&format!("{name}_sum{labels} {}\n", histogram.sum));
+    out.push_str(&format!("{name}_count{labels} {}\n", histogram.count));
+}
+
+fn escape_label(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('"', "\\\"")
+        .replace('\n', "\\n")
+}
+
+fn parse_last_success(text: &str) -> Vec<(String, i64)> {
+    let prefix = format!("{PREFIX}_last_success_timestamp_seconds{{feed=\"");
+    text.lines()
+        .filter_map(|line| {
+            let (source, timestamp) = line.strip_prefix(&prefix)?.rsplit_once("\"} ")?;
+            Some((source.to_string(), timestamp.trim().parse().ok()?))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::{Error, Stage};
+    use crate::run_report::{ArticleReport, FeedReport};
+    use crate::test_support::temp_dir;
+
+    fn report() -> RunReport {
+        let mut ok = ArticleReport::new("https://ledge.ai/articles/a", "A");
+        ok.http_status = Some(200);
+        ok.fetch_ms = Some(300);
+        ok.content_chars = Some(4200);
+
+        let mut cached = ArticleReport::new("https://ledge.ai/articles/b", "B");
+        cached.cached = true;
+        cached.content_chars = Some(800);
+
+        let mut not_found = ArticleReport::new("https://ledge.ai/articles/c", "C");
+        not_found.http_status = Some(404);
+        not_found.fetch_ms = Some(50);
+        not_found.fail(&Error::Http {
+            stage: Stage::Article,
+            url: not_found.url.clone(),
+            status: 404,
+        });
+
+        let mut unreachable = ArticleReport::new("https://ledge.ai/articles/d", "D");
+        unreachable.fail(&Error::Timeout {
+            stage: Stage::Article,
+            url: unreachable.url.clone(),
+        });
+
+        let mut report = RunReport::new(Utc::now());
+        report.duration_ms = 45_000;
+        report.feeds.push(FeedReport {
+            source: "ledge_ai".to_string(),
+            feed: "feeds/rss.xml".to_string(),
+            duration_ms: 45_000,
+            articles: vec![ok, cached, not_found, unreachable],
+            health_failures: Vec::new(),
+            error: None,
+        });
+        report
+    }
+
+    #[test]
+    fn test_render_counters_and_histograms() {
+        let mut metrics = Metrics::default();
+        metrics.record_run(&report());
+        metrics.record_failure("feeds/rss.xml", "no_listing_data");
+        metrics.record_success(
+            "feeds/rss.xml",
+            DateTime::parse_from_rfc3339("2025-07-14T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+        );
+        let text = metrics.render();
+        let lines: Vec<&str> = text.lines().collect();
+
+        for line in [
+            "# TYPE ledge_ai_feed_articles_discovered_total counter",
+            "ledge_ai_feed_articles_discovered_total{feed=\"feeds/rss.xml\"} 4",
+            "ledge_ai_feed_articles_fetched_total{feed=\"feeds/rss.xml\"} 2",
+            "ledge_ai_feed_articles_extracted_total{feed=\"feeds/rss.xml\"} 2",
+            "ledge_ai_feed_articles_failed_total{feed=\"feeds/rss.xml\",reason=\"timeout\"} 1",
+            "ledge_ai_feed_articles_failed_total{feed=\"feeds/rss.xml\",reason=\"http_status\"} 1",
+            "ledge_ai_feed_feeds_failed_total{feed=\"feeds/rss.xml\",reason=\"no_listing_data\"} 1",
+            "ledge_ai_feed_fetch_duration_seconds_bucket{feed=\"feeds/rss.xml\",le=\"0.1\"} 1",
+            "ledge_ai_feed_fetch_duration_seconds_bucket{feed=\"feeds/rss.xml\",le=\"0.5\"} 2",
+            "ledge_ai_feed_fetch_duration_seconds_bucket{feed=\"feeds/rss.xml\",le=\"+Inf\"} 2",
+            "ledge_ai_feed_fetch_duration_seconds_sum{feed=\"feeds/rss.xml\"} 0.35",
+            "ledge_ai_feed_fetch_duration_seconds_count{feed=\"feeds/rss.xml\"} 2",
+            "ledge_ai_feed_article_body_chars_bucket{feed=\"feeds/rss.xml\",le=\"1000\"} 1",
+            "ledge_ai_feed_article_body_chars_bucket{feed=\"feeds/rss.xml\",le=\"5000\"} 2",
+            "ledge_ai_feed_run_duration_seconds_bucket{le=\"60\"} 1",
+            "ledge_ai_feed_run_duration_seconds_sum 45",
+            "ledge_ai_feed_last_success_timestamp_seconds{feed=\"feeds/rss.xml\"} 1752451200",
+        ] {
+            assert!(lines.contains(&line), "missing {line} in\n{text}");
+        }
+    }
+
+    #[test]
+    fn test_textfile_keeps_previous_success() {
+        let dir = temp_dir("metrics");
+        let path = dir.join("ledge_ai_feed.prom");
+
+        let mut first = Metrics::default();
+        first.record_success("feeds/rss.xml", Utc::now());
+        first.record_success("feeds/business.xml", Utc::now());
+        first.write_textfile(&path).unwrap();
+        let previous = parse_last_success(&fs::read_to_string(&path).unwrap());
+        assert_eq!(previous.len(), 2);
+
+        // The second run only succeeds for one feed
+        let mut second = Metrics::default();
+        second.record_success("feeds/rss.xml", Utc::now() + chrono::Duration::hours(1));
+        second.write_textfile(&path).unwrap();
+        let current = parse_last_success(&fs::read_to_string(&path).unwrap());
+        assert_eq!(current[0], previous[0]);
+        assert!(current[1].1 > previous[1].1);
+        assert!(!path.with_extension("prom.tmp").exists());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
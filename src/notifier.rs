@@ -0,0 +1,344 @@
+use crate::config::{WebhookConfig, WebhookPreset};
+use crate::error::{Error, Stage};
+use crate::http_client::HttpClient;
+use crate::rss_item::RssItem;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde_json::{json, Map, Value};
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// First retry waits this long, each further one twice as long
+const RETRY_BASE_DELAY: Duration = Duration::from_secs(1);
+
+static PLACEHOLDER_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"\{(\w+)\}").unwrap());
+
+/// Post each article to the webhooks whose URLs are listed with it, oldest
+/// first so chats read in order. Returns the `(link, webhook URL)` pairs that
+/// were delivered (or, in dry-run mode, printed).
+pub async fn notify_new_items(
+    client: &HttpClient,
+    webhooks: &[WebhookConfig],
+    announcements: &[(RssItem, Vec<String>)],
+    dry_run: bool,
+) -> Vec<(String, String)> {
+    let mut delivered = Vec::new();
+    for (item, urls) in announcements.iter().rev() {
+        for webhook in webhooks
+            .iter()
+            .filter(|webhook| urls.contains(&webhook.url))
+        {
+            let payload = render_payload(webhook, item);
+            if dry_run {
+                info!(
+                    webhook = %webhook.url,
+                    article = %item.link,
+                    payload = %payload,
+                    "Dry run: webhook not sent"
+                );
+            } else if let Err(e) = send_with_retry(
+                client,
+                &webhook.url,
+                &payload,
+                webhook.retries,
+                RETRY_BASE_DELAY,
+            )
+            .await
+            {
+                warn!(
+                    webhook = %webhook.url,
+                    article = %item.link,
+                    error = %e,
+                    "Webhook delivery failed"
+                );
+                continue;
+            }
+            delivered.push((item.link.clone(), webhook.url.clone()));
+        }
+    }
+    delivered
+}
+
+/// Fill the webhook's template (or preset) with the item's fields
+pub fn render_payload(webhook: &WebhookConfig, item: &RssItem) -> Value {
+    let template = match (&webhook.template, webhook.preset) {
+        (Some(template), _) => template.clone(),
+        (None, Some(preset)) => preset_template(preset),
+        (None, None) => json!({
+            "title": "{title}",
+            "link": "{link}",
+            "summary": "{summary}",
+            "image": "{image}",
+            "category": "{category}",
+            "author": "{author}",
+            "published": "{published}",
+        }),
+    };
+    render(&template, item).unwrap_or(Value::Object(Map::new()))
+}
+
+fn preset_template(preset: WebhookPreset) -> Value {
+    match preset {
+        WebhookPreset::Slack => json!({
+            "text": "<{link}|{title}>",
+            "blocks": [
+                {
+                    "type": "section",
+                    "text": { "type": "mrkdwn", "text": "*<{link}|{title}>*\n{summary}" },
+                    "accessory": { "type": "image", "image_url": "{image}", "alt_text": "{title}" }
+                }
+            ]
+        }),
+        WebhookPreset::Discord => json!({
+            "embeds": [
+                {
+                    "title": "{title}",
+                    "url": "{link}",
+                    "description": "{summary}",
+                    "image": { "url": "{image}" },
+                    "footer": { "text": "{category}" },
+                    "timestamp": "{published}"
+                }
+            ]
+        }),
+    }
+}
+
+/// Replace `{field}` placeholders in every string. Fields that render empty
+/// are dropped, along with objects left without the keys they need (e.g. an
+/// image block without an image), so chat APIs do not reject the payload.
+fn render(template: &Value, item: &RssItem) -> Option<Value> {
+    match template {
+        Value::String(text) => {
+            let rendered = PLACEHOLDER_PATTERN.replace_all(text, |caps: &regex::Captures| {
+                field(item, &caps[1]).unwrap_or_else(|| caps[0].to_string())
+            });
+            (!rendered.is_empty()).then(|| Value::String(rendered.into_owned()))
+        }
+        Value::Array(values) => Some(Value::Array(
+            values
+                .iter()
+                .filter_map(|value| render(value, item))
+                .collect(),
+        )),
+        Value::Object(object) => {
+            let mut rendered = Map::new();
+            for (key, value) in object {
+                match render(value, item) {
+                    Some(value) => {
+                        rendered.insert(key.clone(), value);
+                    }
+                    // A required string went missing; drop the whole object
+                    None if matches!(key.as_str(), "url" | "image_url") => return None,
+                    None => {}
+                }
+            }
+            (!rendered.is_empty()).then_some(Value::Object(rendered))
+        }
+        other => Some(other.clone()),
+    }
+}
+
+fn field(item: &RssItem, name: &str) -> Option<String> {
+    let value = match name {
+        "title" => item.title.clone(),
+        "link" => item.link.clone(),
+        "summary" => item.summary.clone().unwrap_or_default(),
+        "image" => item.image().unwrap_or_default(),
+        "category" => item.category.clone().unwrap_or_default(),
+        "tags" => item.tags.join(", "),
+        "author" => item.author.clone().unwrap_or_default(),
+        "published" => item.pub_date.to_rfc3339(),
+        _ => return None,
+    };
+    Some(value)
+}
+
+/// POST the payload, retrying network errors, 429 and 5xx responses with
+/// exponential backoff. Other error statuses fail immediately.
+pub async fn send_with_retry(
+    client: &HttpClient,
+    url: &str,
+    payload: &Value,
+    retries: u32,
+    base_delay: Duration,
+) -> Result<(), Error> {
+    let mut attempt = 0;
+    loop {
+        let error = match client.post_json(url, payload, Stage::Webhook).await {
+            Ok(status) if (200..300).contains(&status) => return Ok(()),
+            Ok(status) => {
+                let error = Error::Http {
+                    stage: Stage::Webhook,
+                    url: url.to_string(),
+                    status,
+                };
+                if status != 429 && status < 500 {
+                    return Err(error);
+                }
+                error
+            }
+            Err(e) => e,
+        };
+        if attempt >= retries {
+            return Err(error);
+        }
+        let delay = base_delay * 2u32.pow(attempt);
+        warn!(
+            error = %error,
+            delay_ms = delay.as_millis() as u64,
+            "Webhook delivery failed, retrying"
+        );
+        tokio::time::sleep(delay).await;
+        attempt += 1;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record_requests;
+    use chrono::{DateTime, Utc};
+
+    fn webhook(preset: Option<WebhookPreset>, template: Option<Value>) -> WebhookConfig {
+        WebhookConfig {
+            url: "https://hooks.example.com/x".to_string(),
+            preset,
+            template,
+            retries: 0,
+        }
+    }
+
+    fn item() -> RssItem {
+        RssItem {
+            title: "生成AIの新モデル".to_string(),
+            link: "https://ledge.ai/articles/new-model".to_string(),
+            description:
+                r#"<p><img src="https://ledge.ai/img/a.jpg?w=1&amp;h=2" alt=""></p><p>本文</p>"#
+                    .to_string(),
+            pub_date: DateTime::parse_from_rfc3339("2025-07-14T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            summary: Some("要約です。".to_string()),
+            category: Some("ビジネス".to_string()),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_render_custom_template() {
+        let template = json!({
+            "message": "{title} ({category})",
+            "url": "{link}",
+            "count": 1,
+            "unknown": "{nope}"
+        });
+
+        assert_eq!(
+            render_payload(&webhook(None, Some(template)), &item()),
+            json!({
+                "message": "生成AIの新モデル (ビジネス)",
+                "url": "https://ledge.ai/articles/new-model",
+                "count": 1,
+                "unknown": "{nope}"
+            })
+        );
+    }
+
+    #[test]
+    fn test_render_presets() {
+        let slack = render_payload(&webhook(Some(WebhookPreset::Slack), None), &item());
+        assert_eq!(
+            slack["text"],
+            "<https://ledge.ai/articles/new-model|生成AIの新モデル>"
+        );
+        assert_eq!(
+            slack["blocks"][0]["accessory"]["image_url"],
+            "https://ledge.ai/img/a.jpg?w=1&h=2"
+        );
+
+        let discord = render_payload(&webhook(Some(WebhookPreset::Discord), None), &item());
+        assert_eq!(discord["embeds"][0]["title"], "生成AIの新モデル");
+        assert_eq!(discord["embeds"][0]["description"], "要約です。");
+        assert_eq!(
+            discord["embeds"][0]["timestamp"],
+            "2025-07-14T00:00:00+00:00"
+        );
+    }
+
+    #[test]
+    fn test_render_drops_empty_fields() {
+        let plain = RssItem {
+            description: "<p>画像なし</p>".to_string(),
+            summary: None,
+            ..item()
+        };
+
+        let discord = render_payload(&webhook(Some(WebhookPreset::Discord), None), &plain);
+        assert!(discord["embeds"][0].get("image").is_none());
+        assert!(discord["embeds"][0].get("description").is_none());
+
+        let slack = render_payload(&webhook(Some(WebhookPreset::Slack), None), &plain);
+        assert!(slack["blocks"][0].get("accessory").is_none());
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_gives_up_after_retries() {
+        let (base, requests) = record_requests("503 Service Unavailable").await;
+
+        let result = send_with_retry(
+            &HttpClient::new(),
+            &base,
+            &json!({"text": "hi"}),
+            2,
+            Duration::ZERO,
+        )
+        .await;
+
+        assert!(result.is_err());
+        assert_eq!(requests.lock().unwrap().len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_send_with_retry_does_not_retry_client_errors() {
+        let (base, requests) = record_requests("400 Bad Request").await;
+
+        let result = send_with_retry(
+            &HttpClient::new(),
+            &base,
+            &json!({"text": "hi"}),
+            2,
+            Duration::ZERO,
+        )
+        .await;
+
+        assert!(matches!(result, Err(Error::Http { status: 400, .. })));
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_notify_new_items_posts_payloads() {
+        let (base, requests) = record_requests("200 OK").await;
+        let hooks = vec![WebhookConfig {
+            url: format!("{base}/hook"),
+            ..webhook(Some(WebhookPreset::Discord), None)
+        }];
+
+        let announcements = vec![(item(), vec![format!("{base}/hook")])];
+        let sent = notify_new_items(&HttpClient::new(), &hooks, &announcements, false).await;
+        assert_eq!(sent, vec![(item().link, format!("{base}/hook"))]);
+        {
+            let requests = requests.lock().unwrap();
+            assert!(requests[0].starts_with("POST /hook HTTP/1.1"));
+            assert!(requests[0].contains("生成AIの新モデル"));
+        }
+
+        let dry_run = notify_new_items(&HttpClient::new(), &hooks, &announcements, true).await;
+        assert_eq!(dry_run.len(), 1);
+        // Webhooks the article is not pending for are skipped
+        let elsewhere = vec![(item(), vec!["https://hooks.example.com/other".to_string()])];
+        let skipped = notify_new_items(&HttpClient::new(), &hooks, &elsewhere, false).await;
+        assert!(skipped.is_empty());
+        assert_eq!(requests.lock().unwrap().len(), 1);
+    }
+}
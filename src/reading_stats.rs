@@ -0,0 +1,130 @@
+use crate::article_extractor::strip_markdown;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+
+// Typical silent reading speeds
+const JAPANESE_CHARS_PER_MINUTE: f64 = 500.0;
+const ENGLISH_WORDS_PER_MINUTE: f64 = 200.0;
+
+static ENGLISH_WORD_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"[A-Za-z]+(?:['’.-][A-Za-z]+)*").unwrap());
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ReadingStats {
+    /// Visible characters, excluding whitespace and markup
+    pub char_count: usize,
+    /// Words in English (Latin script) passages
+    pub word_count: usize,
+    /// Estimated reading time, rounded up to whole minutes
+    pub minutes: u32,
+}
+
+impl ReadingStats {
+    /// Short Japanese summary line, e.g. "約7分で読めます（3,200文字）"
+    pub fn label(&self) -> String {
+        format!(
+            "約{}分で読めます（{}文字）",
+            self.minutes,
+            format_thousands(self.char_count)
+        )
+    }
+}
+
+/// Count characters and estimate reading time for extracted markdown.
+/// Japanese text is timed per character, English passages per word.
+pub fn compute_reading_stats(markdown: &str) -> ReadingStats {
+    let text = strip_markdown(markdown);
+
+    let char_count = text.chars().filter(|c| !c.is_whitespace()).count();
+    let japanese_chars = text.chars().filter(|c| is_japanese(*c)).count();
+    let word_count = ENGLISH_WORD_PATTERN.find_iter(&text).count();
+
+    let minutes = japanese_chars as f64 / JAPANESE_CHARS_PER_MINUTE
+        + word_count as f64 / ENGLISH_WORDS_PER_MINUTE;
+    let minutes = if char_count == 0 {
+        0
+    } else {
+        (minutes.ceil() as u32).max(1)
+    };
+
+    ReadingStats {
+        char_count,
+        word_count,
+        minutes,
+    }
+}
+
+fn is_japanese(c: char) -> bool {
+    matches!(c,
+        '\u{3000}'..='\u{303F}' // CJK punctuation
+        | '\u{3040}'..='\u{309F}' // Hiragana
+        | '\u{30A0}'..='\u{30FF}' // Katakana
+        | '\u{4E00}'..='\u{9FFF}' // CJK unified ideographs
+        | '\u{FF00}'..='\u{FFEF}' // Full-width forms
+    )
+}
+
+fn format_thousands(n: usize) -> String {
+    let digits = n.to_string();
+    let mut result = String::new();
+    for (i, c) in digits.chars().enumerate() {
+        if i > 0 && (digits.len() - i) % 3 == 0 {
+            result.push(',');
+        }
+        result.push(c);
+    }
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_compute_reading_stats_japanese() {
+        let markdown = "あ".repeat(1200);
+        let stats = compute_reading_stats(&markdown);
+        assert_eq!(stats.char_count, 1200);
+        assert_eq!(stats.word_count, 0);
+        assert_eq!(stats.minutes, 3);
+    }
+
+    #[test]
+    fn test_compute_reading_stats_english_words() {
+        let markdown = "word ".repeat(400);
+        let stats = compute_reading_stats(&markdown);
+        assert_eq!(stats.word_count, 400);
+        assert_eq!(stats.minutes, 2);
+    }
+
+    #[test]
+    fn test_compute_reading_stats_skips_numbers() {
+        let stats = compute_reading_stats("2025年7月14日、OpenAIがGPT-4.5を発表した");
+        assert_eq!(stats.word_count, 2);
+    }
+
+    #[test]
+    fn test_compute_reading_stats_ignores_markup() {
+        let markdown = "## 見出し\n\n[リンク](https://example.com/very/long/url) と ![画像](https://example.com/a.png) **強調**";
+        let stats = compute_reading_stats(markdown);
+        // 見出し + リンク + と + 強調
+        assert_eq!(stats.char_count, 9);
+        assert_eq!(stats.minutes, 1);
+    }
+
+    #[test]
+    fn test_compute_reading_stats_empty() {
+        assert_eq!(compute_reading_stats("  \n "), ReadingStats::default());
+    }
+
+    #[test]
+    fn test_reading_stats_label() {
+        let stats = ReadingStats {
+            char_count: 3200,
+            word_count: 12,
+            minutes: 7,
+        };
+        assert_eq!(stats.label(), "約7分で読めます（3,200文字）");
+    }
+}
@@ -1,26 +1,87 @@
+use crate::error::Error;
+use crate::reading_stats::ReadingStats;
 use crate::rss_item::RssItem;
-use rss::{ChannelBuilder, ItemBuilder};
+use crate::source::{ChannelInfo, FeedLinks};
+use chrono::{DateTime, Utc};
+use rss::extension::atom::{AtomExtension, Link};
+use rss::extension::dublincore::DublinCoreExtension;
+use rss::extension::{Extension, ExtensionBuilder, ExtensionMap};
+use rss::{CategoryBuilder, ChannelBuilder, GuidBuilder, ItemBuilder};
+use std::collections::BTreeMap;
 use std::io::Cursor;
 
-pub fn generate_rss(items: Vec<RssItem>) -> Result<String, Box<dyn std::error::Error>> {
+/// Namespace for feed extensions specific to this generator (reading time etc.)
+pub const LEDGE_NAMESPACE_PREFIX: &str = "ledge";
+pub const LEDGE_NAMESPACE_URI: &str = "https://yoshiori.github.io/ledge-ai-feed/ns/1.0";
+
+pub fn generate_rss(
+    channel_info: &ChannelInfo,
+    links: FeedLinks,
+    items: Vec<RssItem>,
+) -> Result<String, Error> {
     let mut channel = ChannelBuilder::default()
-        .title("Ledge.ai 新着記事")
-        .link("https://ledge.ai/")
-        .description("Ledge.ai の最新テクノロジー記事")
+        .title(channel_info.title.clone())
+        .link(channel_info.link.clone())
+        .description(channel_info.description.clone())
+        .language(Some(channel_info.language.clone()))
+        .namespaces(BTreeMap::from([(
+            LEDGE_NAMESPACE_PREFIX.to_string(),
+            LEDGE_NAMESPACE_URI.to_string(),
+        )]))
         .build();
+    channel.set_atom_ext(atom_links(links));
 
     let rss_items: Vec<rss::Item> = items
         .into_iter()
         .map(|item| {
+            // With a summary, <description> carries the summary and the full
+            // article moves to <content:encoded>
+            let (body, content) = match &item.summary {
+                Some(summary) => (
+                    format!("<p>{}</p>", escape_html(summary)),
+                    Some(item.description.clone()),
+                ),
+                None => (item.description.clone(), None),
+            };
+            let description = match &item.reading_stats {
+                Some(stats) => format!("<p>{}</p>{body}", stats.label()),
+                None => body,
+            };
+
+            let categories = item
+                .category
+                .iter()
+                .chain(item.tags.iter())
+                .map(|name| CategoryBuilder::default().name(name.clone()).build())
+                .collect::<Vec<_>>();
+
+            // Plain links stay the id readers already know; re-surfaced items
+            // get a fresh, non-permalink guid
+            let guid = item.resurfaced.then(|| {
+                GuidBuilder::default()
+                    .value(item.guid())
+                    .permalink(false)
+                    .build()
+            });
+            let extensions = ledge_extensions(&item.reading_stats, &item.updated);
+
             ItemBuilder::default()
+                .guid(guid)
                 .title(Some(item.title))
                 .link(Some(item.link))
-                .description(Some(item.description))
+                .description(Some(description))
+                .content(content)
+                .categories(categories)
+                .dublin_core_ext(item.author.map(|author| DublinCoreExtension {
+                    creators: vec![author],
+                    ..Default::default()
+                }))
                 .pub_date(Some(
                     item.pub_date
                         .format("%a, %d %b %Y %H:%M:%S GMT")
                         .to_string(),
                 ))
+                .extensions(extensions)
                 .build()
         })
         .collect();
@@ -29,19 +90,88 @@ pub fn generate_rss(items: Vec<RssItem>) -> Result<String, Box<dyn std::error::E
 
     // Use rss crate's built-in pretty_write_to method for formatted XML output
     let mut buffer = Cursor::new(Vec::new());
-    channel.pretty_write_to(&mut buffer, b' ', 2)?;
+    channel
+        .pretty_write_to(&mut buffer, b' ', 2)
+        .map_err(Error::feed_write("rss"))?;
 
-    let pretty_xml = String::from_utf8(buffer.into_inner())?;
+    let pretty_xml = String::from_utf8(buffer.into_inner()).map_err(Error::feed_write("rss"))?;
 
     // No additional processing needed at this stage
 
     Ok(pretty_xml)
 }
 
+/// `<atom:link>` elements for the feed's own URL and its WebSub hub
+fn atom_links(links: FeedLinks) -> Option<AtomExtension> {
+    let link = |rel: &str, href: &str| {
+        let mut link = Link::default();
+        link.set_rel(rel);
+        link.set_href(href);
+        if rel == "self" {
+            link.set_mime_type(Some("application/rss+xml".to_string()));
+        }
+        link
+    };
+    let atom_links: Vec<Link> = links
+        .self_url
+        .map(|url| link("self", url))
+        .into_iter()
+        .chain(links.hub.map(|hub| link("hub", hub)))
+        .collect();
+
+    (!atom_links.is_empty()).then_some(AtomExtension { links: atom_links })
+}
+
+pub fn escape_html(text: &str) -> String {
+    text.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+}
+
+/// Escape a value for a double-quoted HTML attribute
+pub fn escape_attr(text: &str) -> String {
+    escape_html(text).replace('"', "&quot;")
+}
+
+/// Build `<ledge:*>` elements for the item metadata we compute ourselves
+fn ledge_extensions(
+    reading_stats: &Option<ReadingStats>,
+    updated: &Option<DateTime<Utc>>,
+) -> ExtensionMap {
+    let mut elements: BTreeMap<String, Vec<Extension>> = BTreeMap::new();
+    let mut add = |name: &str, value: String| {
+        let qualified = format!("{LEDGE_NAMESPACE_PREFIX}:{name}");
+        elements.insert(
+            qualified.clone(),
+            vec![ExtensionBuilder::default()
+                .name(qualified)
+                .value(Some(value))
+                .build()],
+        );
+    };
+
+    if let Some(stats) = reading_stats {
+        add("charCount", stats.char_count.to_string());
+        add("wordCount", stats.word_count.to_string());
+        add("readingTime", stats.minutes.to_string());
+    }
+    if let Some(updated) = updated {
+        add("updated", updated.to_rfc3339());
+    }
+
+    let mut extensions = ExtensionMap::new();
+    if !elements.is_empty() {
+        extensions.insert(LEDGE_NAMESPACE_PREFIX.to_string(), elements);
+    }
+    extensions
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::reading_stats::ReadingStats;
     use crate::rss_item::RssItem;
+    use crate::source::{LedgeAi, Source};
     use chrono::{DateTime, Utc};
     use std::io::Cursor;
 
@@ -60,16 +190,18 @@ mod tests {
                 link: "https://example.com/1".to_string(),
                 description: "<p>Content 1</p>".to_string(),
                 pub_date: date1,
+                ..Default::default()
             },
             RssItem {
                 title: "Article 2".to_string(),
                 link: "https://example.com/2".to_string(),
                 description: "<p>Content 2</p>".to_string(),
                 pub_date: date2,
+                ..Default::default()
             },
         ];
 
-        let result = generate_rss(items);
+        let result = generate_rss(&LedgeAi.channel(), FeedLinks::default(), items);
         assert!(result.is_ok());
 
         let rss_content = result.unwrap();
@@ -84,6 +216,83 @@ mod tests {
         assert!(rss_content.contains("  "));
     }
 
+    #[test]
+    fn test_generate_rss_includes_reading_stats() {
+        let items = vec![RssItem {
+            title: "Long Article".to_string(),
+            link: "https://example.com/long".to_string(),
+            description: "<p>Body</p>".to_string(),
+            reading_stats: Some(ReadingStats {
+                char_count: 3200,
+                word_count: 10,
+                minutes: 7,
+            }),
+            ..Default::default()
+        }];
+
+        let rss_content = generate_rss(&LedgeAi.channel(), FeedLinks::default(), items).unwrap();
+        assert!(rss_content.contains(&format!(r#"xmlns:ledge="{LEDGE_NAMESPACE_URI}""#)));
+        assert!(rss_content.contains("<ledge:charCount>3200</ledge:charCount>"));
+        assert!(rss_content.contains("<ledge:readingTime>7</ledge:readingTime>"));
+        assert!(rss_content.contains("約7分で読めます（3,200文字）"));
+    }
+
+    #[test]
+    fn test_generate_rss_uses_summary_for_description() {
+        let items = vec![RssItem {
+            title: "Summarized Article".to_string(),
+            link: "https://example.com/summary".to_string(),
+            description: "<p>Full body</p>".to_string(),
+            summary: Some("要約の一文目。R&Dの二文目。".to_string()),
+            ..Default::default()
+        }];
+
+        let rss_content = generate_rss(&LedgeAi.channel(), FeedLinks::default(), items).unwrap();
+        assert!(rss_content.contains("xmlns:content="));
+        assert!(rss_content.contains(
+            "<description><![CDATA[<p>要約の一文目。R&amp;Dの二文目。</p>]]></description>"
+        ));
+        assert!(
+            rss_content.contains("<content:encoded><![CDATA[<p>Full body</p>]]></content:encoded>")
+        );
+    }
+
+    #[test]
+    fn test_generate_rss_includes_categories_and_author() {
+        let items = vec![RssItem {
+            title: "Categorized Article".to_string(),
+            link: "https://example.com/categorized".to_string(),
+            category: Some("ビジネス".to_string()),
+            tags: vec!["生成AI".to_string()],
+            author: Some("Ledge編集部".to_string()),
+            ..Default::default()
+        }];
+
+        let rss_content = generate_rss(&LedgeAi.channel(), FeedLinks::default(), items).unwrap();
+        assert!(rss_content.contains("<category>ビジネス</category>"));
+        assert!(rss_content.contains("<category>生成AI</category>"));
+        assert!(rss_content.contains("<dc:creator>Ledge編集部</dc:creator>"));
+        assert!(rss_content.contains("xmlns:dc="));
+    }
+
+    #[test]
+    fn test_generate_rss_includes_websub_links() {
+        let links = FeedLinks {
+            self_url: Some("https://example.com/rss.xml"),
+            hub: Some("https://hub.example.com/"),
+        };
+        let rss_content = generate_rss(&LedgeAi.channel(), links, Vec::new()).unwrap();
+
+        assert!(rss_content.contains("xmlns:atom=\"http://www.w3.org/2005/Atom\""));
+        assert!(rss_content.contains("<atom:link href=\"https://hub.example.com/\" rel=\"hub\""));
+        assert!(
+            rss_content.contains("<atom:link href=\"https://example.com/rss.xml\" rel=\"self\"")
+        );
+
+        let plain = generate_rss(&LedgeAi.channel(), FeedLinks::default(), Vec::new()).unwrap();
+        assert!(!plain.contains("atom:link"));
+    }
+
     #[test]
     fn test_rss_crate_pretty_write_to() {
         let test_date = DateTime::parse_from_rfc3339("2025-01-14T10:00:00+09:00")
@@ -95,6 +304,7 @@ mod tests {
             link: "https://example.com/test".to_string(),
             description: "<p>Test Content</p>".to_string(),
             pub_date: test_date,
+            ..Default::default()
         }];
 
         let mut channel = rss::ChannelBuilder::default()
@@ -146,9 +356,10 @@ mod tests {
             link: "https://example.com/simple".to_string(),
             description: "Simple Content".to_string(),
             pub_date: simple_date,
+            ..Default::default()
         }];
 
-        let result = generate_rss(items);
+        let result = generate_rss(&LedgeAi.channel(), FeedLinks::default(), items);
         assert!(result.is_ok());
 
         let rss_content = result.unwrap();
@@ -1,10 +1,55 @@
+use crate::reading_stats::ReadingStats;
 use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
 
+static IMG_SRC_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r#"(?is)<img\b[^>]*?\bsrc="([^"]+)""#).unwrap());
+
+#[derive(Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
 pub struct RssItem {
     pub title: String,
     pub link: String,
     pub description: String,
+    /// Extracted article markdown, kept to diff later revisions
+    pub markdown: String,
     pub pub_date: DateTime<Utc>,
+    /// Set when the article was modified after publication
+    pub updated: Option<DateTime<Utc>>,
+    pub reading_stats: Option<ReadingStats>,
+    pub summary: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub author: Option<String>,
+    /// Significantly revised and announced again under a new id
+    pub resurfaced: bool,
+}
+
+impl RssItem {
+    /// Stable entry id; re-surfaced items get a new one so readers show them again
+    pub fn guid(&self) -> String {
+        match (self.resurfaced, self.updated) {
+            (true, Some(updated)) => format!("{}#updated-{}", self.link, updated.timestamp()),
+            _ => self.link.clone(),
+        }
+    }
+
+    /// Date used to order the feed: re-surfaced items move up to their update
+    pub fn sort_date(&self) -> DateTime<Utc> {
+        match (self.resurfaced, self.updated) {
+            (true, Some(updated)) => updated,
+            _ => self.pub_date,
+        }
+    }
+
+    /// URL of the first image in the description, used as the hero image
+    pub fn image(&self) -> Option<String> {
+        IMG_SRC_PATTERN
+            .captures(&self.description)
+            .map(|caps| caps[1].replace("&amp;", "&"))
+    }
 }
 
 #[cfg(test)]
@@ -22,11 +67,47 @@ mod tests {
             link: "https://example.com".to_string(),
             description: "Test Description".to_string(),
             pub_date: test_date,
+            ..Default::default()
         };
 
         assert_eq!(item.title, "Test Title");
         assert_eq!(item.link, "https://example.com");
         assert_eq!(item.description, "Test Description");
         assert_eq!(item.pub_date, test_date);
+        assert_eq!(item.updated, None);
+        assert_eq!(item.reading_stats, None);
+        assert_eq!(item.summary, None);
+        assert_eq!(item.category, None);
+        assert!(item.tags.is_empty());
+        assert_eq!(item.author, None);
+        assert!(!item.resurfaced);
+        assert_eq!(item.guid(), "https://example.com");
+        assert_eq!(item.sort_date(), test_date);
+        assert_eq!(item.image(), None);
+    }
+
+    #[test]
+    fn test_resurfaced_item_gets_new_guid_and_sort_date() {
+        let published = DateTime::parse_from_rfc3339("2025-01-14T12:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let updated = DateTime::parse_from_rfc3339("2025-02-01T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+        let mut item = RssItem {
+            link: "https://example.com/a".to_string(),
+            pub_date: published,
+            updated: Some(updated),
+            ..Default::default()
+        };
+        assert_eq!(item.guid(), "https://example.com/a");
+        assert_eq!(item.sort_date(), published);
+
+        item.resurfaced = true;
+        assert_eq!(
+            item.guid(),
+            format!("https://example.com/a#updated-{}", updated.timestamp())
+        );
+        assert_eq!(item.sort_date(), updated);
     }
 }
@@ -0,0 +1,270 @@
+use crate::error::Error;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use std::fs;
+use std::path::Path;
+use tracing::warn;
+
+/// Bodies shorter than this are reported as possibly truncated
+pub const SHORT_CONTENT_CHARS: usize = 500;
+
+/// Everything one run did to every article, written as JSON with `--report`
+#[derive(Debug, Serialize)]
+pub struct RunReport {
+    pub started_at: DateTime<Utc>,
+    pub duration_ms: u64,
+    pub feeds: Vec<FeedReport>,
+}
+
+#[derive(Debug, Serialize)]
+pub struct FeedReport {
+    pub source: String,
+    /// The feed's id, see [`crate::config::FeedConfig::id`]
+    pub feed: String,
+    pub duration_ms: u64,
+    pub articles: Vec<ArticleReport>,
+    /// Why the feed's outputs were left untouched
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub health_failures: Vec<String>,
+    /// Why the feed could not be generated at all
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ArticleStatus {
+    Ok,
+    /// Processed, but older than the crawl cutoff
+    Skipped,
+    Failed,
+}
+
+/// Diagnostics for one article, filled in while it is processed
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ArticleReport {
+    pub url: String,
+    pub title: String,
+    pub status: ArticleStatus,
+    /// `None` when the page came from this run's page cache or the request failed
+    pub http_status: Option<u16>,
+    pub fetch_ms: Option<u64>,
+    pub cached: bool,
+    /// Script pattern the body was found with, e.g. `body`
+    pub content_strategy: Option<String>,
+    /// Where the publication date came from, e.g. `nuxt` or `meta`
+    pub date_strategy: Option<String>,
+    pub content_chars: Option<usize>,
+    pub warnings: Vec<String>,
+    pub error: Option<String>,
+    /// Failure class of `error`, e.g. `http_status` or `no_content`
+    pub reason: Option<&'static str>,
+}
+
+impl ArticleReport {
+    pub fn new(url: &str, title: &str) -> Self {
+        Self {
+            url: url.to_string(),
+            title: title.to_string(),
+            status: ArticleStatus::Ok,
+            http_status: None,
+            fetch_ms: None,
+            cached: false,
+            content_strategy: None,
+            date_strategy: None,
+            content_chars: None,
+            warnings: Vec::new(),
+            error: None,
+            reason: None,
+        }
+    }
+
+    /// Record a problem that did not stop the article from being processed
+    pub fn warn(&mut self, message: impl Into<String>) {
+        let message = message.into();
+        warn!(url = %self.url, "{message}");
+        self.warnings.push(message);
+    }
+
+    pub fn fail(&mut self, error: &Error) {
+        self.status = ArticleStatus::Failed;
+        self.error = Some(error.to_string());
+        self.reason = Some(error.reason());
+    }
+}
+
+impl RunReport {
+    pub fn new(started_at: DateTime<Utc>) -> Self {
+        Self {
+            started_at,
+            duration_ms: 0,
+            feeds: Vec::new(),
+        }
+    }
+
+    pub fn articles(&self) -> impl Iterator<Item = &ArticleReport> {
+        self.feeds.iter().flat_map(|feed| &feed.articles)
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), Error> {
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(Error::io(parent))?;
+        }
+        let json = serde_json::to_string_pretty(self).map_err(|e| Error::io(path)(e.into()))?;
+        fs::write(path, json).map_err(Error::io(path))
+    }
+
+    /// One line per article, grouped by feed, for reading in a terminal
+    pub fn summary_table(&self) -> String {
+        let mut table = String::new();
+        for feed in &self.feeds {
+            let count = |status| {
+                feed.articles
+                    .iter()
+                    .filter(|article| article.status == status)
+                    .count()
+            };
+            let warnings: usize = feed.articles.iter().map(|a| a.warnings.len()).sum();
+            table.push_str(&format!(
+                "Feed {}: {} articles, {} ok, {} skipped, {} failed, {warnings} warnings ({:.1}s)\n",
+                feed.source,
+                feed.articles.len(),
+                count(ArticleStatus::Ok),
+                count(ArticleStatus::Skipped),
+                count(ArticleStatus::Failed),
+                feed.duration_ms as f64 / 1000.0
+            ));
+            for failure in &feed.health_failures {
+                table.push_str(&format!("Health check failed: {failure}\n"));
+            }
+            if let Some(error) = &feed.error {
+                table.push_str(&format!("Feed failed: {error}\n"));
+            }
+            table.push_str(&format!(
+                "{:<8} {:>4} {:>8} {:<16} {:<8} {:>6} {:>4}  URL\n",
+                "STATUS", "HTTP", "FETCH_MS", "CONTENT", "DATE", "CHARS", "WARN"
+            ));
+            for article in &feed.articles {
+                let status = match article.status {
+                    ArticleStatus::Ok => "ok",
+                    ArticleStatus::Skipped => "skipped",
+                    ArticleStatus::Failed => "failed",
+                };
+                let or_dash = |value: Option<String>| value.unwrap_or_else(|| "-".to_string());
+                table.push_str(&format!(
+                    "{status:<8} {:>4} {:>8} {:<16} {:<8} {:>6} {:>4}  {}\n",
+                    match (article.cached, article.http_status) {
+                        (true, _) => "hit".to_string(),
+                        (false, status) => or_dash(status.map(|s| s.to_string())),
+                    },
+                    or_dash(article.fetch_ms.map(|ms| ms.to_string())),
+                    or_dash(article.content_strategy.clone()),
+                    or_dash(article.date_strategy.clone()),
+                    or_dash(article.content_chars.map(|chars| chars.to_string())),
+                    article.warnings.len(),
+                    article.url
+                ));
+            }
+        }
+        table
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn report() -> RunReport {
+        let mut ok = ArticleReport::new("https://ledge.ai/articles/a", "A");
+        ok.http_status = Some(200);
+        ok.fetch_ms = Some(120);
+        ok.content_strategy = Some("body".to_string());
+        ok.date_strategy = Some("nuxt".to_string());
+        ok.content_chars = Some(4200);
+
+        let mut short = ArticleReport::new("https://ledge.ai/articles/b", "B");
+        short.cached = true;
+        short.content_strategy = Some("article_content".to_string());
+        short.content_chars = Some(320);
+        short.warn("Content is only 320 chars, it may be truncated");
+
+        let mut failed = ArticleReport::new("https://ledge.ai/articles/c", "C");
+        failed.http_status = Some(404);
+        failed.fail(&Error::Http {
+            stage: crate::error::Stage::Article,
+            url: "https://ledge.ai/articles/c".to_string(),
+            status: 404,
+        });
+
+        RunReport {
+            started_at: DateTime::parse_from_rfc3339("2025-07-14T00:00:00Z")
+                .unwrap()
+                .with_timezone(&Utc),
+            duration_ms: 1500,
+            feeds: vec![FeedReport {
+                source: "ledge_ai".to_string(),
+                feed: "feeds/rss.xml".to_string(),
+                duration_ms: 1500,
+                articles: vec![ok, short, failed],
+                health_failures: Vec::new(),
+                error: None,
+            }],
+        }
+    }
+
+    #[test]
+    fn test_report_json() {
+        let json = serde_json::to_value(report()).unwrap();
+        let articles = &json["feeds"][0]["articles"];
+        assert_eq!(articles[0]["status"], "ok");
+        assert_eq!(articles[0]["content_strategy"], "body");
+        assert_eq!(
+            articles[1]["warnings"][0],
+            "Content is only 320 chars, it may be truncated"
+        );
+        assert_eq!(articles[2]["status"], "failed");
+        assert_eq!(
+            articles[2]["error"],
+            "article request to https://ledge.ai/articles/c returned HTTP 404"
+        );
+        assert_eq!(articles[2]["reason"], "http_status");
+        assert_eq!(articles[2]["http_status"], 404);
+    }
+
+    #[test]
+    fn test_summary_table() {
+        let table = report().summary_table();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[0],
+            "Feed ledge_ai: 3 articles, 2 ok, 0 skipped, 1 failed, 1 warnings (1.5s)"
+        );
+        assert!(
+            lines[2].starts_with("ok        200      120 body             nuxt       4200    0")
+        );
+        assert!(
+            lines[3].starts_with("ok        hit        - article_content  -           320    1")
+        );
+        assert!(lines[4].starts_with("failed    404"));
+        assert!(lines[4].ends_with("https://ledge.ai/articles/c"));
+    }
+
+    #[test]
+    fn test_summary_table_health_failures() {
+        let mut report = report();
+        report.feeds[0].health_failures = vec!["Only 3 items, expected at least 10".to_string()];
+        let table = report.summary_table();
+        let lines: Vec<&str> = table.lines().collect();
+        assert_eq!(
+            lines[1],
+            "Health check failed: Only 3 items, expected at least 10"
+        );
+        assert!(lines[2].starts_with("STATUS"));
+
+        let json = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            json["feeds"][0]["health_failures"][0],
+            "Only 3 items, expected at least 10"
+        );
+    }
+}
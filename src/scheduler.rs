@@ -0,0 +1,279 @@
+use crate::config::ScheduleConfig;
+use crate::error::Error;
+use chrono::{
+    DateTime, Datelike, Duration, Local, NaiveDate, NaiveDateTime, TimeZone, Timelike, Utc,
+};
+use std::collections::hash_map::RandomState;
+use std::hash::{BuildHasher, Hasher};
+
+/// Give up looking for a matching minute after this many years (e.g. "0 0 31 2 *")
+const MAX_CRON_YEARS: i32 = 5;
+
+/// Standard five-field cron expression: minute, hour, day of month, month and
+/// day of week. Supports `*`, lists, ranges and steps (`*/15`, `1-5`, `0,30`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Cron {
+    minutes: Vec<bool>,
+    hours: Vec<bool>,
+    days: Vec<bool>,
+    months: Vec<bool>,
+    weekdays: Vec<bool>,
+    /// Day of month and day of week are OR-ed when both are restricted
+    days_restricted: bool,
+    weekdays_restricted: bool,
+}
+
+impl Cron {
+    pub fn parse(expression: &str) -> Result<Self, Error> {
+        let fields: Vec<&str> = expression.split_whitespace().collect();
+        let [minute, hour, day, month, weekday] = fields[..] else {
+            return Err(Error::config(format!(
+                "Cron expression '{expression}' must have 5 fields"
+            )));
+        };
+        let parse = |field: &str, min: u32, max: u32| {
+            parse_field(field, min, max)
+                .map_err(|e| Error::config(format!("Invalid cron expression '{expression}': {e}")))
+        };
+
+        let mut weekdays = parse(weekday, 0, 7)?;
+        // Both 0 and 7 are Sunday
+        weekdays[0] |= weekdays[7];
+        weekdays.truncate(7);
+
+        Ok(Self {
+            minutes: parse(minute, 0, 59)?,
+            hours: parse(hour, 0, 23)?,
+            days: parse(day, 1, 31)?,
+            months: parse(month, 1, 12)?,
+            weekdays,
+            days_restricted: day != "*",
+            weekdays_restricted: weekday != "*",
+        })
+    }
+
+    fn matches_day(&self, date: NaiveDate) -> bool {
+        let day = self.days[date.day() as usize];
+        let weekday = self.weekdays[date.weekday().num_days_from_sunday() as usize];
+        match (self.days_restricted, self.weekdays_restricted) {
+            (true, true) => day || weekday,
+            (true, false) => day,
+            (false, true) => weekday,
+            (false, false) => true,
+        }
+    }
+
+    /// First matching minute strictly after `after`
+    pub fn next_after(&self, after: NaiveDateTime) -> Option<NaiveDateTime> {
+        let mut time = after.with_second(0)?.with_nanosecond(0)? + Duration::minutes(1);
+        let limit = after.year() + MAX_CRON_YEARS;
+
+        while time.year() <= limit {
+            if !self.months[time.month() as usize] {
+                let (year, month) = if time.month() == 12 {
+                    (time.year() + 1, 1)
+                } else {
+                    (time.year(), time.month() + 1)
+                };
+                time = NaiveDate::from_ymd_opt(year, month, 1)?.and_hms_opt(0, 0, 0)?;
+            } else if !self.matches_day(time.date()) {
+                time = time.date().succ_opt()?.and_hms_opt(0, 0, 0)?;
+            } else if !self.hours[time.hour() as usize] {
+                time = time.with_minute(0)? + Duration::hours(1);
+            } else if !self.minutes[time.minute() as usize] {
+                time += Duration::minutes(1);
+            } else {
+                return Some(time);
+            }
+        }
+        None
+    }
+}
+
+/// Flags for the values `field` selects, indexed by value
+fn parse_field(field: &str, min: u32, max: u32) -> Result<Vec<bool>, String> {
+    let mut selected = vec![false; max as usize + 1];
+    for part in field.split(',') {
+        let (range, step) = match part.split_once('/') {
+            Some((range, step)) => (
+                range,
+                step.parse::<u32>()
+                    .ok()
+                    .filter(|step| *step > 0)
+                    .ok_or_else(|| format!("invalid step in '{part}'"))?,
+            ),
+            None => (part, 1),
+        };
+        let value = |text: &str| {
+            text.parse::<u32>()
+                .ok()
+                .filter(|value| (min..=max).contains(value))
+                .ok_or_else(|| format!("'{text}' is not between {min} and {max}"))
+        };
+        let (start, end) = match range {
+            "*" => (min, max),
+            _ => match range.split_once('-') {
+                Some((start, end)) => (value(start)?, value(end)?),
+                // "5/10" means from 5 to the end in steps of 10
+                None if step > 1 => (value(range)?, max),
+                None => (value(range)?, value(range)?),
+            },
+        };
+        if start > end {
+            return Err(format!("range '{range}' is reversed"));
+        }
+        for value in (start..=end).step_by(step as usize) {
+            selected[value as usize] = true;
+        }
+    }
+    Ok(selected)
+}
+
+/// When a feed runs in daemon mode
+#[derive(Debug, Clone, PartialEq)]
+pub enum Schedule {
+    Interval(std::time::Duration),
+    Cron(Cron),
+}
+
+impl Schedule {
+    pub fn from_config(config: &ScheduleConfig) -> Result<Self, Error> {
+        match (&config.cron, config.interval_minutes) {
+            (Some(cron), None) => Ok(Schedule::Cron(Cron::parse(cron)?)),
+            (None, Some(minutes)) if minutes > 0 => Ok(Schedule::Interval(
+                std::time::Duration::from_secs(minutes * 60),
+            )),
+            (None, Some(_)) => Err(Error::config("schedule.interval_minutes must be positive")),
+            _ => Err(Error::config(
+                "schedule needs exactly one of cron or interval_minutes",
+            )),
+        }
+    }
+
+    /// Next run after a run finished at `after`. Cron expressions follow the
+    /// local time zone, like cron itself.
+    pub fn next_after(&self, after: DateTime<Utc>) -> DateTime<Utc> {
+        match self {
+            Schedule::Interval(interval) => {
+                after + Duration::from_std(*interval).unwrap_or(Duration::hours(1))
+            }
+            Schedule::Cron(cron) => {
+                let mut local = after.with_timezone(&Local).naive_local();
+                // Skip times that do not exist locally (daylight saving gaps)
+                while let Some(next) = cron.next_after(local) {
+                    if let Some(time) = Local.from_local_datetime(&next).earliest() {
+                        return time.with_timezone(&Utc);
+                    }
+                    local = next;
+                }
+                // Never matches; effectively disables the feed
+                after + Duration::days(365 * i64::from(MAX_CRON_YEARS))
+            }
+        }
+    }
+}
+
+/// Random delay up to `max_seconds`, so scheduled runs do not hit the site in lockstep
+pub fn jitter(max_seconds: u64) -> Duration {
+    if max_seconds == 0 {
+        return Duration::zero();
+    }
+    let mut hasher = RandomState::new().build_hasher();
+    hasher.write_u128(Utc::now().timestamp_nanos_opt().unwrap_or_default() as u128);
+    Duration::seconds((hasher.finish() % (max_seconds + 1)) as i64)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn at(value: &str) -> NaiveDateTime {
+        NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M").unwrap()
+    }
+
+    #[test]
+    fn test_cron_every_fifteen_minutes() {
+        let cron = Cron::parse("*/15 * * * *").unwrap();
+        assert_eq!(
+            cron.next_after(at("2025-07-14 10:07")),
+            Some(at("2025-07-14 10:15"))
+        );
+        assert_eq!(
+            cron.next_after(at("2025-07-14 10:45")),
+            Some(at("2025-07-14 11:00"))
+        );
+    }
+
+    #[test]
+    fn test_cron_lists_ranges_and_weekdays() {
+        // 9:30 and 18:30 on weekdays
+        let cron = Cron::parse("30 9,18 * * 1-5").unwrap();
+        // 2025-07-11 is a Friday
+        assert_eq!(
+            cron.next_after(at("2025-07-11 18:30")),
+            Some(at("2025-07-14 09:30"))
+        );
+
+        // Sunday can be written as 7
+        let cron = Cron::parse("0 0 * * 7").unwrap();
+        assert_eq!(
+            cron.next_after(at("2025-07-14 00:00")),
+            Some(at("2025-07-20 00:00"))
+        );
+    }
+
+    #[test]
+    fn test_cron_day_of_month_or_day_of_week() {
+        // The 1st of the month or any Monday
+        let cron = Cron::parse("0 6 1 * 1").unwrap();
+        assert_eq!(
+            cron.next_after(at("2025-07-15 00:00")),
+            Some(at("2025-07-21 06:00"))
+        );
+        assert_eq!(
+            cron.next_after(at("2025-07-29 00:00")),
+            Some(at("2025-08-01 06:00"))
+        );
+    }
+
+    #[test]
+    fn test_cron_rejects_invalid_expressions() {
+        assert!(Cron::parse("* * * *").is_err());
+        assert!(Cron::parse("60 * * * *").is_err());
+        assert!(Cron::parse("*/0 * * * *").is_err());
+        assert!(Cron::parse("5-1 * * * *").is_err());
+        assert!(Cron::parse("0 0 31 2 *")
+            .unwrap()
+            .next_after(at("2025-01-01 00:00"))
+            .is_none());
+    }
+
+    #[test]
+    fn test_schedule_from_config() {
+        let interval = Schedule::from_config(&ScheduleConfig {
+            interval_minutes: Some(30),
+            cron: None,
+            jitter_seconds: 0,
+        })
+        .unwrap();
+        let now = Utc::now();
+        assert_eq!(interval.next_after(now), now + Duration::minutes(30));
+
+        assert!(Schedule::from_config(&ScheduleConfig {
+            interval_minutes: Some(30),
+            cron: Some("* * * * *".to_string()),
+            jitter_seconds: 0,
+        })
+        .is_err());
+        assert!(Schedule::from_config(&ScheduleConfig::default()).is_err());
+    }
+
+    #[test]
+    fn test_jitter_stays_within_bounds() {
+        assert_eq!(jitter(0), Duration::zero());
+        for _ in 0..20 {
+            let delay = jitter(30);
+            assert!(delay >= Duration::zero() && delay <= Duration::seconds(30));
+        }
+    }
+}
@@ -0,0 +1,592 @@
+use crate::config::{Config, FeedFormat};
+use crate::metrics::{SharedMetrics, CONTENT_TYPE as METRICS_CONTENT_TYPE};
+use crate::rss_generator::escape_html;
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+use tokio::io::{AsyncRead, AsyncReadExt, AsyncWriteExt};
+use tokio::net::{TcpListener, TcpStream};
+use tracing::{info, warn};
+
+/// Requests with larger headers are rejected
+const MAX_REQUEST_BYTES: usize = 16 * 1024;
+/// Clients get this long to send the request head
+const READ_TIMEOUT: Duration = Duration::from_secs(10);
+const HTTP_DATE_FORMAT: &str = "%a, %d %b %Y %H:%M:%S GMT";
+
+/// A generated feed file and the URL path it is served under
+#[derive(Debug, PartialEq)]
+pub struct ServedFeed {
+    pub url_path: String,
+    pub file: PathBuf,
+    pub format: FeedFormat,
+}
+
+struct Request {
+    method: String,
+    path: String,
+    headers: Vec<(String, String)>,
+}
+
+/// How reading a request head ended
+#[derive(Debug, PartialEq)]
+enum Head {
+    Complete(Vec<u8>),
+    TooLarge,
+    TimedOut,
+    Closed,
+}
+
+struct Response {
+    status: &'static str,
+    headers: Vec<(&'static str, String)>,
+    body: Vec<u8>,
+}
+
+/// Every feed file the configuration writes. Category feeds are found by
+/// scanning the split output directories, since categories vary between runs.
+/// Files outside the working directory are not served.
+pub fn served_feeds(config: &Config) -> Vec<ServedFeed> {
+    let mut feeds: Vec<ServedFeed> = Vec::new();
+    for (file, format) in feed_files(config) {
+        let Some(url_path) = url_path(&file) else {
+            continue;
+        };
+        if feeds.iter().all(|feed| feed.url_path != url_path) {
+            feeds.push(ServedFeed {
+                url_path,
+                file,
+                format,
+            });
+        }
+    }
+    feeds
+}
+
+/// Every feed file the configuration writes, servable or not
+fn feed_files(config: &Config) -> Vec<(PathBuf, FeedFormat)> {
+    let mut files: Vec<(PathBuf, FeedFormat)> = Vec::new();
+    for feed in &config.feeds {
+        let diff_outputs = feed.diff.iter().flat_map(|diff| &diff.outputs);
+        for output in feed.outputs.iter().chain(diff_outputs) {
+            files.push((output.path.clone(), output.format));
+        }
+        if let Some(split) = &feed.split {
+            let mut found: Vec<(PathBuf, FeedFormat)> = fs::read_dir(&split.output_dir)
+                .into_iter()
+                .flatten()
+                .filter_map(|entry| {
+                    let path = entry.ok()?.path();
+                    let extension = path.extension()?.to_str()?;
+                    let format = split
+                        .formats
+                        .iter()
+                        .find(|format| format.extension() == extension)?;
+                    Some((path, *format))
+                })
+                .collect();
+            found.sort_by(|a, b| a.0.cmp(&b.0));
+            files.extend(found);
+        }
+    }
+    files
+}
+
+/// `feeds/ai.xml` -> `/feeds/ai.xml`; absolute paths and `..` are not served
+fn url_path(file: &Path) -> Option<String> {
+    let mut segments = Vec::new();
+    for component in file.components() {
+        match component {
+            Component::Normal(segment) => segments.push(segment.to_str()?),
+            Component::CurDir => {}
+            _ => return None,
+        }
+    }
+    Some(format!("/{}", segments.join("/")))
+}
+
+/// Accept connections until the listener fails, one task per connection
+pub async fn run_server(listener: TcpListener, config: Arc<Config>, metrics: SharedMetrics) {
+    // The feed list is rebuilt for every request; report what it leaves out once
+    for (file, _) in feed_files(&config) {
+        if url_path(&file).is_none() {
+            warn!(path = %file.display(), "Not serving a feed outside the working directory");
+        }
+    }
+    while let Ok((socket, _)) = listener.accept().await {
+        let config = Arc::clone(&config);
+        let metrics = Arc::clone(&metrics);
+        tokio::spawn(async move {
+            if let Err(e) = handle_connection(socket, &config, &metrics).await {
+                warn!(error = %e, "HTTP connection failed");
+            }
+        });
+    }
+}
+
+async fn handle_connection(
+    mut socket: TcpStream,
+    config: &Config,
+    metrics: &SharedMetrics,
+) -> std::io::Result<()> {
+    let head = match tokio::time::timeout(READ_TIMEOUT, read_head(&mut socket)).await {
+        Ok(head) => head?,
+        Err(_) => Head::TimedOut,
+    };
+    let response = match head {
+        Head::Closed => return Ok(()),
+        Head::TooLarge => text_response(
+            "431 Request Header Fields Too Large",
+            "Request Header Fields Too Large",
+        ),
+        Head::TimedOut => text_response("408 Request Timeout", "Request Timeout"),
+        Head::Complete(buffer) => match parse_request(&buffer) {
+            Some(request) => {
+                let mut response = respond(&request, &served_feeds(config), metrics);
+                info!(
+                    method = %request.method,
+                    path = %request.path,
+                    status = response.status,
+                    "HTTP request"
+                );
+                if request.method == "HEAD" {
+                    // Same headers as GET, without the body
+                    let length = response.body.len();
+                    response
+                        .headers
+                        .push(("Content-Length", length.to_string()));
+                    response.body.clear();
+                }
+                response
+            }
+            None => text_response("400 Bad Request", "Bad Request"),
+        },
+    };
+
+    socket.write_all(&response.to_bytes()).await?;
+    socket.shutdown().await
+}
+
+/// Read until the blank line ending the request head, giving up once it
+/// would exceed `MAX_REQUEST_BYTES`
+async fn read_head<R: AsyncRead + Unpin>(reader: &mut R) -> std::io::Result<Head> {
+    let mut buffer = Vec::new();
+    let mut chunk = [0u8; 4096];
+    loop {
+        if let Some(end) = buffer.windows(4).position(|window| window == b"\r\n\r\n") {
+            return Ok(if end + 4 > MAX_REQUEST_BYTES {
+                Head::TooLarge
+            } else {
+                Head::Complete(buffer)
+            });
+        }
+        if buffer.len() > MAX_REQUEST_BYTES {
+            return Ok(Head::TooLarge);
+        }
+        let read = reader.read(&mut chunk).await?;
+        if read == 0 {
+            return Ok(Head::Closed);
+        }
+        buffer.extend_from_slice(&chunk[..read]);
+    }
+}
+
+fn parse_request(buffer: &[u8]) -> Option<Request> {
+    let text = std::str::from_utf8(buffer).ok()?;
+    let head = text.split("\r\n\r\n").next()?;
+    let mut lines = head.split("\r\n");
+    let mut request_line = lines.next()?.split_whitespace();
+    let method = request_line.next()?.to_string();
+    let target = request_line.next()?;
+    let path = percent_decode(target.split('?').next()?)?;
+    let headers = lines
+        .filter_map(|line| {
+            let (name, value) = line.split_once(':')?;
+            Some((name.trim().to_ascii_lowercase(), value.trim().to_string()))
+        })
+        .collect();
+    Some(Request {
+        method,
+        path,
+        headers,
+    })
+}
+
+/// `/feeds/%E7%94%9F%E6%88%90ai.xml` -> `/feeds/生成ai.xml`; `None` when the
+/// escapes are malformed or do not decode to UTF-8
+fn percent_decode(path: &str) -> Option<String> {
+    let bytes = path.as_bytes();
+    let mut decoded = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+    while i < bytes.len() {
+        if bytes[i] == b'%' {
+            let hex = std::str::from_utf8(bytes.get(i + 1..i + 3)?).ok()?;
+            decoded.push(u8::from_str_radix(hex, 16).ok()?);
+            i += 3;
+        } else {
+            decoded.push(bytes[i]);
+            i += 1;
+        }
+    }
+    String::from_utf8(decoded).ok()
+}
+
+/// Escape everything but unreserved characters and `/`, for use in hrefs
+fn percent_encode(path: &str) -> String {
+    path.bytes()
+        .map(|byte| match byte {
+            b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' | b'/' => {
+                (byte as char).to_string()
+            }
+            _ => format!("%{byte:02X}"),
+        })
+        .collect()
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(header, _)| header == name)
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+impl Response {
+    fn to_bytes(&self) -> Vec<u8> {
+        let mut head = format!("HTTP/1.1 {}\r\n", self.status);
+        let has_length = self
+            .headers
+            .iter()
+            .any(|(name, _)| *name == "Content-Length");
+        for (name, value) in &self.headers {
+            head.push_str(&format!("{name}: {value}\r\n"));
+        }
+        // A 304 describes the unchanged representation; it has no body of its own
+        if !has_length && !self.status.starts_with("304") {
+            head.push_str(&format!("Content-Length: {}\r\n", self.body.len()));
+        }
+        head.push_str("Connection: close\r\n\r\n");
+
+        let mut bytes = head.into_bytes();
+        bytes.extend_from_slice(&self.body);
+        bytes
+    }
+}
+
+fn text_response(status: &'static str, body: &str) -> Response {
+    Response {
+        status,
+        headers: vec![("Content-Type", "text/plain; charset=utf-8".to_string())],
+        body: body.as_bytes().to_vec(),
+    }
+}
+
+fn respond(request: &Request, feeds: &[ServedFeed], metrics: &SharedMetrics) -> Response {
+    if request.method != "GET" && request.method != "HEAD" {
+        let mut response = text_response("405 Method Not Allowed", "Method Not Allowed");
+        response.headers.push(("Allow", "GET, HEAD".to_string()));
+        return response;
+    }
+    if request.path == "/" || request.path == "/index.html" {
+        return Response {
+            status: "200 OK",
+            headers: vec![("Content-Type", "text/html; charset=utf-8".to_string())],
+            body: index_html(feeds).into_bytes(),
+        };
+    }
+    if request.path == "/metrics" {
+        return Response {
+            status: "200 OK",
+            headers: vec![("Content-Type", METRICS_CONTENT_TYPE.to_string())],
+            body: metrics.lock().unwrap().render().into_bytes(),
+        };
+    }
+
+    let Some(feed) = feeds.iter().find(|feed| feed.url_path == request.path) else {
+        return text_response("404 Not Found", "Not Found");
+    };
+    let (Ok(body), Ok(metadata)) = (fs::read(&feed.file), fs::metadata(&feed.file)) else {
+        // Configured but not generated yet
+        return text_response("404 Not Found", "Not Found");
+    };
+
+    let etag = format!(
+        "\"{}\"",
+        Sha256::digest(&body)
+            .iter()
+            .take(8)
+            .map(|b| format!("{b:02x}"))
+            .collect::<String>()
+    );
+    let modified: DateTime<Utc> = metadata.modified().unwrap_or(SystemTime::UNIX_EPOCH).into();
+    let last_modified = modified.format(HTTP_DATE_FORMAT).to_string();
+    let headers = vec![
+        ("ETag", etag.clone()),
+        ("Last-Modified", last_modified),
+        ("Cache-Control", "no-cache".to_string()),
+    ];
+
+    if is_not_modified(request, &etag, modified) {
+        return Response {
+            status: "304 Not Modified",
+            headers,
+            body: Vec::new(),
+        };
+    }
+
+    let mut headers = headers;
+    headers.insert(0, ("Content-Type", feed.format.content_type().to_string()));
+    Response {
+        status: "200 OK",
+        headers,
+        body,
+    }
+}
+
+/// `If-None-Match` wins over `If-Modified-Since` when both are sent (RFC 9110)
+fn is_not_modified(request: &Request, etag: &str, modified: DateTime<Utc>) -> bool {
+    if let Some(if_none_match) = request.header("if-none-match") {
+        return if_none_match
+            .split(',')
+            .map(|tag| tag.trim().trim_start_matches("W/"))
+            .any(|tag| tag == etag || tag == "*");
+    }
+    request
+        .header("if-modified-since")
+        .and_then(|value| DateTime::parse_from_rfc2822(value).ok())
+        .is_some_and(|since| modified.timestamp() <= since.timestamp())
+}
+
+fn index_html(feeds: &[ServedFeed]) -> String {
+    let alternates: String = feeds
+        .iter()
+        .map(|feed| {
+            let mime = feed.format.content_type().split(';').next().unwrap_or("");
+            format!(
+                "<link rel=\"alternate\" type=\"{mime}\" href=\"{}\">\n",
+                percent_encode(&feed.url_path)
+            )
+        })
+        .collect();
+    let entries: String = feeds
+        .iter()
+        .map(|feed| {
+            let label = match feed.format {
+                FeedFormat::Rss => "RSS",
+                FeedFormat::Atom => "Atom",
+                FeedFormat::Json => "JSON Feed",
+            };
+            format!(
+                "<li><a href=\"{}\">{}</a> ({label})</li>\n",
+                percent_encode(&feed.url_path),
+                escape_html(&feed.url_path)
+            )
+        })
+        .collect();
+
+    format!(
+        "<!DOCTYPE html>\n<html lang=\"ja\">\n<head>\n<meta charset=\"utf-8\">\n<title>フィード一覧</title>\n{alternates}</head>\n\
+         <body>\n<h1>フィード一覧</h1>\n<ul>\n{entries}</ul>\n</body>\n</html>\n"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::OutputConfig;
+    use crate::test_support::temp_dir;
+
+    fn get(path: &str, headers: &[(&str, &str)]) -> Request {
+        Request {
+            method: "GET".to_string(),
+            path: path.to_string(),
+            headers: headers
+                .iter()
+                .map(|(name, value)| (name.to_string(), value.to_string()))
+                .collect(),
+        }
+    }
+
+    fn header<'a>(response: &'a Response, name: &str) -> Option<&'a str> {
+        response
+            .headers
+            .iter()
+            .find(|(header, _)| *header == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    #[test]
+    fn test_url_path() {
+        assert_eq!(
+            url_path(Path::new("feeds/ai.xml")).as_deref(),
+            Some("/feeds/ai.xml")
+        );
+        assert_eq!(
+            url_path(Path::new("./rss.xml")).as_deref(),
+            Some("/rss.xml")
+        );
+        assert_eq!(url_path(Path::new("/etc/passwd")), None);
+        assert_eq!(url_path(Path::new("../secret.xml")), None);
+    }
+
+    #[test]
+    fn test_served_feeds_include_default_outputs() {
+        let feeds = served_feeds(&Config::default());
+        let paths: Vec<&str> = feeds.iter().map(|feed| feed.url_path.as_str()).collect();
+        assert_eq!(paths, vec!["/rss.xml"]);
+    }
+
+    #[test]
+    fn test_respond_with_etag_and_not_modified() {
+        let dir = temp_dir("serve-respond");
+        let file = dir.join("rss.xml");
+        fs::write(&file, "<rss></rss>").unwrap();
+        let feeds = vec![ServedFeed {
+            url_path: "/rss.xml".to_string(),
+            file,
+            format: FeedFormat::Rss,
+        }];
+
+        let metrics = SharedMetrics::default();
+        let response = respond(&get("/rss.xml", &[]), &feeds, &metrics);
+        assert_eq!(response.status, "200 OK");
+        assert_eq!(
+            header(&response, "Content-Type"),
+            Some("application/rss+xml; charset=utf-8")
+        );
+        assert_eq!(response.body, b"<rss></rss>");
+        let etag = header(&response, "ETag").unwrap().to_string();
+        let last_modified = header(&response, "Last-Modified").unwrap().to_string();
+
+        let response = respond(
+            &get("/rss.xml", &[("if-none-match", &etag)]),
+            &feeds,
+            &metrics,
+        );
+        assert_eq!(response.status, "304 Not Modified");
+        assert!(response.body.is_empty());
+
+        let response = respond(
+            &get("/rss.xml", &[("if-modified-since", &last_modified)]),
+            &feeds,
+            &metrics,
+        );
+        assert_eq!(response.status, "304 Not Modified");
+
+        let response = respond(
+            &get(
+                "/rss.xml",
+                &[
+                    ("if-none-match", "\"stale\""),
+                    ("if-modified-since", &last_modified),
+                ],
+            ),
+            &feeds,
+            &metrics,
+        );
+        assert_eq!(response.status, "200 OK");
+
+        assert_eq!(
+            respond(&get("/other.xml", &[]), &feeds, &metrics).status,
+            "404 Not Found"
+        );
+        let index = respond(&get("/", &[]), &feeds, &metrics);
+        assert!(String::from_utf8(index.body)
+            .unwrap()
+            .contains("<a href=\"/rss.xml\">/rss.xml</a> (RSS)"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[test]
+    fn test_respond_to_percent_encoded_paths() {
+        let dir = temp_dir("serve-encoded");
+        let file = dir.join("生成ai.xml");
+        fs::write(&file, "<rss></rss>").unwrap();
+        let feeds = vec![ServedFeed {
+            url_path: "/feeds/生成ai.xml".to_string(),
+            file,
+            format: FeedFormat::Rss,
+        }];
+        let metrics = SharedMetrics::default();
+
+        let request =
+            parse_request(b"GET /feeds/%E7%94%9F%E6%88%90ai.xml?x=1 HTTP/1.1\r\n\r\n").unwrap();
+        assert_eq!(request.path, "/feeds/生成ai.xml");
+        assert_eq!(respond(&request, &feeds, &metrics).status, "200 OK");
+        assert!(parse_request(b"GET /feeds/%E7%94 HTTP/1.1\r\n\r\n").is_none());
+        assert!(parse_request(b"GET /%zz HTTP/1.1\r\n\r\n").is_none());
+
+        let index = String::from_utf8(respond(&get("/", &[]), &feeds, &metrics).body).unwrap();
+        assert!(index
+            .contains("<a href=\"/feeds/%E7%94%9F%E6%88%90ai.xml\">/feeds/生成ai.xml</a> (RSS)"));
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+
+    #[tokio::test]
+    async fn test_read_head_limits_size() {
+        let request = b"GET / HTTP/1.1\r\nHost: localhost\r\n\r\n";
+        assert_eq!(
+            read_head(&mut &request[..]).await.unwrap(),
+            Head::Complete(request.to_vec())
+        );
+
+        let mut huge = b"GET / HTTP/1.1\r\nX-Padding: ".to_vec();
+        huge.extend(std::iter::repeat(b'a').take(MAX_REQUEST_BYTES));
+        assert_eq!(read_head(&mut &huge[..]).await.unwrap(), Head::TooLarge);
+        huge.extend_from_slice(b"\r\n\r\n");
+        assert_eq!(read_head(&mut &huge[..]).await.unwrap(), Head::TooLarge);
+
+        assert_eq!(
+            read_head(&mut &b"GET / HT"[..]).await.unwrap(),
+            Head::Closed
+        );
+    }
+
+    #[tokio::test]
+    async fn test_run_server_serves_generated_feed() {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let mut config = Config::default();
+        config.feeds[0].outputs = vec![OutputConfig {
+            path: PathBuf::from("feed.json"),
+            format: FeedFormat::Json,
+            url: None,
+            filters: Default::default(),
+        }];
+        let metrics = SharedMetrics::default();
+        metrics
+            .lock()
+            .unwrap()
+            .record_success("ledge_ai", Utc::now());
+        tokio::spawn(run_server(listener, Arc::new(config), metrics));
+
+        let response = reqwest::get(format!("{base}/")).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert!(response.text().await.unwrap().contains("/feed.json"));
+
+        let response = reqwest::get(format!("{base}/metrics")).await.unwrap();
+        assert_eq!(response.status(), 200);
+        assert_eq!(
+            response.headers()["content-type"],
+            "text/plain; version=0.0.4; charset=utf-8"
+        );
+        assert!(response
+            .text()
+            .await
+            .unwrap()
+            .contains("ledge_ai_feed_last_success_timestamp_seconds{feed=\"ledge_ai\"}"));
+
+        let response = reqwest::Client::new()
+            .post(format!("{base}/feed.json"))
+            .send()
+            .await
+            .unwrap();
+        assert_eq!(response.status(), 405);
+    }
+}
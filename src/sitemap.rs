@@ -0,0 +1,335 @@
+use crate::error::{Error, Stage};
+use crate::html_parser::ArticleInfo;
+use crate::http_client::HttpClient;
+use crate::source::Source;
+use chrono::{DateTime, NaiveDate, Utc};
+use flate2::read::GzDecoder;
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::VecDeque;
+use std::io::Read;
+use std::time::Instant;
+use tracing::{info, warn};
+
+/// Size limit from the sitemaps.org protocol (uncompressed)
+const MAX_SITEMAP_BYTES: usize = 50 * 1024 * 1024;
+/// Sitemap indexes may not nest, but be lenient with one or two levels
+const MAX_INDEX_DEPTH: usize = 3;
+
+static SITEMAP_ENTRY_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<sitemap\b[^>]*>(.*?)</sitemap>").unwrap());
+static URL_ENTRY_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<url\b[^>]*>(.*?)</url>").unwrap());
+static LOC_PATTERN: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?s)<loc>\s*(?:<!\[CDATA\[)?\s*(.*?)\s*(?:\]\]>)?\s*</loc>").unwrap()
+});
+static LASTMOD_PATTERN: Lazy<Regex> =
+    Lazy::new(|| Regex::new(r"(?s)<lastmod>\s*(.*?)\s*</lastmod>").unwrap());
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct SitemapEntry {
+    pub loc: String,
+    pub lastmod: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug, PartialEq)]
+pub enum Sitemap {
+    /// `<sitemapindex>` pointing at further sitemaps
+    Index(Vec<SitemapEntry>),
+    /// `<urlset>` listing pages
+    UrlSet(Vec<SitemapEntry>),
+}
+
+/// Parse a sitemap index or URL set
+pub fn parse_sitemap(xml: &str) -> Sitemap {
+    let entries = |pattern: &Regex| -> Vec<SitemapEntry> {
+        pattern
+            .captures_iter(xml)
+            .filter_map(|caps| {
+                let entry = &caps[1];
+                let loc = LOC_PATTERN.captures(entry)?[1].to_string();
+                Some(SitemapEntry {
+                    loc: unescape_xml(&loc),
+                    lastmod: LASTMOD_PATTERN
+                        .captures(entry)
+                        .and_then(|caps| parse_lastmod(&caps[1])),
+                })
+            })
+            .collect()
+    };
+
+    if xml.contains("<sitemapindex") {
+        Sitemap::Index(entries(&SITEMAP_ENTRY_PATTERN))
+    } else {
+        Sitemap::UrlSet(entries(&URL_ENTRY_PATTERN))
+    }
+}
+
+/// Decode the sitemap fetched from `url`, gunzipping it when it is compressed
+pub fn decode_sitemap(url: &str, bytes: Vec<u8>) -> Result<String, Error> {
+    let decode_error = |reason: String| Error::Decode {
+        url: url.to_string(),
+        reason,
+    };
+    if bytes.starts_with(&[0x1f, 0x8b]) {
+        let mut xml = String::new();
+        GzDecoder::new(bytes.as_slice())
+            .take(MAX_SITEMAP_BYTES as u64)
+            .read_to_string(&mut xml)
+            .map_err(|e| decode_error(e.to_string()))?;
+        Ok(xml)
+    } else {
+        String::from_utf8(bytes).map_err(|e| decode_error(e.to_string()))
+    }
+}
+
+/// W3C datetime as used by sitemaps: a full timestamp, one without seconds, or a date
+pub fn parse_lastmod(value: &str) -> Option<DateTime<Utc>> {
+    let value = value.trim();
+    if let Ok(parsed) = DateTime::parse_from_rfc3339(value) {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(parsed) = DateTime::parse_from_str(value, "%Y-%m-%dT%H:%M%:z") {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    if let Ok(parsed) = DateTime::parse_from_str(&value.replace('Z', "+00:00"), "%Y-%m-%dT%H:%M%:z")
+    {
+        return Some(parsed.with_timezone(&Utc));
+    }
+    NaiveDate::parse_from_str(value, "%Y-%m-%d")
+        .ok()
+        .and_then(|date| date.and_hms_opt(0, 0, 0))
+        .map(|datetime| DateTime::from_naive_utc_and_offset(datetime, Utc))
+}
+
+fn unescape_xml(text: &str) -> String {
+    text.replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&apos;", "'")
+        .replace("&amp;", "&")
+}
+
+/// Collect article URLs from the given sitemaps, following sitemap indexes.
+/// Entries modified before `cutoff` are skipped and at most `max_urls` of the
+/// most recently modified articles are returned. Sitemap entries carry no
+/// title; it is read from the article page later.
+pub async fn discover_from_sitemaps(
+    client: &HttpClient,
+    source: &dyn Source,
+    sitemap_urls: Vec<String>,
+    cutoff: Option<DateTime<Utc>>,
+    max_urls: usize,
+) -> Result<Vec<ArticleInfo>, Error> {
+    let mut queue: VecDeque<(String, usize)> =
+        sitemap_urls.into_iter().map(|url| (url, 0)).collect();
+    let mut entries: Vec<SitemapEntry> = Vec::new();
+    let is_recent = |entry: &SitemapEntry| {
+        cutoff.map_or(true, |cutoff| {
+            entry.lastmod.map_or(true, |lastmod| lastmod >= cutoff)
+        })
+    };
+
+    while let Some((url, depth)) = queue.pop_front() {
+        let started = Instant::now();
+        let fetched = client
+            .fetch_bytes(&url, MAX_SITEMAP_BYTES, Stage::Sitemap)
+            .await
+            .and_then(|bytes| decode_sitemap(&url, bytes));
+        let xml = match fetched {
+            Ok(xml) => xml,
+            Err(e) if depth == 0 => return Err(e),
+            Err(e) => {
+                warn!(url = %url, error = %e, "Skipping sitemap");
+                continue;
+            }
+        };
+        info!(
+            url = %url,
+            stage = "sitemap",
+            bytes = xml.len(),
+            duration_ms = started.elapsed().as_millis() as u64,
+            "Fetched sitemap"
+        );
+
+        match parse_sitemap(&xml) {
+            Sitemap::Index(sitemaps) => {
+                if depth + 1 < MAX_INDEX_DEPTH {
+                    queue.extend(
+                        sitemaps
+                            .into_iter()
+                            .filter(|sitemap| is_recent(sitemap))
+                            .map(|sitemap| (sitemap.loc, depth + 1)),
+                    );
+                }
+            }
+            Sitemap::UrlSet(urls) => {
+                for entry in urls {
+                    if !source.is_article_url(&entry.loc) || !is_recent(&entry) {
+                        continue;
+                    }
+                    match entries.iter_mut().find(|known| known.loc == entry.loc) {
+                        Some(known) => known.lastmod = known.lastmod.max(entry.lastmod),
+                        None => entries.push(entry),
+                    }
+                }
+            }
+        }
+    }
+
+    // Most recently modified first; entries without lastmod last
+    entries.sort_by_key(|entry| std::cmp::Reverse(entry.lastmod));
+    entries.truncate(max_urls);
+    info!(articles = entries.len(), "Found articles in sitemaps");
+
+    Ok(entries
+        .into_iter()
+        .map(|entry| ArticleInfo {
+            title: String::new(),
+            url: entry.loc,
+            date: entry
+                .lastmod
+                .map(|lastmod| lastmod.to_rfc3339())
+                .unwrap_or_default(),
+            category: None,
+            lastmod: entry.lastmod,
+        })
+        .collect())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::source::LedgeAi;
+    use crate::test_support::serve;
+    use flate2::write::GzEncoder;
+    use flate2::Compression;
+    use std::io::Write;
+
+    fn utc(value: &str) -> DateTime<Utc> {
+        DateTime::parse_from_rfc3339(value)
+            .unwrap()
+            .with_timezone(&Utc)
+    }
+
+    #[test]
+    fn test_parse_sitemap_url_set() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <urlset xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <url><loc>https://ledge.ai/articles/a?x=1&amp;y=2</loc><lastmod>2025-07-14T07:50:00+09:00</lastmod></url>
+                <url><loc><![CDATA[https://ledge.ai/articles/b]]></loc></url>
+            </urlset>"#;
+
+        assert_eq!(
+            parse_sitemap(xml),
+            Sitemap::UrlSet(vec![
+                SitemapEntry {
+                    loc: "https://ledge.ai/articles/a?x=1&y=2".to_string(),
+                    lastmod: Some(utc("2025-07-13T22:50:00Z")),
+                },
+                SitemapEntry {
+                    loc: "https://ledge.ai/articles/b".to_string(),
+                    lastmod: None,
+                },
+            ])
+        );
+    }
+
+    #[test]
+    fn test_parse_sitemap_index() {
+        let xml = r#"<sitemapindex xmlns="http://www.sitemaps.org/schemas/sitemap/0.9">
+                <sitemap><loc>https://ledge.ai/sitemap-articles.xml.gz</loc><lastmod>2025-07-14</lastmod></sitemap>
+            </sitemapindex>"#;
+
+        assert_eq!(
+            parse_sitemap(xml),
+            Sitemap::Index(vec![SitemapEntry {
+                loc: "https://ledge.ai/sitemap-articles.xml.gz".to_string(),
+                lastmod: Some(utc("2025-07-14T00:00:00Z")),
+            }])
+        );
+    }
+
+    #[test]
+    fn test_parse_lastmod_formats() {
+        assert_eq!(
+            parse_lastmod("2025-07-14T07:50:00.000Z"),
+            Some(utc("2025-07-14T07:50:00Z"))
+        );
+        assert_eq!(
+            parse_lastmod("2025-07-14T07:50+09:00"),
+            Some(utc("2025-07-13T22:50:00Z"))
+        );
+        assert_eq!(
+            parse_lastmod("2025-07-14T07:50Z"),
+            Some(utc("2025-07-14T07:50:00Z"))
+        );
+        assert_eq!(
+            parse_lastmod("2025-07-14"),
+            Some(utc("2025-07-14T00:00:00Z"))
+        );
+        assert_eq!(parse_lastmod("yesterday"), None);
+    }
+
+    #[test]
+    fn test_decode_sitemap_gzip() {
+        let xml = "<urlset><url><loc>https://ledge.ai/articles/a</loc></url></urlset>";
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+        encoder.write_all(xml.as_bytes()).unwrap();
+        let compressed = encoder.finish().unwrap();
+
+        let url = "https://ledge.ai/sitemap.xml.gz";
+        assert_eq!(decode_sitemap(url, compressed.clone()).unwrap(), xml);
+        assert_eq!(decode_sitemap(url, xml.as_bytes().to_vec()).unwrap(), xml);
+        assert!(matches!(
+            decode_sitemap(url, compressed[..compressed.len() / 2].to_vec()),
+            Err(Error::Decode { .. })
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_discover_from_sitemaps_follows_index() {
+        let base = serve(vec![
+            (
+                "/sitemap.xml",
+                r#"<sitemapindex>
+                    <sitemap><loc>{base}/sitemap-new.xml</loc><lastmod>2025-07-14</lastmod></sitemap>
+                    <sitemap><loc>{base}/sitemap-old.xml</loc><lastmod>2020-01-01</lastmod></sitemap>
+                </sitemapindex>"#
+                    .to_string(),
+            ),
+            (
+                "/sitemap-new.xml",
+                r#"<urlset>
+                    <url><loc>https://ledge.ai/articles/older</loc><lastmod>2025-07-01</lastmod></url>
+                    <url><loc>https://ledge.ai/articles/newer</loc><lastmod>2025-07-14</lastmod></url>
+                    <url><loc>https://ledge.ai/categories/business</loc><lastmod>2025-07-14</lastmod></url>
+                    <url><loc>https://ledge.ai/articles/stale</loc><lastmod>2023-01-01</lastmod></url>
+                </urlset>"#
+                    .to_string(),
+            ),
+        ])
+        .await;
+
+        let articles = discover_from_sitemaps(
+            &HttpClient::new(),
+            &LedgeAi,
+            vec![format!("{base}/sitemap.xml")],
+            Some(utc("2025-01-01T00:00:00Z")),
+            10,
+        )
+        .await
+        .unwrap();
+
+        let urls: Vec<&str> = articles.iter().map(|a| a.url.as_str()).collect();
+        assert_eq!(
+            urls,
+            vec![
+                "https://ledge.ai/articles/newer",
+                "https://ledge.ai/articles/older"
+            ]
+        );
+        assert_eq!(articles[0].title, "");
+        assert_eq!(articles[0].lastmod, Some(utc("2025-07-14T00:00:00Z")));
+    }
+}
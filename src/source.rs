@@ -0,0 +1,171 @@
+use crate::article_extractor::{
+    extract_article_author, extract_article_category, extract_article_content,
+    extract_article_date, extract_article_modified_date, extract_article_tags,
+    extract_article_title,
+};
+use crate::error::Error;
+use crate::html_parser::{
+    parse_articles_from_html, parse_listing_links, ArticleInfo, ListingLinks, LEDGE_AI_BASE_URL,
+};
+
+/// Feed-level metadata describing where the articles come from
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChannelInfo {
+    pub title: String,
+    pub link: String,
+    pub description: String,
+    pub language: String,
+}
+
+/// Links a generated feed carries besides the site itself
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct FeedLinks<'a> {
+    /// Public URL of the feed itself
+    pub self_url: Option<&'a str>,
+    /// WebSub hub subscribers can register with for push updates
+    pub hub: Option<&'a str>,
+}
+
+/// What a source extracts from a single article page
+#[derive(Debug, PartialEq)]
+pub struct ExtractedArticle {
+    /// Title from the page itself, used when the listing did not provide one
+    pub title: Option<String>,
+    pub markdown: String,
+    /// Publication date as found on the page, if any
+    pub date: Option<String>,
+    /// Last revision date as found on the page, if any
+    pub modified_date: Option<String>,
+    pub category: Option<String>,
+    pub tags: Vec<String>,
+    pub author: Option<String>,
+    /// How the body and the publication date were found, for the run report
+    pub content_strategy: Option<String>,
+    pub date_strategy: Option<String>,
+}
+
+/// A news site the generator can build a feed for. Implementations only parse
+/// pages; fetching is shared by the generator so every source gets the same
+/// HTTP client, politeness and error handling.
+pub trait Source {
+    /// Short identifier used in logs, e.g. "ledge_ai"
+    fn id(&self) -> &str;
+
+    fn channel(&self) -> ChannelInfo;
+
+    /// Pages listing the latest articles
+    fn listing_urls(&self) -> Vec<String>;
+
+    /// Sitemaps (or sitemap indexes) covering the site's articles
+    fn sitemap_urls(&self) -> Vec<String>;
+
+    /// Whether a URL found in a sitemap is an article page
+    fn is_article_url(&self, url: &str) -> bool;
+
+    /// Articles on a listing page; fails when there are none
+    fn parse_listing(&self, html: &str, page_url: &str) -> Result<Vec<ArticleInfo>, Error>;
+
+    /// Pagination and category/tag listings linked from a listing page
+    fn listing_links(&self, html: &str, page_url: &str) -> ListingLinks;
+
+    fn extract_article(&self, html: &str, url: &str) -> Result<ExtractedArticle, Error>;
+}
+
+/// https://ledge.ai/
+pub struct LedgeAi;
+
+impl Source for LedgeAi {
+    fn id(&self) -> &str {
+        "ledge_ai"
+    }
+
+    fn channel(&self) -> ChannelInfo {
+        ChannelInfo {
+            title: "Ledge.ai 新着記事".to_string(),
+            link: format!("{LEDGE_AI_BASE_URL}/"),
+            description: "Ledge.ai の最新テクノロジー記事".to_string(),
+            language: "ja".to_string(),
+        }
+    }
+
+    fn listing_urls(&self) -> Vec<String> {
+        vec![format!("{LEDGE_AI_BASE_URL}/")]
+    }
+
+    fn sitemap_urls(&self) -> Vec<String> {
+        vec![format!("{LEDGE_AI_BASE_URL}/sitemap.xml")]
+    }
+
+    fn is_article_url(&self, url: &str) -> bool {
+        url.starts_with(LEDGE_AI_BASE_URL) && url.contains("/articles/")
+    }
+
+    fn parse_listing(&self, html: &str, page_url: &str) -> Result<Vec<ArticleInfo>, Error> {
+        parse_articles_from_html(html, page_url)
+    }
+
+    fn listing_links(&self, html: &str, page_url: &str) -> ListingLinks {
+        parse_listing_links(html, page_url)
+    }
+
+    fn extract_article(&self, html: &str, url: &str) -> Result<ExtractedArticle, Error> {
+        let (markdown, content_strategy) = extract_article_content(html, url)?;
+        let (date, date_strategy) = extract_article_date(html).unzip();
+        Ok(ExtractedArticle {
+            title: extract_article_title(html),
+            markdown,
+            date,
+            modified_date: extract_article_modified_date(html),
+            category: extract_article_category(html),
+            tags: extract_article_tags(html),
+            author: extract_article_author(html),
+            content_strategy: Some(content_strategy.to_string()),
+            date_strategy: date_strategy.map(str::to_string),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ledge_ai_source_metadata() {
+        let source = LedgeAi;
+        assert_eq!(source.id(), "ledge_ai");
+        assert_eq!(source.listing_urls(), vec!["https://ledge.ai/"]);
+        assert_eq!(source.channel().title, "Ledge.ai 新着記事");
+        assert_eq!(source.sitemap_urls(), vec!["https://ledge.ai/sitemap.xml"]);
+        assert!(source.is_article_url("https://ledge.ai/articles/grok4"));
+        assert!(!source.is_article_url("https://ledge.ai/categories/business"));
+    }
+
+    #[test]
+    fn test_ledge_ai_source_parses_listing() {
+        let html = r#"<html><body><a href="/articles/test1">Test Article 1</a></body></html>"#;
+        let articles = LedgeAi.parse_listing(html, LEDGE_AI_BASE_URL).unwrap();
+        assert_eq!(articles.len(), 1);
+        assert_eq!(articles[0].url, "https://ledge.ai/articles/test1");
+    }
+
+    #[test]
+    fn test_ledge_ai_source_extracts_article() {
+        let body = "本文".repeat(200);
+        let html = format!(
+            r#"<html><head><meta property="article:published_time" content="2025-07-14T07:50:00.000Z"></head>
+            <body><script>window.__NUXT__={{"body":"{body}"}}</script></body></html>"#
+        );
+
+        let article = LedgeAi
+            .extract_article(&html, "https://ledge.ai/articles/a")
+            .unwrap();
+        assert_eq!(article.markdown, body);
+        assert_eq!(article.date.as_deref(), Some("2025-07-14T07:50:00.000Z"));
+    }
+
+    #[test]
+    fn test_sources_are_object_safe() {
+        let sources: Vec<Box<dyn Source>> = vec![Box::new(LedgeAi)];
+        assert_eq!(sources[0].id(), "ledge_ai");
+    }
+}
@@ -0,0 +1,551 @@
+use crate::article_diff::{diff_markdown, Revision, MAX_REVISIONS};
+use crate::error::Error;
+use crate::rss_item::RssItem;
+use chrono::{DateTime, Utc};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::{BTreeMap, HashSet};
+use std::fs;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tracing::warn;
+
+/// Every article seen so far, keyed by URL and persisted as JSON between runs
+pub struct StateStore {
+    path: PathBuf,
+    items: BTreeMap<String, StoredItem>,
+    last_digest: Option<DateTime<Utc>>,
+}
+
+#[derive(Clone, Serialize, Deserialize)]
+pub struct StoredItem {
+    #[serde(flatten)]
+    pub item: RssItem,
+    pub first_seen: DateTime<Utc>,
+    /// Imported by `backfill` or by the first run against an empty store;
+    /// never announced as a new article
+    #[serde(default)]
+    pub backfilled: bool,
+    /// SHA-256 of the body text, used to notice revisions
+    pub content_hash: String,
+    /// Paragraph diffs of past revisions, oldest first
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub revisions: Vec<Revision>,
+    /// Webhook URLs the article has not been delivered to yet
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub pending_webhooks: Vec<String>,
+}
+
+/// What recording an item revealed about the article
+#[derive(Debug, PartialEq)]
+pub enum Change {
+    New,
+    Unchanged,
+    /// The body text differs from the stored one; `ratio` is the share of it
+    /// that changed, from 0.0 to 1.0
+    Changed {
+        ratio: f64,
+    },
+}
+
+/// Lock file next to the state store (`state.json.lock`) holding the owner's
+/// process id, so two runs never write the same store. Released on drop; a
+/// lock left behind by a process that no longer runs is taken over.
+pub struct StateLock {
+    path: PathBuf,
+}
+
+#[derive(Default, Serialize, Deserialize)]
+struct StateFile {
+    items: Vec<StoredItem>,
+    /// When the last digest email was sent
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    last_digest: Option<DateTime<Utc>>,
+}
+
+impl StateStore {
+    /// Load the store, starting empty when the file does not exist yet
+    pub fn load(path: &Path) -> Result<Self, Error> {
+        let state: StateFile = match fs::read_to_string(path) {
+            Ok(text) => serde_json::from_str(&text).map_err(|e| Error::Corrupt {
+                path: path.to_path_buf(),
+                reason: e.to_string(),
+            })?,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => StateFile::default(),
+            Err(e) => return Err(Error::io(path)(e)),
+        };
+
+        Ok(Self {
+            path: path.to_path_buf(),
+            items: state
+                .items
+                .into_iter()
+                .map(|stored| (stored.item.link.clone(), stored))
+                .collect(),
+            last_digest: state.last_digest,
+        })
+    }
+
+    /// Write the store atomically, newest articles first
+    pub fn save(&self) -> Result<(), Error> {
+        let mut items: Vec<StoredItem> = self.items.values().cloned().collect();
+        items.sort_by_key(|stored| std::cmp::Reverse(stored.item.pub_date));
+        let json = serde_json::to_string_pretty(&StateFile {
+            items,
+            last_digest: self.last_digest,
+        })
+        .map_err(|e| Error::io(&self.path)(e.into()))?;
+
+        if let Some(parent) = self.path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(Error::io(parent))?;
+        }
+        let temp_path = self.path.with_extension("tmp");
+        fs::write(&temp_path, json).map_err(Error::io(&temp_path))?;
+        fs::rename(&temp_path, &self.path).map_err(Error::io(&self.path))?;
+        Ok(())
+    }
+
+    pub fn get(&self, link: &str) -> Option<&StoredItem> {
+        self.items.get(link)
+    }
+
+    /// Record an item, keeping `first_seen` and `backfilled` of a known one.
+    /// A changed body sets `item.updated` (when the page did not provide one)
+    /// and clears `resurfaced` until [`Self::mark_resurfaced`] is called for this
+    /// revision; an unchanged one inherits both from the previous record.
+    pub fn record(&mut self, item: &mut RssItem, now: DateTime<Utc>, backfilled: bool) -> Change {
+        let content_hash = content_hash(&item.description);
+
+        let Some(stored) = self.items.get_mut(&item.link) else {
+            self.items.insert(
+                item.link.clone(),
+                StoredItem {
+                    item: item.clone(),
+                    first_seen: now,
+                    backfilled,
+                    content_hash,
+                    revisions: Vec::new(),
+                    pending_webhooks: Vec::new(),
+                },
+            );
+            return Change::New;
+        };
+
+        let change = if stored.content_hash == content_hash {
+            item.updated = item.updated.or(stored.item.updated);
+            item.resurfaced = stored.item.resurfaced;
+            Change::Unchanged
+        } else {
+            item.updated = item.updated.or(Some(now));
+            item.resurfaced = false;
+            if let Some(revision) = diff_markdown(&stored.item.markdown, &item.markdown, now) {
+                stored.revisions.push(revision);
+                let excess = stored.revisions.len().saturating_sub(MAX_REVISIONS);
+                stored.revisions.drain(..excess);
+            }
+            Change::Changed {
+                ratio: change_ratio(&stored.item.description, &item.description),
+            }
+        };
+
+        stored.item = item.clone();
+        stored.content_hash = content_hash;
+        change
+    }
+
+    /// Remember that an article was announced again after a significant revision
+    pub fn mark_resurfaced(&mut self, link: &str) {
+        if let Some(stored) = self.items.get_mut(link) {
+            stored.item.resurfaced = true;
+        }
+    }
+
+    /// Queue an article for delivery to the given webhooks
+    pub fn add_pending_webhooks(&mut self, link: &str, urls: &[String]) {
+        if let Some(stored) = self.items.get_mut(link) {
+            for url in urls {
+                if !stored.pending_webhooks.contains(url) {
+                    stored.pending_webhooks.push(url.clone());
+                }
+            }
+        }
+    }
+
+    /// Webhook URLs an article still has to be delivered to
+    pub fn pending_webhooks(&self, link: &str) -> &[String] {
+        self.items
+            .get(link)
+            .map_or(&[], |stored| stored.pending_webhooks.as_slice())
+    }
+
+    pub fn mark_delivered(&mut self, link: &str, url: &str) {
+        if let Some(stored) = self.items.get_mut(link) {
+            stored.pending_webhooks.retain(|pending| pending != url);
+        }
+    }
+
+    /// Recorded revisions of an article, oldest first
+    pub fn revisions(&self, link: &str) -> &[Revision] {
+        self.items
+            .get(link)
+            .map_or(&[], |stored| stored.revisions.as_slice())
+    }
+
+    /// Every stored article, in no particular order
+    pub fn items(&self) -> impl Iterator<Item = &StoredItem> {
+        self.items.values()
+    }
+
+    pub fn last_digest(&self) -> Option<DateTime<Utc>> {
+        self.last_digest
+    }
+
+    pub fn set_last_digest(&mut self, at: DateTime<Utc>) {
+        self.last_digest = Some(at);
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+}
+
+impl StateLock {
+    pub fn acquire(state_path: &Path) -> Result<Self, Error> {
+        let mut path = state_path.as_os_str().to_owned();
+        path.push(".lock");
+        let path = PathBuf::from(path);
+        if let Some(parent) = path.parent().filter(|p| !p.as_os_str().is_empty()) {
+            fs::create_dir_all(parent).map_err(Error::io(parent))?;
+        }
+
+        for _ in 0..2 {
+            match fs::OpenOptions::new()
+                .write(true)
+                .create_new(true)
+                .open(&path)
+            {
+                Ok(mut file) => {
+                    write!(file, "{}", std::process::id()).map_err(Error::io(&path))?;
+                    return Ok(Self { path });
+                }
+                Err(e) if e.kind() == std::io::ErrorKind::AlreadyExists => {
+                    let owner = fs::read_to_string(&path).unwrap_or_default();
+                    if is_running(owner.trim()) {
+                        return Err(Error::Locked {
+                            path,
+                            pid: owner.trim().to_string(),
+                        });
+                    }
+                    warn!(lock = %path.display(), "Taking over stale lock");
+                    fs::remove_file(&path).map_err(Error::io(&path))?;
+                }
+                Err(e) => return Err(Error::io(&path)(e)),
+            }
+        }
+        Err(Error::io(&path)(std::io::ErrorKind::AlreadyExists.into()))
+    }
+}
+
+impl Drop for StateLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Whether the process is alive; assumed so where /proc is not available
+fn is_running(pid: &str) -> bool {
+    let Ok(pid) = pid.parse::<u32>() else {
+        return false;
+    };
+    pid == std::process::id()
+        || !Path::new("/proc/self").exists()
+        || Path::new(&format!("/proc/{pid}")).exists()
+}
+
+static TAG_PATTERN: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]+>").unwrap());
+
+/// Visible text of an article body, so markup-only changes (e.g. mirrored
+/// image URLs) do not count as revisions
+fn body_text(html: &str) -> String {
+    TAG_PATTERN
+        .replace_all(html, " ")
+        .split_whitespace()
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn content_hash(html: &str) -> String {
+    Sha256::digest(body_text(html).as_bytes())
+        .iter()
+        .map(|b| format!("{b:02x}"))
+        .collect()
+}
+
+/// Jaccard distance between the character bigrams of two bodies
+fn change_ratio(old_html: &str, new_html: &str) -> f64 {
+    let bigrams = |html: &str| -> HashSet<(char, char)> {
+        let chars: Vec<char> = body_text(html).chars().collect();
+        chars.windows(2).map(|pair| (pair[0], pair[1])).collect()
+    };
+    let old = bigrams(old_html);
+    let new = bigrams(new_html);
+
+    let union = old.union(&new).count();
+    if union == 0 {
+        return 0.0;
+    }
+    1.0 - old.intersection(&new).count() as f64 / union as f64
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::reading_stats::ReadingStats;
+    use crate::test_support::temp_dir;
+
+    fn item(link: &str, date: &str) -> RssItem {
+        RssItem {
+            title: format!("Title of {link}"),
+            link: link.to_string(),
+            pub_date: DateTime::parse_from_rfc3339(date)
+                .unwrap()
+                .with_timezone(&Utc),
+            reading_stats: Some(ReadingStats {
+                char_count: 1000,
+                word_count: 0,
+                minutes: 2,
+            }),
+            tags: vec!["生成AI".to_string()],
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_state_store_round_trip() {
+        let path = temp_dir("state-round-trip").join("state.json");
+        let now = Utc::now();
+
+        let mut store = StateStore::load(&path).unwrap();
+        assert_eq!(store.len(), 0);
+        let mut a = item("https://ledge.ai/articles/a", "2025-07-14T00:00:00Z");
+        let mut b = item("https://ledge.ai/articles/b", "2025-07-15T00:00:00Z");
+        assert_eq!(store.record(&mut a, now, false), Change::New);
+        assert_eq!(store.record(&mut b, now, true), Change::New);
+        store.save().unwrap();
+
+        let reloaded = StateStore::load(&path).unwrap();
+        assert_eq!(reloaded.len(), 2);
+        let stored = reloaded.get("https://ledge.ai/articles/b").unwrap();
+        assert!(stored.backfilled);
+        assert_eq!(stored.item.tags, vec!["生成AI"]);
+        assert_eq!(stored.item.reading_stats.unwrap().char_count, 1000);
+        assert!(
+            !reloaded
+                .get("https://ledge.ai/articles/a")
+                .unwrap()
+                .backfilled
+        );
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_state_store_record_keeps_first_seen() {
+        let path = temp_dir("state-record").join("state.json");
+        let first = DateTime::parse_from_rfc3339("2025-07-14T00:00:00Z")
+            .unwrap()
+            .with_timezone(&Utc);
+
+        let mut store = StateStore::load(&path).unwrap();
+        store.record(
+            &mut item("https://ledge.ai/articles/a", "2025-07-14T00:00:00Z"),
+            first,
+            true,
+        );
+        let mut renamed = item("https://ledge.ai/articles/a", "2025-07-14T00:00:00Z");
+        renamed.title = "Updated".to_string();
+        assert_eq!(
+            store.record(&mut renamed, Utc::now(), false),
+            Change::Unchanged
+        );
+        assert_eq!(renamed.updated, None);
+
+        let stored = store.get("https://ledge.ai/articles/a").unwrap();
+        assert_eq!(stored.item.title, "Updated");
+        assert_eq!(stored.first_seen, first);
+        assert!(stored.backfilled);
+    }
+
+    #[test]
+    fn test_state_store_keeps_pending_webhooks() {
+        let path = temp_dir("state-pending").join("state.json");
+        let link = "https://ledge.ai/articles/a";
+        let hooks = vec![
+            "https://hooks.example.com/a".to_string(),
+            "https://hooks.example.com/b".to_string(),
+        ];
+
+        let mut store = StateStore::load(&path).unwrap();
+        store.record(&mut item(link, "2025-07-14T00:00:00Z"), Utc::now(), false);
+        store.add_pending_webhooks(link, &hooks);
+        store.add_pending_webhooks(link, &hooks[..1]);
+        store.mark_delivered(link, &hooks[0]);
+        store.save().unwrap();
+
+        let mut reloaded = StateStore::load(&path).unwrap();
+        // Recording the article again keeps its pending deliveries
+        reloaded.record(&mut item(link, "2025-07-14T00:00:00Z"), Utc::now(), false);
+        assert_eq!(reloaded.pending_webhooks(link), &hooks[1..]);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_state_store_detects_body_changes() {
+        let path = temp_dir("state-changes").join("state.json");
+        let first = Utc::now() - chrono::Duration::days(3);
+        let now = Utc::now();
+        let with_body = |body: &str| RssItem {
+            description: body.to_string(),
+            ..item("https://ledge.ai/articles/a", "2025-07-14T00:00:00Z")
+        };
+
+        let mut store = StateStore::load(&path).unwrap();
+        store.record(
+            &mut with_body("<p>生成AIの導入事例を紹介します。</p>"),
+            first,
+            false,
+        );
+
+        // Markup-only changes are not revisions
+        let mut same_text = with_body(r#"<p class="x">生成AIの導入事例を紹介します。</p>"#);
+        assert_eq!(store.record(&mut same_text, now, false), Change::Unchanged);
+
+        let mut revised =
+            with_body("<p>生成AIの導入事例を紹介します。</p><p>追記：価格が発表された。</p>");
+        let Change::Changed { ratio } = store.record(&mut revised, now, false) else {
+            panic!("expected a change");
+        };
+        assert!(ratio > 0.2 && ratio < 1.0, "ratio was {ratio}");
+        assert_eq!(revised.updated, Some(now));
+
+        // The update date sticks on later runs without further changes
+        let mut again =
+            with_body("<p>生成AIの導入事例を紹介します。</p><p>追記：価格が発表された。</p>");
+        assert_eq!(
+            store.record(&mut again, now + chrono::Duration::hours(1), false),
+            Change::Unchanged
+        );
+        assert_eq!(again.updated, Some(now));
+
+        store.mark_resurfaced("https://ledge.ai/articles/a");
+        let mut resurfaced = again.clone();
+        store.record(&mut resurfaced, now, false);
+        assert!(resurfaced.resurfaced);
+
+        // A later small edit is a new revision that has not been re-surfaced
+        let later = now + chrono::Duration::days(1);
+        let mut small_edit =
+            with_body("<p>生成AIの導入事例を紹介します。</p><p>追記：価格が発表された！</p>");
+        assert!(matches!(
+            store.record(&mut small_edit, later, false),
+            Change::Changed { .. }
+        ));
+        assert!(!small_edit.resurfaced);
+        let mut unchanged = small_edit.clone();
+        store.record(&mut unchanged, later, false);
+        assert!(!unchanged.resurfaced);
+    }
+
+    #[test]
+    fn test_state_store_keeps_revision_diffs() {
+        let path = temp_dir("state-revisions").join("state.json");
+        let link = "https://ledge.ai/articles/a";
+        let with_markdown = |markdown: &str| RssItem {
+            description: format!("<p>{markdown}</p>"),
+            markdown: markdown.to_string(),
+            ..item(link, "2025-07-14T00:00:00Z")
+        };
+        let now = Utc::now();
+
+        let mut store = StateStore::load(&path).unwrap();
+        store.record(&mut with_markdown("導入。\n\n価格は未定。"), now, false);
+        assert!(store.revisions(link).is_empty());
+
+        store.record(&mut with_markdown("導入。\n\n価格は1万円。"), now, false);
+        store.save().unwrap();
+
+        let reloaded = StateStore::load(&path).unwrap();
+        let revisions = reloaded.revisions(link);
+        assert_eq!(revisions.len(), 1);
+        assert_eq!(revisions[0].inserted, vec!["価格は1万円。"]);
+        assert_eq!(revisions[0].removed, vec!["価格は未定。"]);
+        assert!(reloaded
+            .revisions("https://ledge.ai/articles/unknown")
+            .is_empty());
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_state_lock_excludes_second_run() {
+        let path = temp_dir("state-lock").join("state.json");
+        let lock_path = path.with_extension("json.lock");
+
+        let lock = StateLock::acquire(&path).unwrap();
+        assert!(lock_path.exists());
+        assert!(matches!(
+            StateLock::acquire(&path),
+            Err(Error::Locked { .. })
+        ));
+        drop(lock);
+        assert!(!lock_path.exists());
+
+        // A lock whose owner is gone is taken over
+        fs::write(&lock_path, "not-a-pid").unwrap();
+        let lock = StateLock::acquire(&path).unwrap();
+        assert_eq!(
+            fs::read_to_string(&lock_path).unwrap(),
+            std::process::id().to_string()
+        );
+        drop(lock);
+
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_change_ratio() {
+        assert_eq!(change_ratio("<p>同じ本文</p>", "<p>同じ本文</p>"), 0.0);
+        assert_eq!(change_ratio("<p>abc</p>", "<p>xyz</p>"), 1.0);
+        assert_eq!(change_ratio("", ""), 0.0);
+    }
+
+    #[test]
+    fn test_state_store_rejects_corrupt_file() {
+        let path = temp_dir("state-corrupt").join("state.json");
+        fs::create_dir_all(path.parent().unwrap()).unwrap();
+        fs::write(&path, "not json").unwrap();
+
+        assert!(matches!(
+            StateStore::load(&path),
+            Err(Error::Corrupt { .. })
+        ));
+        let _ = fs::remove_dir_all(path.parent().unwrap());
+    }
+
+    #[test]
+    fn test_state_store_save_failure_is_an_output_error() {
+        let dir = temp_dir("state-unwritable");
+        fs::create_dir_all(&dir).unwrap();
+        let state = StateStore::load(&dir.join("sub").join("state.json")).unwrap();
+        // The state's parent directory is taken by a regular file
+        fs::write(dir.join("sub"), "").unwrap();
+
+        let error = state.save().unwrap_err();
+        assert!(matches!(&error, Error::Io { path, .. } if path == &dir.join("sub")));
+        assert_eq!(error.exit_code(), crate::error::EXIT_OUTPUT);
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,234 @@
+use crate::article_extractor::{preprocess_markdown_content, strip_markdown};
+use std::collections::HashMap;
+
+// Articles with at least this many sentences get a three-sentence summary
+const LONG_ARTICLE_SENTENCES: usize = 8;
+// Extra weight for sentences near the top; news articles lead with the gist
+const LEAD_BIAS: f64 = 0.5;
+const MIN_SENTENCE_CHARS: usize = 10;
+const MAX_SENTENCE_CHARS: usize = 200;
+
+/// Build a 2-3 sentence extractive summary of an article's markdown.
+/// Sentences are scored by the frequency of the terms they contain, with a
+/// bias toward the lead, and returned in their original order.
+pub fn summarize(markdown: &str) -> Option<String> {
+    let sentences = split_sentences(&body_text(markdown));
+    let candidates: Vec<(usize, &String)> = sentences
+        .iter()
+        .enumerate()
+        .filter(|(_, sentence)| {
+            let len = sentence.chars().count();
+            (MIN_SENTENCE_CHARS..=MAX_SENTENCE_CHARS).contains(&len)
+        })
+        .collect();
+    if candidates.is_empty() {
+        return None;
+    }
+
+    let mut frequencies: HashMap<String, usize> = HashMap::new();
+    for (_, sentence) in &candidates {
+        for term in terms(sentence) {
+            *frequencies.entry(term).or_insert(0) += 1;
+        }
+    }
+
+    let mut scored: Vec<(usize, f64)> = candidates
+        .iter()
+        .map(|(index, sentence)| {
+            let sentence_terms = terms(sentence);
+            let tf: f64 = sentence_terms
+                .iter()
+                .map(|term| frequencies[term] as f64)
+                .sum::<f64>()
+                / sentence_terms.len().max(1) as f64;
+            (*index, tf * (1.0 + LEAD_BIAS / (1.0 + *index as f64)))
+        })
+        .collect();
+    scored.sort_by(|a, b| b.1.partial_cmp(&a.1).unwrap_or(std::cmp::Ordering::Equal));
+
+    let count = if sentences.len() >= LONG_ARTICLE_SENTENCES {
+        3
+    } else {
+        2
+    };
+    let mut selected: Vec<usize> = scored.iter().take(count).map(|(i, _)| *i).collect();
+    selected.sort_unstable();
+
+    Some(
+        selected
+            .iter()
+            .map(|i| sentences[*i].as_str())
+            .collect::<Vec<_>>()
+            .join(""),
+    )
+}
+
+/// Visible text of the article without headings, tables and filtered blocks
+fn body_text(markdown: &str) -> String {
+    let preprocessed = preprocess_markdown_content(markdown);
+    let lines: Vec<&str> = preprocessed
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.starts_with('#') && !line.starts_with('|'))
+        .collect();
+    strip_markdown(&lines.join("\n"))
+}
+
+/// Split text into sentences on Japanese and English sentence terminators,
+/// treating line breaks as boundaries too
+fn split_sentences(text: &str) -> Vec<String> {
+    let mut sentences = Vec::new();
+    let mut current = String::new();
+    let mut chars = text.chars().peekable();
+
+    while let Some(c) = chars.next() {
+        let boundary = match c {
+            '。' | '！' | '？' => {
+                current.push(c);
+                // Keep closing brackets with the sentence they end, e.g. 「…。」
+                while let Some(&next) = chars.peek() {
+                    if matches!(next, '」' | '』' | '）' | ')') {
+                        current.push(next);
+                        chars.next();
+                    } else {
+                        break;
+                    }
+                }
+                true
+            }
+            '.' | '!' | '?' => {
+                current.push(c);
+                chars.peek().map_or(true, |next| next.is_whitespace())
+            }
+            '\n' => true,
+            _ => {
+                current.push(c);
+                false
+            }
+        };
+
+        if boundary {
+            let sentence = current.trim().to_string();
+            if !sentence.is_empty() {
+                sentences.push(sentence);
+            }
+            current.clear();
+        }
+    }
+
+    let sentence = current.trim().to_string();
+    if !sentence.is_empty() {
+        sentences.push(sentence);
+    }
+    sentences
+}
+
+/// Terms used for scoring: lowercase Latin words and bigrams of Japanese text.
+/// Bigrams made only of hiragana are mostly particles and inflections, so they
+/// are skipped.
+fn terms(sentence: &str) -> Vec<String> {
+    let mut result = Vec::new();
+    let mut word = String::new();
+    let mut previous: Option<char> = None;
+
+    for c in sentence.chars() {
+        if c.is_ascii_alphanumeric() {
+            word.push(c.to_ascii_lowercase());
+            previous = None;
+            continue;
+        }
+        if word.len() > 1 {
+            result.push(std::mem::take(&mut word));
+        }
+        word.clear();
+
+        if is_cjk(c) {
+            if let Some(p) = previous {
+                if !(is_hiragana(p) && is_hiragana(c)) {
+                    result.push(format!("{p}{c}"));
+                }
+            }
+            previous = Some(c);
+        } else {
+            previous = None;
+        }
+    }
+    if word.len() > 1 {
+        result.push(word);
+    }
+
+    result
+}
+
+fn is_hiragana(c: char) -> bool {
+    ('\u{3040}'..='\u{309F}').contains(&c)
+}
+
+fn is_cjk(c: char) -> bool {
+    is_hiragana(c)
+        || ('\u{30A0}'..='\u{30FF}').contains(&c)
+        || ('\u{4E00}'..='\u{9FFF}').contains(&c)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_split_sentences() {
+        let text = "OpenAIは新モデルを発表した。性能は大幅に向上！「価格は据え置きだ。」本当か？\nThis is English. Version 1.5 works.";
+        assert_eq!(
+            split_sentences(text),
+            vec![
+                "OpenAIは新モデルを発表した。",
+                "性能は大幅に向上！",
+                "「価格は据え置きだ。」",
+                "本当か？",
+                "This is English.",
+                "Version 1.5 works.",
+            ]
+        );
+    }
+
+    #[test]
+    fn test_terms_skip_hiragana_only_bigrams() {
+        assert_eq!(terms("AIを使う"), vec!["ai", "を使", "使う"]);
+    }
+
+    #[test]
+    fn test_summarize_prefers_central_sentences_in_original_order() {
+        let markdown = "\
+## 概要
+
+米OpenAIは新しい言語モデルGPT-5を発表した。
+GPT-5は推論性能が大きく向上した言語モデルである。
+会場には多くの記者が集まり、天候にも恵まれた。
+同社によると言語モデルの推論コストも半減したという。
+昼食には弁当が配られた。";
+
+        let summary = summarize(markdown).unwrap();
+        assert_eq!(
+            summary,
+            "米OpenAIは新しい言語モデルGPT-5を発表した。GPT-5は推論性能が大きく向上した言語モデルである。"
+        );
+    }
+
+    #[test]
+    fn test_summarize_long_article_uses_three_sentences() {
+        let markdown = (1..=10)
+            .map(|i| format!("生成AIの活用事例その{i}を紹介する。"))
+            .collect::<Vec<_>>()
+            .join("");
+        let summary = summarize(&markdown).unwrap();
+        assert_eq!(summary.matches('。').count(), 3);
+    }
+
+    #[test]
+    fn test_summarize_ignores_filtered_blocks_and_short_text() {
+        assert_eq!(
+            summarize(":::box\n関連記事：生成AIプラットフォームの解説記事\n:::"),
+            None
+        );
+        assert_eq!(summarize("短い。"), None);
+    }
+}
@@ -0,0 +1,51 @@
+use crate::error::{Error, Stage};
+use crate::http_client::HttpClient;
+
+/// Tell the hub that the feed at `topic` changed (WebSub "publish" ping), so
+/// it fetches the feed and pushes it to subscribers
+pub async fn publish(client: &HttpClient, hub: &str, topic: &str) -> Result<(), Error> {
+    client
+        .post_form(
+            hub,
+            &[("hub.mode", "publish"), ("hub.url", topic)],
+            Stage::WebSub,
+        )
+        .await
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::record_requests;
+
+    #[tokio::test]
+    async fn test_publish_posts_form_to_hub() {
+        let (hub, requests) = record_requests("204 No Content").await;
+
+        publish(
+            &HttpClient::new(),
+            &format!("{hub}/hub"),
+            "https://example.com/rss.xml",
+        )
+        .await
+        .unwrap();
+
+        let requests = requests.lock().unwrap();
+        assert_eq!(requests.len(), 1);
+        assert!(requests[0].starts_with("POST /hub HTTP/1.1"));
+        assert!(requests[0]
+            .to_lowercase()
+            .contains("content-type: application/x-www-form-urlencoded"));
+        assert!(
+            requests[0].ends_with("hub.mode=publish&hub.url=https%3A%2F%2Fexample.com%2Frss.xml")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_publish_reports_hub_errors() {
+        let (hub, _) = record_requests("500 Internal Server Error").await;
+
+        let result = publish(&HttpClient::new(), &hub, "https://example.com/rss.xml").await;
+        assert!(result.is_err());
+    }
+}